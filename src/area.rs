@@ -28,8 +28,22 @@ use {
 ///
 /// **NB:**
 ///   - The top-left anchor can be any valid `(U, U)` coordinate, positive or negative, in any
-///   quadrant.
+///     quadrant.
 ///   - The width and height must both be positive and nonzero.
+///   - The right and bottom edges (the anchor plus the width or height) must fit in `U`. So a
+///     region can't extend past `U::max_value() - 1`.
+///
+/// Regions are half-open: a region covers the coordinates from its anchor up to, but not including,
+/// its right and bottom edges, i.e. `[x, x + width) × [y, y + height)`. So two regions which share
+/// an edge don't overlap, a point on the boundary between them belongs to exactly one of them, and
+/// when a node of the tree is subdivided, each of its coordinates lands in exactly one subquadrant.
+/// Every comparison here ([`.contains()`], [`.contains_pt()`], [`.intersects()`]) follows this
+/// convention, and so do queries: a query region doesn't find regions which only touch its
+/// right or bottom edge.
+///
+/// [`.contains()`]: #method.contains
+/// [`.contains_pt()`]: #method.contains_pt
+/// [`.intersects()`]: #method.intersects
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Builder)]
 #[builder(build_fn(validate = "Self::validate"))]
@@ -55,6 +69,16 @@ where
                 return Err("Areas may not have nonpositive heights.".to_string());
             }
         }
+        // The right and bottom edges must be representable, or every comparison against them
+        // would wrap around.
+        if let Some(anchor) = self.anchor {
+            let (w, h) = self.dimensions.unwrap_or((U::one(), U::one()));
+            if anchor.x().checked_add(&w).is_none() || anchor.y().checked_add(&h).is_none() {
+                return Err(
+                    "Areas may not extend past the bounds of the coordinate type.".to_string(),
+                );
+            }
+        }
         Ok(())
     }
 }
@@ -143,6 +167,40 @@ where
             && self.bottom_edge() > other.top_edge()
     }
 
+    /// Whether or not an area shares part of an edge with another area, without overlapping it.
+    ///
+    /// The shared part must have positive length, so two areas which only meet at a corner don't
+    /// touch. (Neither do overlapping areas; see [`.intersects()`].)
+    /// ```
+    /// use quadtree_rs::area::AreaBuilder;
+    ///
+    /// let area = |x, y| AreaBuilder::<u8>::default()
+    ///     .anchor((x, y).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    ///
+    /// assert!(area(0, 0).touches(area(2, 1)));
+    /// // Only a corner.
+    /// assert!(!area(0, 0).touches(area(2, 2)));
+    /// // Overlapping.
+    /// assert!(!area(0, 0).touches(area(1, 1)));
+    /// ```
+    ///
+    /// [`.intersects()`]: #method.intersects
+    pub fn touches(self, other: Self) -> bool {
+        let x_overlap =
+            self.left_edge() < other.right_edge() && other.left_edge() < self.right_edge();
+        let y_overlap =
+            self.top_edge() < other.bottom_edge() && other.top_edge() < self.bottom_edge();
+        let side_by_side = (self.right_edge() == other.left_edge()
+            || other.right_edge() == self.left_edge())
+            && y_overlap;
+        let stacked = (self.bottom_edge() == other.top_edge()
+            || other.bottom_edge() == self.top_edge())
+            && x_overlap;
+        side_by_side || stacked
+    }
+
     /// Whether or not an area wholly contains another area.
     pub fn contains(self, other: Self) -> bool {
         other.right_edge() <= self.right_edge()
@@ -153,18 +211,40 @@ where
 
     /// Whether or not an area contains a point.
     pub fn contains_pt(self, pt: point::Point<U>) -> bool {
-        self.contains(
-            AreaBuilder::default()
-                .anchor(pt)
-                .dimensions((U::one(), U::one()))
-                .build()
-                .expect("Unexpected error in Area::contains_pt."),
-        )
+        // Compared directly, rather than as a 1x1 area, since no area can be anchored at the
+        // largest coordinate.
+        self.left_edge() <= pt.x()
+            && pt.x() < self.right_edge()
+            && self.top_edge() <= pt.y()
+            && pt.y() < self.bottom_edge()
+    }
+
+    /// The top-left corner of the region. Alias for [`.anchor()`].
+    ///
+    /// [`.anchor()`]: #method.anchor
+    pub fn top_left(&self) -> point::Point<U> {
+        self.anchor()
+    }
+
+    /// The bottom-right corner of the region, i.e. `(right_edge, bottom_edge)`.
+    ///
+    /// Like the right and bottom edges, this lies just outside the region: a 2x2 region at (0,0)
+    /// has its bottom-right corner at (2,2).
+    pub fn bottom_right(&self) -> point::Point<U> {
+        point::Point {
+            x: self.right_edge(),
+            y: self.bottom_edge(),
+        }
     }
 
-    // NB: The center point is an integer and thus rounded, i.e. a 2x2 region at (0,0) has a center
-    // at (0,0), when in reality the center would be at (0.5, 0.5).
-    pub(crate) fn center_pt(&self) -> point::Point<U> {
+    /// The center of the region.
+    ///
+    /// **NB:** The center point is an integer and thus rounded down, i.e. a 3x3 region at (0,0) has
+    /// a center at (1,1), when in reality the center would be at (1.5, 1.5).
+    ///
+    /// Found as `anchor + dimensions / 2` rather than by averaging the edges, so that it never
+    /// overflows, even for regions which reach the largest coordinate `U` can hold.
+    pub fn center(&self) -> point::Point<U> {
         self.anchor()
             + point::Point {
                 x: self.width() / Self::two(),
@@ -172,12 +252,304 @@ where
             }
     }
 
+    /// The region where this area and another overlap, or `None` if they don't.
+    ///
+    /// Areas which only share an edge or a corner don't overlap (see [`.intersects()`]), since
+    /// their intersection would have zero area.
+    ///
+    /// ```
+    /// use quadtree_rs::area::AreaBuilder;
+    ///
+    /// let a = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((4, 3))
+    ///     .build().unwrap();
+    /// let b = AreaBuilder::default()
+    ///     .anchor((2, 1).into())
+    ///     .dimensions((5, 5))
+    ///     .build().unwrap();
+    ///
+    /// let overlap = a.intersection(b).unwrap();
+    /// assert_eq!(overlap.anchor(), (2, 1).into());
+    /// assert_eq!((overlap.width(), overlap.height()), (2, 2));
+    ///
+    /// // Just touching.
+    /// let c = AreaBuilder::default()
+    ///     .anchor((4, 0).into())
+    ///     .build().unwrap();
+    /// assert!(a.intersection(c).is_none());
+    /// ```
+    ///
+    /// [`.intersects()`]: #method.intersects
+    pub fn intersection(&self, other: Self) -> Option<Self> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let left = self.left_edge().max(other.left_edge());
+        let top = self.top_edge().max(other.top_edge());
+        let right = self.right_edge().min(other.right_edge());
+        let bottom = self.bottom_edge().min(other.bottom_edge());
+        AreaBuilder::default()
+            .anchor(point::Point { x: left, y: top })
+            .dimensions((right - left, bottom - top))
+            .build()
+            .ok()
+    }
+
+    /// The smallest region which contains both this area and another.
+    ///
+    /// ```
+    /// use quadtree_rs::area::AreaBuilder;
+    ///
+    /// let a = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// let b = AreaBuilder::default()
+    ///     .anchor((5, 1).into())
+    ///     .dimensions((1, 4))
+    ///     .build().unwrap();
+    ///
+    /// let both = a.union(b);
+    /// assert_eq!(both.anchor(), (0, 0).into());
+    /// assert_eq!(both.bottom_right(), (6, 5).into());
+    /// ```
+    pub fn union(&self, other: Self) -> Self {
+        let left = self.left_edge().min(other.left_edge());
+        let top = self.top_edge().min(other.top_edge());
+        let right = self.right_edge().max(other.right_edge());
+        let bottom = self.bottom_edge().max(other.bottom_edge());
+        AreaBuilder::default()
+            .anchor(point::Point { x: left, y: top })
+            .dimensions((right - left, bottom - top))
+            .build()
+            .expect("Unexpected error in Area::union.")
+    }
+
+    /// This region with its width and height multiplied by `factor`, about the same center.
+    ///
+    /// Factors below one shrink it and factors above one grow it. Each new dimension is rounded to
+    /// the nearest integer, but is never less than one, so a factor of zero leaves the single cell
+    /// at the middle. When a dimension changes by an odd amount, the region can't stay exactly
+    /// centered, and it moves half a cell left or up instead, rounding down as [`.center()`] does:
+    /// a 3x3 region scaled by 2 becomes 6x6, reaching 2 cells further left and up but only 1
+    /// further right and down, and a 5x5 region scaled by 0.4 becomes the 2x2 one 1 cell in from
+    /// its top-left. A region which would reach past the coordinates `U` can hold is clipped to
+    /// them.
+    ///
+    /// ```
+    /// use quadtree_rs::area::AreaBuilder;
+    ///
+    /// let a = AreaBuilder::default()
+    ///     .anchor((4, 4).into())
+    ///     .dimensions((4, 3))
+    ///     .build().unwrap();
+    ///
+    /// let zoomed_in = a.scaled(0.5);
+    /// assert_eq!(zoomed_in.anchor(), (5, 4).into());
+    /// assert_eq!((zoomed_in.width(), zoomed_in.height()), (2, 2));
+    ///
+    /// let zoomed_out = a.scaled(2.0);
+    /// assert_eq!(zoomed_out.anchor(), (2, 2).into());
+    /// assert_eq!((zoomed_out.width(), zoomed_out.height()), (8, 6));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `factor` is negative or not finite.
+    ///
+    /// [`.center()`]: #method.center
+    pub fn scaled(&self, factor: f64) -> Self {
+        assert!(
+            factor.is_finite() && factor >= 0.0,
+            "Areas can only be scaled by a finite, nonnegative factor."
+        );
+        let (left, width) = Self::scaled_span(self.left_edge(), self.width(), factor);
+        let (top, height) = Self::scaled_span(self.top_edge(), self.height(), factor);
+        AreaBuilder::default()
+            .anchor(point::Point { x: left, y: top })
+            .dimensions((width, height))
+            .build()
+            .expect("Unexpected error in Area::scaled.")
+    }
+
+    // The region shifted by @by. The caller must check that its edges won't overflow U.
+    pub(crate) fn translated(&self, by: point::Point<U>) -> Self {
+        Area {
+            anchor: self.anchor + by,
+            dimensions: self.dimensions,
+        }
+    }
+
+    // The point within the region which is closest to @pt. If @pt is inside the region, this is
+    // @pt itself.
+    pub(crate) fn closest_pt(&self, pt: point::Point<U>) -> point::Point<U> {
+        point::Point {
+            x: pt
+                .x()
+                .max(self.left_edge())
+                .min(self.right_edge() - U::one()),
+            y: pt
+                .y()
+                .max(self.top_edge())
+                .min(self.bottom_edge() - U::one()),
+        }
+    }
+
+    // The squared Euclidean distance from @pt to the closest point of the region. This is computed
+    // in f64 (via offset()) so that coordinates near the bounds of U can't overflow.
+    pub(crate) fn distance_sq(&self, pt: point::Point<U>) -> f64 {
+        let closest = self.closest_pt(pt);
+        let dx = offset(pt.x(), closest.x());
+        let dy = offset(pt.y(), closest.y());
+        dx * dx + dy * dy
+    }
+
+    // The squared Euclidean distance from @pt to the furthest point of the region, computed in
+    // f64 as for distance_sq().
+    pub(crate) fn farthest_distance_sq(&self, pt: point::Point<U>) -> f64 {
+        let axis = |p: U, lo: U, hi: U| offset(p, lo).abs().max(offset(p, hi - U::one()).abs());
+        let dx = axis(pt.x(), self.left_edge(), self.right_edge());
+        let dy = axis(pt.y(), self.top_edge(), self.bottom_edge());
+        dx * dx + dy * dy
+    }
+
+    // How far a ray from the center of the cell at @origin, heading in @dir, travels before it
+    // enters the region, in multiples of @dir. Zero if @origin is inside the region, and None if the
+    // ray never enters it (including rays which only graze a corner).
+    pub(crate) fn ray_entry(&self, origin: point::Point<U>, dir: (i32, i32)) -> Option<f64> {
+        // The range of distances over which the ray is between @lo and @hi along one axis.
+        // Measured from the center of the cell at @o, via offset(), so that nearby edges stay
+        // exact however large the coordinates are.
+        let slab = |o: U, d: i32, lo: U, hi: U| -> Option<(f64, f64)> {
+            let (lo, hi) = (offset(o, lo) - 0.5, offset(o, hi) - 0.5);
+            if d == 0 {
+                return if lo <= 0.0 && 0.0 < hi {
+                    Some((f64::NEG_INFINITY, f64::INFINITY))
+                } else {
+                    None
+                };
+            }
+            let (t1, t2) = (lo / f64::from(d), hi / f64::from(d));
+            Some((t1.min(t2), t1.max(t2)))
+        };
+        let (x_enter, x_exit) = slab(origin.x(), dir.0, self.left_edge(), self.right_edge())?;
+        let (y_enter, y_exit) = slab(origin.y(), dir.1, self.top_edge(), self.bottom_edge())?;
+        let enter = x_enter.max(y_enter).max(0.0);
+        if enter < x_exit.min(y_exit) {
+            Some(enter)
+        } else {
+            None
+        }
+    }
+
+    // The area of the overlap between the region and the simple polygon @verts, found by clipping
+    // the polygon to each of the region's edges in turn (Sutherland-Hodgman). The region is convex,
+    // so this is exact for concave polygons too: clipping one can leave edges doubling back along
+    // the region's border, but those enclose no area.
+    pub(crate) fn polygon_overlap(&self, verts: &[[f64; 2]]) -> f64 {
+        let edges = [
+            (0, self.left_edge().to_f64().unwrap(), true),
+            (0, self.right_edge().to_f64().unwrap(), false),
+            (1, self.top_edge().to_f64().unwrap(), true),
+            (1, self.bottom_edge().to_f64().unwrap(), false),
+        ];
+        let mut poly = verts.to_vec();
+        for &(axis, bound, keep_above) in edges.iter() {
+            let inside = |p: &[f64; 2]| {
+                if keep_above {
+                    p[axis] >= bound
+                } else {
+                    p[axis] <= bound
+                }
+            };
+            // Where the segment from @a to @b crosses the edge. The coordinate along @axis is set
+            // exactly, so that slivers left along an edge have no area at all.
+            let crossing = |a: &[f64; 2], b: &[f64; 2]| {
+                let t = (bound - a[axis]) / (b[axis] - a[axis]);
+                let mut q = [0.0; 2];
+                q[axis] = bound;
+                q[1 - axis] = a[1 - axis] + t * (b[1 - axis] - a[1 - axis]);
+                q
+            };
+            let mut clipped = Vec::with_capacity(poly.len() + 1);
+            for (i, cur) in poly.iter().enumerate() {
+                let prev = &poly[(i + poly.len() - 1) % poly.len()];
+                match (inside(prev), inside(cur)) {
+                    (true, true) => clipped.push(*cur),
+                    (false, true) => {
+                        clipped.push(crossing(prev, cur));
+                        clipped.push(*cur);
+                    }
+                    (true, false) => clipped.push(crossing(prev, cur)),
+                    (false, false) => {}
+                }
+            }
+            if clipped.is_empty() {
+                return 0.0;
+            }
+            poly = clipped;
+        }
+        // The shoelace formula, relative to the first vertex so that points sharing a coordinate
+        // with it cancel exactly.
+        let o = poly[0];
+        let twice_area: f64 = poly
+            .iter()
+            .zip(poly.iter().skip(1))
+            .map(|(p, q)| (p[0] - o[0]) * (q[1] - o[1]) - (q[0] - o[0]) * (p[1] - o[1]))
+            .sum();
+        twice_area.abs() / 2.0
+    }
+
     pub(crate) fn dimensions(&self) -> (U, U) {
         self.dimensions
     }
 
+    // The start and length of the span [@start, @start + @len) scaled by @factor about its middle,
+    // for .scaled().
+    fn scaled_span(start: U, len: U, factor: f64) -> (U, U) {
+        let old = len.to_f64().unwrap();
+        let new = (old * factor).round().max(1.0);
+        if let Some(new_len) = U::from(new).filter(|&new_len| new_len <= len) {
+            // Shrinking is exact in U, and rounds the start down.
+            return (start + (len - new_len) / Self::two(), new_len);
+        }
+        // Growing reaches past the start by the larger half of the difference, and past the end by
+        // the smaller, saturating at the bounds of U.
+        let grow = new - old;
+        let new_start = U::from((grow / 2.0).ceil())
+            .and_then(|before| start.checked_sub(&before))
+            .unwrap_or_else(U::min_value);
+        let new_end = U::from((grow / 2.0).floor())
+            .and_then(|after| (start + len).checked_add(&after))
+            .unwrap_or_else(U::max_value);
+        // A signed span from near U::min_value() to near U::max_value() is wider than U can say.
+        let new_len = new_end.checked_sub(&new_start).unwrap_or_else(U::max_value);
+        (new_start, new_len)
+    }
+
     // Strongly-typed alias for U::one() + U::One()
     fn two() -> U {
         U::one() + U::one()
     }
 }
+
+// The signed distance from @from to @to along one axis, in f64. The difference is taken in U
+// wherever it fits, so that it stays exact for nearby coordinates however large they are;
+// converting each coordinate first would lose the low bits of values past 2^53. Only a difference
+// too wide for U (between coordinates of opposite signs) is taken in f64.
+pub(crate) fn offset<U>(from: U, to: U) -> f64
+where
+    U: PrimInt,
+{
+    let (lo, hi) = if from <= to { (from, to) } else { (to, from) };
+    let magnitude = match hi.checked_sub(&lo) {
+        Some(difference) => difference.to_f64().unwrap(),
+        None => hi.to_f64().unwrap() - lo.to_f64().unwrap(),
+    };
+    if from <= to {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A builder for configuring a [`Quadtree`].
+//!
+//! [`Quadtree`]: ../struct.Quadtree.html
+
+use {
+    crate::{
+        area::AreaBuilder, eviction::EvictionPolicy, point::Point, qtinner::QTInner, Quadtree,
+    },
+    num::PrimInt,
+    std::{collections::HashMap, default::Default, marker::PhantomData},
+};
+
+/// Configures and builds an empty [`Quadtree<U, V>`], as an alternative to its constructors.
+///
+/// Only the depth must be set. Everything else defaults to what [`.new_with_anchor()`] uses: an
+/// anchor at (0, 0), a node capacity of zero (see [`.with_capacity()`]), a tree which isn't
+/// growable (see [`.new_growable()`]), and no maximum number of entries (see
+/// [`.with_max_entries()`]).
+/// ```
+/// use quadtree_rs::{builder::QuadtreeBuilder, point::Point, Quadtree};
+///
+/// let qt: Quadtree<i32, char> = QuadtreeBuilder::default()
+///     .anchor(Point {x: -8, y: -8})
+///     .depth(4)
+///     .node_capacity(2)
+///     .build().unwrap();
+/// assert_eq!(qt.anchor(), Point {x: -8, y: -8});
+/// assert_eq!(qt.width(), 16);
+///
+/// // Unlike the constructors, a tree which wouldn't fit in U is an error rather than a panic.
+/// assert!(QuadtreeBuilder::<u8, char>::default().depth(8).build().is_err());
+/// assert!(QuadtreeBuilder::<u8, char>::default().build().is_err());
+/// ```
+///
+/// [`Quadtree<U, V>`]: ../struct.Quadtree.html
+/// [`.new_with_anchor()`]: ../struct.Quadtree.html#method.new_with_anchor
+/// [`.with_capacity()`]: ../struct.Quadtree.html#method.with_capacity
+/// [`.new_growable()`]: ../struct.Quadtree.html#method.new_growable
+/// [`.with_max_entries()`]: ../struct.Quadtree.html#method.with_max_entries
+#[derive(Debug, Clone, Copy)]
+pub struct QuadtreeBuilder<U, V>
+where
+    U: PrimInt + Default,
+{
+    anchor: Point<U>,
+    depth: Option<usize>,
+    node_capacity: usize,
+    growable: bool,
+    max_entries: Option<(usize, EvictionPolicy)>,
+    // Quadtrees of any V can be built from the same settings.
+    phantom: PhantomData<fn() -> V>,
+}
+
+impl<U, V> Default for QuadtreeBuilder<U, V>
+where
+    U: PrimInt + Default,
+{
+    fn default() -> Self {
+        QuadtreeBuilder {
+            anchor: Point {
+                x: U::zero(),
+                y: U::zero(),
+            },
+            depth: None,
+            node_capacity: 0,
+            growable: false,
+            max_entries: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<U, V> QuadtreeBuilder<U, V>
+where
+    U: PrimInt + Default,
+{
+    /// The top-left corner of the region the quadtree represents.
+    pub fn anchor(&mut self, anchor: Point<U>) -> &mut Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// The depth of the quadtree, which is `2^depth` wide and high.
+    pub fn depth(&mut self, depth: usize) -> &mut Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// How many regions a node holds before it subdivides. See [`.with_capacity()`].
+    ///
+    /// [`.with_capacity()`]: ../struct.Quadtree.html#method.with_capacity
+    pub fn node_capacity(&mut self, node_capacity: usize) -> &mut Self {
+        self.node_capacity = node_capacity;
+        self
+    }
+
+    /// Whether the quadtree grows to hold regions inserted outside it. See [`.new_growable()`].
+    ///
+    /// [`.new_growable()`]: ../struct.Quadtree.html#method.new_growable
+    pub fn growable(&mut self, growable: bool) -> &mut Self {
+        self.growable = growable;
+        self
+    }
+
+    /// The most entries the quadtree holds at once, and which to evict to make room for another.
+    /// See [`.with_max_entries()`].
+    ///
+    /// [`.with_max_entries()`]: ../struct.Quadtree.html#method.with_max_entries
+    pub fn max_entries(&mut self, max_entries: usize, policy: EvictionPolicy) -> &mut Self {
+        self.max_entries = Some((max_entries, policy));
+        self
+    }
+
+    /// Builds the empty quadtree, or returns why it can't be: either the depth wasn't set, a
+    /// region `2^depth` on a side wouldn't fit in `U` from the anchor, or the maximum number of
+    /// entries is zero.
+    pub fn build(&self) -> Result<Quadtree<U, V>, String> {
+        let depth = self
+            .depth
+            .ok_or_else(|| "A quadtree's depth must be set.".to_string())?;
+        if depth >= U::max_value().count_ones() as usize {
+            return Err("A quadtree's depth must be less than the bit width of U.".to_string());
+        }
+        if let Some((0, _)) = self.max_entries {
+            return Err("A quadtree must be able to hold an entry.".to_string());
+        }
+        let side = U::one() << depth;
+        AreaBuilder::default()
+            .anchor(self.anchor)
+            .dimensions((side, side))
+            .build()?;
+        Ok(Quadtree {
+            inner: QTInner::new_with_capacity(self.anchor, depth, self.node_capacity),
+            store: HashMap::new(),
+            growable: self.growable,
+            max_entries: self.max_entries,
+        })
+    }
+}
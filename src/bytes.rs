@@ -0,0 +1,252 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A compact binary encoding for a [`Quadtree`], for when `serde` is not an option.
+//!
+//! See [`.to_bytes()`] and [`.from_bytes()`]. Coordinates and values are converted to and from
+//! bytes by the [`Codec`] trait, which is implemented for the primitive types and `String`.
+//!
+//! [`Quadtree`]: ../struct.Quadtree.html
+//! [`.to_bytes()`]: ../struct.Quadtree.html#method.to_bytes
+//! [`.from_bytes()`]: ../struct.Quadtree.html#method.from_bytes
+//! [`Codec`]: trait.Codec.html
+
+use {
+    crate::eviction::EvictionPolicy,
+    std::{convert::TryInto, error::Error, fmt},
+};
+
+// The layout, with every integer little-endian:
+//
+//   magic          4 bytes, b"QTRS"
+//   version        u8
+//   coord width    u8, the size in bytes of one coordinate
+//   anchor         x, y
+//   depth          u64
+//   node capacity  u64
+//   growable       u8, 0 or 1
+//   max entries    u64, or 0 if there's no maximum
+//   policy         u8, the EvictionPolicy (see policy_tag()), or 0 if there's no maximum
+//   entry count    u64
+//   entries        x, y, width, height, then a u32 length and that many bytes of value
+//
+// Only the entries are written out. The nodes are rebuilt from them on load.
+//
+// Version 1 had no growable, max entries or policy fields. It's still read, as a tree which
+// neither grows nor has a maximum, just as serde reads a tree written without them.
+pub(crate) const MAGIC: &[u8; 4] = b"QTRS";
+pub(crate) const VERSION: u8 = 2;
+
+pub(crate) fn policy_tag(policy: EvictionPolicy) -> u8 {
+    match policy {
+        EvictionPolicy::Oldest => 0,
+        EvictionPolicy::FarthestFromCentroid => 1,
+    }
+}
+
+pub(crate) fn policy_from_tag(tag: u8) -> Option<EvictionPolicy> {
+    match tag {
+        0 => Some(EvictionPolicy::Oldest),
+        1 => Some(EvictionPolicy::FarthestFromCentroid),
+        _ => None,
+    }
+}
+
+/// Converts a value to and from a run of bytes.
+///
+/// Values are length-prefixed, so [`decode`] is handed exactly the bytes that [`encode`] wrote.
+/// Coordinates are not, so when `U` is a coordinate type every value must encode to the same
+/// number of bytes.
+///
+/// [`decode`]: #tymethod.decode
+/// [`encode`]: #tymethod.encode
+pub trait Codec: Sized {
+    /// Appends the encoding of this value to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads a value back from the bytes written by [`encode`], or `None` if they don't describe
+    /// one.
+    ///
+    /// [`encode`]: #tymethod.encode
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_codec_le {
+    ($($t:ty),*) => {
+        $(
+            impl Codec for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &[u8]) -> Option<Self> {
+                    Some(Self::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_le!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+// The pointer-sized integers are widened, so that the encoding doesn't depend on the platform.
+impl Codec for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        u64::decode(bytes)?.try_into().ok()
+    }
+}
+
+impl Codec for isize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as i64).encode(out);
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        i64::decode(bytes)?.try_into().ok()
+    }
+}
+
+impl Codec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(u8::from(*self));
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Codec for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u32::from(*self).encode(out);
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        std::char::from_u32(u32::decode(bytes)?)
+    }
+}
+
+impl Codec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl Codec for () {
+    fn encode(&self, _out: &mut Vec<u8>) {}
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+/// The ways in which [`.from_bytes()`] can fail.
+///
+/// [`.from_bytes()`]: ../struct.Quadtree.html#method.from_bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer doesn't start with the magic header.
+    BadMagic,
+    /// The buffer was written by a version of the format this one can't read.
+    UnsupportedVersion(u8),
+    /// The buffer was written for coordinates of a different width, in bytes.
+    CoordinateWidth { expected: u8, found: u8 },
+    /// The buffer ended partway through.
+    Truncated,
+    /// The buffer is complete, but what it describes is not a valid quadtree.
+    Invalid(String),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "missing magic header"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            DecodeError::CoordinateWidth { expected, found } => write!(
+                f,
+                "coordinates are {} bytes wide, expected {}",
+                found, expected
+            ),
+            DecodeError::Truncated => write!(f, "buffer ended early"),
+            DecodeError::Invalid(why) => write!(f, "invalid quadtree: {}", why),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+// A cursor over the buffer being decoded. Every read checks the length first, so a short buffer
+// turns into an error rather than a panic.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < n {
+            return Err(DecodeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::decode(self.take(4)?).expect("four bytes make a u32"))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::decode(self.take(8)?).expect("eight bytes make a u64"))
+    }
+
+    // Values are length-prefixed, so this reads the length and then hands exactly that many bytes
+    // to the Codec.
+    pub(crate) fn value<V: Codec>(&mut self) -> Result<V, DecodeError> {
+        let len = self.u32()? as usize;
+        V::decode(self.take(len)?).ok_or_else(|| DecodeError::Invalid("undecodable value".into()))
+    }
+
+    pub(crate) fn coordinate<U: Codec>(&mut self, width: usize) -> Result<U, DecodeError> {
+        U::decode(self.take(width)?)
+            .ok_or_else(|| DecodeError::Invalid("undecodable coordinate".into()))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
@@ -67,7 +67,7 @@ use {
 /// [`Quadtree`]: ../struct.Quadtree.html
 // TODO(ambuc): Entry should hold Box<V> for better return-by-value semantics.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Entry<U, V>
 where
     U: PrimInt + Default,
@@ -126,6 +126,14 @@ where
         self.area().dimensions()
     }
 
+    pub(crate) fn set_area(&mut self, region: Area<U>) {
+        self.region = region;
+    }
+
+    pub(crate) fn into_value(self) -> V {
+        self.value
+    }
+
     pub fn handle(&self) -> u64 {
         self.handle
     }
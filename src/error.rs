@@ -0,0 +1,51 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Errors which can be returned by a [`Quadtree`].
+//!
+//! [`Quadtree`]: ../struct.Quadtree.html
+
+use std::{error::Error, fmt};
+
+/// The ways in which [`.try_insert()`], [`.translate()`], and [`.remap_regions()`] can fail.
+///
+/// [`.try_insert()`]: ../struct.Quadtree.html#method.try_insert
+/// [`.translate()`]: ../struct.Quadtree.html#method.translate
+/// [`.remap_regions()`]: ../struct.Quadtree.html#method.remap_regions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuadtreeError {
+    /// The region is valid, but doesn't lie wholly within the region the quadtree represents.
+    OutOfBounds,
+    /// The region's width or height is zero or negative, so it covers nothing.
+    ZeroArea,
+    /// The region's right or bottom edge is past the largest coordinate the type can hold. Or, from
+    /// [`.translate()`], the shift would move the tree past either end of the type's range.
+    ///
+    /// [`.translate()`]: ../struct.Quadtree.html#method.translate
+    Overflow,
+}
+
+impl fmt::Display for QuadtreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuadtreeError::OutOfBounds => write!(f, "region lies outside the quadtree"),
+            QuadtreeError::ZeroArea => write!(f, "region has a nonpositive width or height"),
+            QuadtreeError::Overflow => {
+                write!(f, "region extends past the bounds of the coordinate type")
+            }
+        }
+    }
+}
+
+impl Error for QuadtreeError {}
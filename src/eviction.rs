@@ -0,0 +1,76 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! How a quadtree with a maximum number of entries makes room for another. See
+//! [`.with_max_entries()`].
+//!
+//! [`.with_max_entries()`]: ../struct.Quadtree.html#method.with_max_entries
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{entry::Entry, types::StoreType},
+    num::PrimInt,
+    std::default::Default,
+};
+
+/// Which entry a full quadtree evicts to make room for a new one.
+///
+/// The entry being inserted is never the one evicted. Where several entries are equally good
+/// candidates, the oldest of them goes.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EvictionPolicy {
+    /// The entry inserted longest ago, i.e. the one with the lowest handle.
+    Oldest,
+    /// The entry whose center lies farthest from the centroid of every entry's center, i.e. the
+    /// most spatially isolated one.
+    FarthestFromCentroid,
+}
+
+impl EvictionPolicy {
+    // The handle of the entry in @store to evict, or None if it's empty.
+    pub(crate) fn choose<U, V>(self, store: &StoreType<U, V>) -> Option<u64>
+    where
+        U: PrimInt + Default,
+    {
+        match self {
+            EvictionPolicy::Oldest => store.keys().min().copied(),
+            EvictionPolicy::FarthestFromCentroid => {
+                let center = |entry: &Entry<U, V>| {
+                    let center = entry.area().center();
+                    (center.x().to_f64().unwrap(), center.y().to_f64().unwrap())
+                };
+                let n = store.len() as f64;
+                let (sum_x, sum_y) = store
+                    .values()
+                    .map(center)
+                    .fold((0.0, 0.0), |sum, c| (sum.0 + c.0, sum.1 + c.1));
+                let centroid = (sum_x / n, sum_y / n);
+                let distance_sq = |entry: &Entry<U, V>| {
+                    let (x, y) = center(entry);
+                    (x - centroid.0).powi(2) + (y - centroid.1).powi(2)
+                };
+                store
+                    .values()
+                    .max_by(|a, b| {
+                        distance_sq(a)
+                            .total_cmp(&distance_sq(b))
+                            .then_with(|| b.handle().cmp(&a.handle()))
+                    })
+                    .map(|entry| entry.handle())
+            }
+        }
+    }
+}
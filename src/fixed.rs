@@ -0,0 +1,155 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixed-point coordinates, for placing things at sub-unit positions in a quadtree over `i64`.
+//!
+//! A [`Fixed<FRAC_BITS>`] is a number with `FRAC_BITS` binary digits after the point, kept as a
+//! raw `i64` counting steps of `2^-FRAC_BITS`. The quadtree only ever sees the raw integers, so
+//! each cell of a `Quadtree<i64, V>` is one such step wide when converting with [`Fixed::point()`]
+//! and [`Fixed::area()`]. A tree spanning `2^n` whole units needs a depth of `n + FRAC_BITS`.
+//! ```
+//! use quadtree_rs::{fixed::Fixed, Quadtree};
+//!
+//! // Quarters of a unit, over 0.0..16.0 in each direction.
+//! type Q2 = Fixed<2>;
+//! let mut qt = Quadtree::<i64, &str>::new(4 + 2);
+//!
+//! qt.insert(Q2::area(1.5, 2.25, 0.5, 0.5).unwrap(), "crate");
+//! qt.insert_pt(Q2::point(10.3, 3.8).unwrap(), "player");
+//!
+//! let near = Q2::area(1.0, 2.0, 1.0, 1.0).unwrap();
+//! assert_eq!(qt.query(near).next().unwrap().value_ref(), &"crate");
+//!
+//! let player = qt.get_at_point(Q2::point(10.3, 3.8).unwrap()).next().unwrap();
+//! assert_eq!(Q2::from_raw(player.anchor().x()).to_f64(), 10.25);
+//! ```
+//!
+//! [`Fixed<FRAC_BITS>`]: struct.Fixed.html
+//! [`Fixed::point()`]: struct.Fixed.html#method.point
+//! [`Fixed::area()`]: struct.Fixed.html#method.area
+
+use crate::{
+    area::{Area, AreaBuilder},
+    point::Point,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A fixed-point number with `FRAC_BITS` fractional bits, kept as a raw `i64`.
+///
+/// `FRAC_BITS` must be at most 62, so that one whole unit is a positive `i64`; anything larger
+/// fails to compile wherever the type is used. Comparisons and hashing work on the raw
+/// value, which orders the same way the number does.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<const FRAC_BITS: u32>(i64);
+
+impl<const FRAC_BITS: u32> Fixed<FRAC_BITS> {
+    /// One whole unit.
+    pub const ONE: Self = Fixed(Self::STEPS);
+
+    // How many raw steps make up a whole unit.
+    const STEPS: i64 = {
+        assert!(FRAC_BITS < 63, "Fixed can have at most 62 fractional bits.");
+        1 << FRAC_BITS
+    };
+    const SCALE: f64 = Self::STEPS as f64;
+
+    /// The number made of `raw` steps of `2^-FRAC_BITS`.
+    pub fn from_raw(raw: i64) -> Self {
+        Fixed(raw)
+    }
+
+    /// The number of steps of `2^-FRAC_BITS` which make up this number, as the quadtree sees it.
+    pub fn raw(self) -> i64 {
+        self.0
+    }
+
+    /// The nearest fixed-point number to `x`, rounding halves away from zero. Returns `None` if
+    /// `x` isn't finite, or is too large in magnitude to represent.
+    /// ```
+    /// use quadtree_rs::fixed::Fixed;
+    ///
+    /// assert_eq!(Fixed::<8>::from_f64(1.5).unwrap().raw(), 384);
+    /// assert_eq!(Fixed::<8>::from_f64(-0.001).unwrap().raw(), 0);
+    /// assert_eq!(Fixed::<8>::from_f64(f64::NAN), None);
+    /// assert_eq!(Fixed::<8>::from_f64(1e300), None);
+    /// ```
+    pub fn from_f64(x: f64) -> Option<Self> {
+        Self::scaled(x, f64::round).map(Fixed)
+    }
+
+    /// This number as an `f64`. Exact, unless the raw value needs more than 53 bits.
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE
+    }
+
+    /// The cell of a `Quadtree<i64, V>` which holds the position `(x, y)`, i.e. the one whose
+    /// fixed-point coordinates round down to it. Returns `None` if either coordinate isn't finite
+    /// or is out of range.
+    /// ```
+    /// use quadtree_rs::{fixed::Fixed, point::Point};
+    ///
+    /// assert_eq!(Fixed::<1>::point(2.9, -0.2), Some(Point {x: 5, y: -1}));
+    /// ```
+    pub fn point(x: f64, y: f64) -> Option<Point<i64>> {
+        Some(Point {
+            x: Self::scaled(x, f64::floor)?,
+            y: Self::scaled(y, f64::floor)?,
+        })
+    }
+
+    /// The smallest region of a `Quadtree<i64, V>` which covers the rectangle with top-left
+    /// corner `(x, y)` and dimensions `(w, h)`, rounding the near edges down and the far edges
+    /// up. A rectangle narrower than one step still covers the cell it's in. Returns `None` if
+    /// any edge isn't finite or is out of range, or if `w` or `h` is negative.
+    /// ```
+    /// use quadtree_rs::fixed::Fixed;
+    ///
+    /// let area = Fixed::<2>::area(0.3, 1.0, 0.5, 0.0).unwrap();
+    /// assert_eq!(area.anchor(), (1, 4).into());
+    /// assert_eq!((area.width(), area.height()), (3, 1));
+    /// ```
+    pub fn area(x: f64, y: f64, w: f64, h: f64) -> Option<Area<i64>> {
+        if !(w >= 0.0 && h >= 0.0) {
+            return None;
+        }
+        let left = Self::scaled(x, f64::floor)?;
+        let top = Self::scaled(y, f64::floor)?;
+        let right = Self::scaled(x + w, f64::ceil)?.max(left.checked_add(1)?);
+        let bottom = Self::scaled(y + h, f64::ceil)?.max(top.checked_add(1)?);
+        AreaBuilder::default()
+            .anchor(Point { x: left, y: top })
+            .dimensions((right.checked_sub(left)?, bottom.checked_sub(top)?))
+            .build()
+            .ok()
+    }
+
+    // @x in raw steps, rounded to an integer by @round, if that fits in an i64.
+    fn scaled(x: f64, round: fn(f64) -> f64) -> Option<i64> {
+        let scaled = round(x * Self::SCALE);
+        // i64::MAX rounds up to 2^63 as an f64, so that bound is exclusive.
+        if scaled.is_finite() && scaled >= i64::MIN as f64 && scaled < i64::MAX as f64 {
+            Some(scaled as i64)
+        } else {
+            None
+        }
+    }
+}
+
+impl<const FRAC_BITS: u32> From<Fixed<FRAC_BITS>> for i64 {
+    fn from(fixed: Fixed<FRAC_BITS>) -> i64 {
+        fixed.0
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Conversions to and from the `geo` crate's geometry types, behind the `geo` feature.
+//
+// Coordinates are copied as-is, so for the integer types a quadtree can be built over these are
+// lossless in both directions. A `Rect` spans from its min corner up to its max corner, which lines
+// up with an `Area` spanning from its anchor up to (but not including) its right and bottom edges.
+// (If float coordinates are ever supported, a `Rect` will have to be rounded outwards to the cells
+// it touches, and the trip back will no longer be exact.)
+
+use {
+    crate::{
+        area::{Area, AreaBuilder},
+        point::Point,
+    },
+    geo_types::{Coord, CoordNum, Rect},
+    num::PrimInt,
+    std::{convert::TryFrom, default::Default},
+};
+
+impl<U> From<Coord<U>> for Point<U>
+where
+    U: PrimInt + CoordNum,
+{
+    fn from(value: Coord<U>) -> Self {
+        Self {
+            x: value.x,
+            y: value.y,
+        }
+    }
+}
+
+impl<U> From<Point<U>> for Coord<U>
+where
+    U: PrimInt + CoordNum,
+{
+    fn from(value: Point<U>) -> Self {
+        Self {
+            x: value.x(),
+            y: value.y(),
+        }
+    }
+}
+
+impl<U> From<geo_types::Point<U>> for Point<U>
+where
+    U: PrimInt + CoordNum,
+{
+    fn from(value: geo_types::Point<U>) -> Self {
+        value.0.into()
+    }
+}
+
+impl<U> From<Point<U>> for geo_types::Point<U>
+where
+    U: PrimInt + CoordNum,
+{
+    fn from(value: Point<U>) -> Self {
+        Self(value.into())
+    }
+}
+
+// Fallible, since a `Rect` may have zero width or height, or an `Area` may not be able to hold its
+// max corner.
+impl<U> TryFrom<Rect<U>> for Area<U>
+where
+    U: PrimInt + Default + CoordNum,
+{
+    type Error = String;
+
+    fn try_from(value: Rect<U>) -> Result<Self, Self::Error> {
+        AreaBuilder::default()
+            .anchor(value.min().into())
+            .dimensions((value.width(), value.height()))
+            .build()
+    }
+}
+
+impl<U> From<Area<U>> for Rect<U>
+where
+    U: PrimInt + Default + CoordNum,
+{
+    fn from(value: Area<U>) -> Self {
+        Self::new(Coord::from(value.top_left()), value.bottom_right().into())
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Axis-aligned half-planes, for [`.query_halfplane()`].
+//!
+//! [`.query_halfplane()`]: ../struct.Quadtree.html#method.query_halfplane
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        area::{Area, AreaBuilder},
+        point::Point,
+    },
+    num::PrimInt,
+    std::default::Default,
+};
+
+/// The axis a half-plane's boundary crosses.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// A boundary at some `x`, i.e. a vertical line.
+    X,
+    /// A boundary at some `y`, i.e. a horizontal line.
+    Y,
+}
+
+/// Which side of its boundary a half-plane lies on.
+///
+/// As with regions, which are half-open, the cells along the boundary belong to the
+/// [`GreaterOrEqual`] side.
+///
+/// [`GreaterOrEqual`]: #variant.GreaterOrEqual
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The coordinates less than the threshold: left of it, or above it.
+    Less,
+    /// The coordinates from the threshold up: right of it, or below it.
+    GreaterOrEqual,
+}
+
+// The part of @region on @side of @threshold along @axis, or None if there isn't any.
+pub(crate) fn clip<U>(region: Area<U>, axis: Axis, threshold: U, side: Side) -> Option<Area<U>>
+where
+    U: PrimInt + Default,
+{
+    let (start, end) = match axis {
+        Axis::X => (region.left_edge(), region.right_edge()),
+        Axis::Y => (region.top_edge(), region.bottom_edge()),
+    };
+    let (start, end) = match side {
+        Side::Less => (start, end.min(threshold)),
+        Side::GreaterOrEqual => (start.max(threshold), end),
+    };
+    if start >= end {
+        return None;
+    }
+    let (anchor, dimensions) = match axis {
+        Axis::X => (
+            Point {
+                x: start,
+                y: region.top_edge(),
+            },
+            (end - start, region.height()),
+        ),
+        Axis::Y => (
+            Point {
+                x: region.left_edge(),
+                y: start,
+            },
+            (region.width(), end - start),
+        ),
+    };
+    AreaBuilder::default()
+        .anchor(anchor)
+        .dimensions(dimensions)
+        .build()
+        .ok()
+}
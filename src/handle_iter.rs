@@ -15,18 +15,26 @@
 use {
     crate::{area::Area, qtinner::QTInner, traversal::Traversal},
     num::PrimInt,
-    std::{collections::HashSet, default::Default, iter::FusedIterator},
+    std::{default::Default, iter::FusedIterator, slice},
 };
 
+// Yields every handle kept at the nodes @search_area reaches, each with the region of the node it
+// was kept at.
+//
+// A handle can be kept at several nodes, so it can come up more than once. Rather than remember
+// every handle seen so far, callers can rely on the nodes holding a handle tiling its region (see
+// validate.rs): exactly one of them contains any given point of the region, so yielding a handle
+// only alongside the node which holds some chosen point of it drops every repeat without
+// allocating anything.
 #[derive(Clone, Debug)]
 pub(crate) struct HandleIter<'a, U>
 where
     U: PrimInt + Default,
 {
     search_area: Area<U>,
-    handle_stack: Vec<u64>,
+    // The handles still to yield, grouped by the node which keeps them.
+    handle_stack: Vec<(Area<U>, slice::Iter<'a, u64>)>,
     qt_stack: Vec<&'a QTInner<U>>,
-    visited: HashSet<u64>,
 }
 
 impl<'a, U> HandleIter<'a, U>
@@ -38,7 +46,6 @@ where
             search_area,
             handle_stack: vec![],
             qt_stack: vec![qt],
-            visited: HashSet::new(),
         }
     }
 
@@ -55,7 +62,6 @@ where
         // created but has not yet been called.
         assert!(self.qt_stack.len() == 1);
         assert!(self.handle_stack.is_empty());
-        assert!(self.visited.is_empty());
 
         self.descend_recurse_step(req, traversal_method);
     }
@@ -77,7 +83,7 @@ where
                     // that our new sole qt.
                     if subquadrant.region().contains(req) {
                         if traversal_method == Traversal::Overlapping {
-                            self.handle_stack.extend(qt.handles());
+                            self.handle_stack.push((qt.region(), qt.handles().iter()));
                         }
 
                         // TODO(ambuc): Could this be done with Vec::swap() or std::mem::replace()?
@@ -99,14 +105,17 @@ impl<U> Iterator for HandleIter<'_, U>
 where
     U: PrimInt + Default,
 {
-    type Item = u64;
+    type Item = (u64, Area<U>);
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            while let Some(handle) = self.handle_stack.pop() {
-                if self.visited.insert(handle) {
-                    return Some(handle);
+            while let Some((region, handles)) = self.handle_stack.last_mut() {
+                match handles.next() {
+                    Some(&handle) => return Some((handle, *region)),
+                    None => {
+                        self.handle_stack.pop();
+                    }
                 }
             }
 
@@ -122,14 +131,8 @@ where
                 }
 
                 // Push my regions onto the region stack
-                match qt.handles().len() {
-                    0 => (),
-                    1 => {
-                        if self.visited.insert(qt.handles()[0]) {
-                            return Some(qt.handles()[0]);
-                        }
-                    }
-                    _ => self.handle_stack.extend(qt.handles()),
+                if !qt.handles().is_empty() {
+                    self.handle_stack.push((qt.region(), qt.handles().iter()));
                 }
 
                 continue;
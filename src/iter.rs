@@ -14,11 +14,19 @@
 
 use {
     crate::{
-        area::Area, entry::Entry, handle_iter::HandleIter, qtinner::QTInner, traversal::Traversal,
+        area::{Area, AreaBuilder},
+        entry::Entry,
+        halfplane::{self, Axis, Side},
+        handle_iter::HandleIter,
+        neighbor_iter::NeighborIter,
+        point::Point,
+        qtinner::{Nodes, QTInner},
+        traversal::Traversal,
         types::StoreType,
+        Quadtree,
     },
     num::PrimInt,
-    std::iter::FusedIterator,
+    std::{collections::HashSet, iter::FusedIterator},
 };
 
 /// An iterator over all regions and values of a [`Quadtree`].
@@ -32,18 +40,18 @@ pub struct Iter<'a, U, V>
 where
     U: PrimInt + Default,
 {
-    store: &'a StoreType<U, V>,
-    handle_iter: HandleIter<'a, U>,
+    // Walks the store rather than the tree, since a handle may be held by more than one node. That
+    // way there's no need to remember which handles have been seen, or to visit empty nodes.
+    inner: std::collections::hash_map::Values<'a, u64, Entry<U, V>>,
 }
 
 impl<'a, U, V> Iter<'a, U, V>
 where
     U: PrimInt + Default,
 {
-    pub(crate) fn new(qt: &'a QTInner<U>, store: &'a StoreType<U, V>) -> Iter<'a, U, V> {
+    pub(crate) fn new(store: &'a StoreType<U, V>) -> Iter<'a, U, V> {
         Iter {
-            store,
-            handle_iter: HandleIter::new(qt, qt.region()),
+            inner: store.values(),
         }
     }
 }
@@ -56,19 +64,12 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        match self.handle_iter.next() {
-            Some(handle) => Some(
-                self.store
-                    .get(&handle)
-                    .expect("Shouldn't have an handle in the tree which isn't in the store."),
-            ),
-            None => None,
-        }
+        self.inner.next()
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.store.len()))
+        self.inner.size_hint()
     }
 }
 
@@ -101,7 +102,7 @@ where
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, None)
+        (self.entries.len(), Some(self.entries.len()))
     }
 }
 
@@ -161,9 +162,19 @@ where
     type Item = &'a Entry<U, V>;
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        for handle in self.handle_iter.by_ref() {
+        for (handle, kept_at) in self.handle_iter.by_ref() {
             if let Some(entry) = self.store.get(&handle) {
-                if self.traversal_method.eval(entry.area(), self.query_region) {
+                let area = entry.area();
+                if !self.traversal_method.eval(area, self.query_region) {
+                    continue;
+                }
+                // Of all the nodes holding this entry, only yield it from the one holding the
+                // top-left corner of where it meets the query.
+                let corner = Point {
+                    x: area.left_edge().max(self.query_region.left_edge()),
+                    y: area.top_edge().max(self.query_region.top_edge()),
+                };
+                if kept_at.contains_pt(corner) {
                     return Some(entry);
                 }
             }
@@ -179,38 +190,1262 @@ where
 
 impl<U, V> FusedIterator for Query<'_, U, V> where U: PrimInt + Default {}
 
-/// An iterator over the values held within a [`Quadtree`].
+/// An iterator over the regions and values of a [`Quadtree`] which overlap some region, and whose
+/// values satisfy a predicate.
 ///
-/// This struct is created by the [`values`] method on [`Quadtree`].
+/// This struct is created by the [`query_filter`] and [`query_filter_strict`] methods on
+/// [`Quadtree`].
 ///
-/// [`values`]: ../struct.Quadtree.html#method.values
+/// [`query_filter`]: ../struct.Quadtree.html#method.query_filter
+/// [`query_filter_strict`]: ../struct.Quadtree.html#method.query_filter_strict
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone)]
+pub struct QueryFilter<'a, U, V, F>
+where
+    U: PrimInt + Default,
+    F: Fn(&V) -> bool,
+{
+    query: Query<'a, U, V>,
+    pred: F,
+}
+
+impl<'a, U, V, F> QueryFilter<'a, U, V, F>
+where
+    U: PrimInt + Default,
+    F: Fn(&V) -> bool,
+{
+    pub(crate) fn new(query: Query<'a, U, V>, pred: F) -> QueryFilter<'a, U, V, F> {
+        QueryFilter { query, pred }
+    }
+}
+
+impl<'a, U, V, F> Iterator for QueryFilter<'a, U, V, F>
+where
+    U: PrimInt + Default,
+    F: Fn(&V) -> bool,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let pred = &self.pred;
+        self.query.find(|entry| pred(entry.value_ref()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.query.size_hint().1)
+    }
+}
+
+impl<U, V, F> FusedIterator for QueryFilter<'_, U, V, F>
+where
+    U: PrimInt + Default,
+    F: Fn(&V) -> bool,
+{
+}
+
+/// An iterator over the regions and values of a [`Quadtree`] which overlap some region, each
+/// paired with the part of its region inside the query region.
+///
+/// This struct is created by the [`query_clipped`] and [`query_clipped_strict`] methods on
+/// [`Quadtree`].
+///
+/// [`query_clipped`]: ../struct.Quadtree.html#method.query_clipped
+/// [`query_clipped_strict`]: ../struct.Quadtree.html#method.query_clipped_strict
 /// [`Quadtree`]: ../struct.Quadtree.html
 #[derive(Clone, Debug)]
-pub struct Values<'a, U, V>
+pub struct QueryClipped<'a, U, V>
 where
     U: PrimInt + Default,
 {
-    pub(crate) inner: Iter<'a, U, V>,
+    query: Query<'a, U, V>,
 }
 
-impl<'a, U, V> Iterator for Values<'a, U, V>
+impl<'a, U, V> QueryClipped<'a, U, V>
 where
     U: PrimInt + Default,
 {
-    type Item = &'a V;
+    pub(crate) fn new(query: Query<'a, U, V>) -> QueryClipped<'a, U, V> {
+        QueryClipped { query }
+    }
+}
+
+impl<'a, U, V> Iterator for QueryClipped<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = (&'a Entry<U, V>, Area<U>);
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|e| e.value_ref())
+        let query_region = self.query.query_region;
+        // Everything the query yields overlaps the query region, so the intersection is never
+        // empty.
+        self.query
+            .find_map(|entry| Some((entry, entry.area().intersection(query_region)?)))
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, None)
+        self.query.size_hint()
     }
 }
 
-impl<U, V> FusedIterator for Values<'_, U, V> where U: PrimInt + Default {}
+impl<U, V> FusedIterator for QueryClipped<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions of a grouped [`Quadtree`] which overlap some region, each with
+/// the values grouped there.
+///
+/// This struct is created by the [`query_grouped`] and [`query_grouped_strict`] methods on
+/// [`Quadtree`].
+///
+/// [`query_grouped`]: ../struct.Quadtree.html#method.query_grouped
+/// [`query_grouped_strict`]: ../struct.Quadtree.html#method.query_grouped_strict
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct QueryGrouped<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    query: Query<'a, U, Vec<V>>,
+}
+
+impl<'a, U, V> QueryGrouped<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(query: Query<'a, U, Vec<V>>) -> QueryGrouped<'a, U, V> {
+        QueryGrouped { query }
+    }
+}
+
+impl<'a, U, V> Iterator for QueryGrouped<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = (Area<U>, &'a [V]);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.query
+            .next()
+            .map(|entry| (entry.area(), entry.value_ref().as_slice()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.query.size_hint()
+    }
+}
+
+impl<U, V> FusedIterator for QueryGrouped<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`], nearest to some point first.
+///
+/// This struct is created by the [`neighbors_iter`] and [`within_radius_by`] methods on
+/// [`Quadtree`].
+///
+/// [`neighbors_iter`]: ../struct.Quadtree.html#method.neighbors_iter
+/// [`within_radius_by`]: ../struct.Quadtree.html#method.within_radius_by
+/// [`Quadtree`]: ../struct.Quadtree.html
+pub struct Neighbors<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    neighbor_iter: NeighborIter<'a, U, V, Distance<'a, U>>,
+}
+
+// The distance to a region, boxed so that a `Neighbors` can be named.
+type Distance<'a, U> = Box<dyn Fn(Area<U>) -> Option<f64> + 'a>;
+
+impl<'a, U, V> Neighbors<'a, U, V>
+where
+    U: PrimInt + Default + 'a,
+{
+    pub(crate) fn new(
+        pt: Point<U>,
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> Neighbors<'a, U, V> {
+        Neighbors {
+            neighbor_iter: NeighborIter::new(
+                qt,
+                store,
+                Box::new(move |area: Area<U>| Some(area.distance_sq(pt))),
+            ),
+        }
+    }
+
+    // Ordered by @distance instead, which rules out whatever it maps to None.
+    pub(crate) fn with_distance(
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+        distance: Distance<'a, U>,
+    ) -> Neighbors<'a, U, V> {
+        Neighbors {
+            neighbor_iter: NeighborIter::new(qt, store, distance),
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for Neighbors<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.neighbor_iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.neighbor_iter.size_hint()
+    }
+}
+
+impl<U, V> FusedIterator for Neighbors<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which lie within some distance of a
+/// point.
+///
+/// This struct is created by the [`within_radius`] method on [`Quadtree`].
+///
+/// [`within_radius`]: ../struct.Quadtree.html#method.within_radius
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct WithinRadius<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // An overlapping query over the circle's bounding box, or None if that box misses the tree.
+    query: Option<Query<'a, U, V>>,
+    center: Point<U>,
+    radius_sq: f64,
+}
+
+impl<'a, U, V> WithinRadius<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        center: Point<U>,
+        radius: U,
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> WithinRadius<'a, U, V> {
+        let radius_f64 = radius.to_f64().unwrap();
+        WithinRadius {
+            query: Self::bounding_box(center, radius, qt.region())
+                .map(|bbox| Query::new(bbox, qt, store, Traversal::Overlapping)),
+            center,
+            radius_sq: radius_f64 * radius_f64,
+        }
+    }
+
+    // The bounding box of the circle, clipped to @region. The clipping keeps the box's edges from
+    // overflowing U.
+    fn bounding_box(center: Point<U>, radius: U, region: Area<U>) -> Option<Area<U>> {
+        if radius < U::zero() {
+            return None;
+        }
+        let left = center.x().saturating_sub(radius).max(region.left_edge());
+        let top = center.y().saturating_sub(radius).max(region.top_edge());
+        let right = center
+            .x()
+            .saturating_add(radius)
+            .min(region.right_edge() - U::one());
+        let bottom = center
+            .y()
+            .saturating_add(radius)
+            .min(region.bottom_edge() - U::one());
+        if left > right || top > bottom {
+            return None;
+        }
+        AreaBuilder::default()
+            .anchor(Point { x: left, y: top })
+            .dimensions((right - left + U::one(), bottom - top + U::one()))
+            .build()
+            .ok()
+    }
+}
+
+impl<'a, U, V> Iterator for WithinRadius<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let center = self.center;
+        let radius_sq = self.radius_sq;
+        self.query
+            .as_mut()?
+            .find(|entry| entry.area().distance_sq(center) <= radius_sq)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.query {
+            Some(query) => (0, query.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<U, V> FusedIterator for WithinRadius<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which reach into a ring around some
+/// point.
+///
+/// This struct is created by the [`within_ring`] method on [`Quadtree`].
+///
+/// [`within_ring`]: ../struct.Quadtree.html#method.within_ring
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct WithinRing<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // Everything within the outer circle, or None if the ring is empty.
+    within_outer: Option<WithinRadius<'a, U, V>>,
+    inner_sq: f64,
+}
+
+impl<'a, U, V> WithinRing<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        center: Point<U>,
+        inner: U,
+        outer: U,
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> WithinRing<'a, U, V> {
+        // A negative inner radius excludes nothing, just like zero.
+        let inner_f64 = inner.max(U::zero()).to_f64().unwrap();
+        WithinRing {
+            within_outer: if inner <= outer {
+                Some(WithinRadius::new(center, outer, qt, store))
+            } else {
+                None
+            },
+            inner_sq: inner_f64 * inner_f64,
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for WithinRing<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let inner_sq = self.inner_sq;
+        let within_outer = self.within_outer.as_mut()?;
+        let center = within_outer.center;
+        within_outer.find(|entry| entry.area().farthest_distance_sq(center) >= inner_sq)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.within_outer {
+            Some(within_outer) => (0, within_outer.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<U, V> FusedIterator for WithinRing<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which overlap an axis-aligned
+/// half-plane.
+///
+/// This struct is created by the [`query_halfplane`] method on [`Quadtree`].
+///
+/// [`query_halfplane`]: ../struct.Quadtree.html#method.query_halfplane
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct QueryHalfPlane<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // A query over the part of the tree in the half-plane, or None if the half-plane misses it.
+    query: Option<Query<'a, U, V>>,
+}
+
+impl<'a, U, V> QueryHalfPlane<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        axis: Axis,
+        threshold: U,
+        side: Side,
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> QueryHalfPlane<'a, U, V> {
+        QueryHalfPlane {
+            query: halfplane::clip(qt.region(), axis, threshold, side)
+                .map(|region| Query::new(region, qt, store, Traversal::Overlapping)),
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for QueryHalfPlane<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.query.as_mut()?.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.query {
+            Some(query) => query.size_hint(),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<U, V> FusedIterator for QueryHalfPlane<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which overlap (or lie within) a
+/// polygon.
+///
+/// This struct is created by the [`query_polygon`] and [`query_polygon_strict`] methods on
+/// [`Quadtree`].
+///
+/// [`query_polygon`]: ../struct.Quadtree.html#method.query_polygon
+/// [`query_polygon_strict`]: ../struct.Quadtree.html#method.query_polygon_strict
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct QueryPolygon<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // A query over the polygon's bounding box, or None if the polygon is degenerate or misses the
+    // tree.
+    query: Option<Query<'a, U, V>>,
+    verts: Vec<[f64; 2]>,
+    traversal_method: Traversal,
+}
+
+impl<'a, U, V> QueryPolygon<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        verts: &[(U, U)],
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+        traversal_method: Traversal,
+    ) -> QueryPolygon<'a, U, V> {
+        let query = if verts.len() < 3 {
+            None
+        } else {
+            Self::bounding_box(verts, qt.region())
+                .map(|bbox| Query::new(bbox, qt, store, traversal_method))
+        };
+        QueryPolygon {
+            query,
+            verts: verts
+                .iter()
+                .map(|(x, y)| [x.to_f64().unwrap(), y.to_f64().unwrap()])
+                .collect(),
+            traversal_method,
+        }
+    }
+
+    // The bounding box of the polygon, clipped to @region. The clipping keeps the box's width and
+    // height from overflowing U.
+    fn bounding_box(verts: &[(U, U)], region: Area<U>) -> Option<Area<U>> {
+        let left = verts.iter().map(|v| v.0).min()?.max(region.left_edge());
+        let top = verts.iter().map(|v| v.1).min()?.max(region.top_edge());
+        let right = verts.iter().map(|v| v.0).max()?.min(region.right_edge());
+        let bottom = verts.iter().map(|v| v.1).max()?.min(region.bottom_edge());
+        if left >= right || top >= bottom {
+            return None;
+        }
+        AreaBuilder::default()
+            .anchor(Point { x: left, y: top })
+            .dimensions((right - left, bottom - top))
+            .build()
+            .ok()
+    }
+
+    fn matches(&self, area: Area<U>) -> bool {
+        let overlap = area.polygon_overlap(&self.verts);
+        match self.traversal_method {
+            Traversal::Overlapping => overlap > 0.0,
+            Traversal::Strict => {
+                // Allow for rounding where the polygon's edges were clipped.
+                let whole = area.width().to_f64().unwrap() * area.height().to_f64().unwrap();
+                overlap >= whole * (1.0 - 1e-9)
+            }
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for QueryPolygon<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = self.query.as_mut()?.next()?;
+            if self.matches(entry.area()) {
+                return Some(entry);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.query {
+            Some(query) => (0, query.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<U, V> FusedIterator for QueryPolygon<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which touch some rectangle.
+///
+/// This struct is created by the [`query_touching`] method on [`Quadtree`].
+///
+/// [`query_touching`]: ../struct.Quadtree.html#method.query_touching
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct Touching<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // An overlapping query over the rectangle grown by one cell on each side, or None if the
+    // rectangle is malformed or misses the tree.
+    query: Option<Query<'a, U, V>>,
+}
+
+impl<'a, U, V> Touching<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        anchor: Point<U>,
+        dimensions: (U, U),
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> Touching<'a, U, V> {
+        Touching {
+            query: Self::grown(anchor, dimensions, qt.region())
+                .map(|area| Query::new(area, qt, store, Traversal::Overlapping)),
+        }
+    }
+
+    // A region touches the rectangle exactly when it overlaps the rectangle grown by one cell on
+    // each side. That's clipped to @region, which keeps its edges from overflowing U.
+    fn grown(anchor: Point<U>, (w, h): (U, U), region: Area<U>) -> Option<Area<U>> {
+        if w < U::zero() || h < U::zero() {
+            return None;
+        }
+        let left = anchor.x().saturating_sub(U::one()).max(region.left_edge());
+        let top = anchor.y().saturating_sub(U::one()).max(region.top_edge());
+        let right = anchor
+            .x()
+            .saturating_add(w)
+            .saturating_add(U::one())
+            .min(region.right_edge());
+        let bottom = anchor
+            .y()
+            .saturating_add(h)
+            .saturating_add(U::one())
+            .min(region.bottom_edge());
+        if left >= right || top >= bottom {
+            return None;
+        }
+        AreaBuilder::default()
+            .anchor(Point { x: left, y: top })
+            .dimensions((right - left, bottom - top))
+            .build()
+            .ok()
+    }
+}
+
+impl<'a, U, V> Iterator for Touching<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.query.as_mut()?.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.query {
+            Some(query) => query.size_hint(),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<U, V> FusedIterator for Touching<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which share an edge with some region,
+/// without overlapping it.
+///
+/// This struct is created by the [`adjacent_to`] method on [`Quadtree`].
+///
+/// [`adjacent_to`]: ../struct.Quadtree.html#method.adjacent_to
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct AdjacentTo<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // Everything touching @area, including at a corner or by overlapping it.
+    touching: Touching<'a, U, V>,
+    area: Area<U>,
+}
+
+impl<'a, U, V> AdjacentTo<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        area: Area<U>,
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> AdjacentTo<'a, U, V> {
+        AdjacentTo {
+            touching: Touching::new(area.anchor(), (area.width(), area.height()), qt, store),
+            area,
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for AdjacentTo<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let area = self.area;
+        self.touching.find(|entry| entry.area().touches(area))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.touching.size_hint().1)
+    }
+}
+
+impl<U, V> FusedIterator for AdjacentTo<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which cover some point.
+///
+/// This struct is created by the [`get_at_point`] method on [`Quadtree`].
+///
+/// [`get_at_point`]: ../struct.Quadtree.html#method.get_at_point
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct AtPoint<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    store: &'a StoreType<U, V>,
+    pt: Point<U>,
+    // The next node down the path of nodes containing @pt, if any.
+    qt: Option<&'a QTInner<U>>,
+    // The handles kept at the node most recently visited.
+    handles: std::slice::Iter<'a, u64>,
+}
+
+impl<'a, U, V> AtPoint<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        pt: Point<U>,
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> AtPoint<'a, U, V> {
+        AtPoint {
+            store,
+            pt,
+            qt: Some(qt).filter(|qt| qt.region().contains_pt(pt)),
+            handles: [].iter(),
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for AtPoint<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    // Every region covering @pt is held somewhere along the path of nodes which contain @pt, so
+    // there's no need to look anywhere else. And a region held at one node is never held lower
+    // down the same path, so nothing is yielded twice.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for handle in self.handles.by_ref() {
+                if let Some(entry) = self.store.get(handle) {
+                    if entry.area().contains_pt(self.pt) {
+                        return Some(entry);
+                    }
+                }
+            }
+            let qt = self.qt.take()?;
+            let pt = self.pt;
+            self.qt = qt
+                .subquadrants()
+                .as_ref()
+                .and_then(|sqs| sqs.iter().find(|sq| sq.region().contains_pt(pt)))
+                .map(|sq| sq.as_ref());
+            self.handles = qt.handles().iter();
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.store.len()))
+    }
+}
+
+impl<U, V> FusedIterator for AtPoint<'_, U, V> where U: PrimInt + Default {}
+
+type Pair<'a, U, V> = (&'a Entry<U, V>, &'a Entry<U, V>);
+
+/// An iterator over the pairs of entries of a [`Quadtree`] whose regions overlap.
+///
+/// This struct is created by the [`overlapping_pairs`] method on [`Quadtree`].
+///
+/// [`overlapping_pairs`]: ../struct.Quadtree.html#method.overlapping_pairs
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct OverlappingPairs<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    store: &'a StoreType<U, V>,
+    // Nodes still to visit, each with how many of @ancestors are kept above it.
+    stack: Vec<(&'a QTInner<U>, usize)>,
+    // The entries kept by the nodes on the path down to the current one.
+    ancestors: Vec<&'a Entry<U, V>>,
+    // Pairs found in the current node, not yet yielded.
+    pending: Vec<Pair<'a, U, V>>,
+}
+
+impl<'a, U, V> OverlappingPairs<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+    ) -> OverlappingPairs<'a, U, V> {
+        OverlappingPairs {
+            store,
+            stack: vec![(qt, 0)],
+            ancestors: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    // Pairs each entry kept by @qt with the overlapping entries kept above it, and before it in
+    // @qt. Entries in other subtrees can't overlap it, and those below it pair with it in turn.
+    // Since a pair may be kept by several nodes in common, it's only yielded by the one holding the
+    // top-left corner of the overlap; see par_query.rs.
+    fn visit(&mut self, qt: &'a QTInner<U>, kept_above: usize) {
+        self.ancestors.truncate(kept_above);
+        for handle in qt.handles() {
+            if let Some(entry) = self.store.get(handle) {
+                for other in self.ancestors.iter() {
+                    if let Some(overlap) = entry.area().intersection(other.area()) {
+                        if qt.region().contains_pt(overlap.anchor()) {
+                            self.pending.push((other, entry));
+                        }
+                    }
+                }
+                self.ancestors.push(entry);
+            }
+        }
+        if let Some(subquadrants) = qt.subquadrants().as_ref() {
+            for sq in subquadrants.iter().rev() {
+                self.stack.push((sq, self.ancestors.len()));
+            }
+        }
+        // Yield in the order the pairs were found.
+        self.pending.reverse();
+    }
+}
+
+impl<'a, U, V> Iterator for OverlappingPairs<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = Pair<'a, U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pair) = self.pending.pop() {
+                return Some(pair);
+            }
+            let (qt, kept_above) = self.stack.pop()?;
+            self.visit(qt, kept_above);
+        }
+    }
+}
+
+impl<U, V> FusedIterator for OverlappingPairs<'_, U, V> where U: PrimInt + Default {}
+
+/// A draining iterator over the entries of a [`Quadtree`] which intersect some region.
+///
+/// This struct is created by the [`drain_region`] method on [`Quadtree`].
+///
+/// [`drain_region`]: ../struct.Quadtree.html#method.drain_region
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Debug)]
+pub struct DrainRegion<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) qt: &'a mut Quadtree<U, V>,
+    // The handles of the matching entries not yet removed.
+    pub(crate) handles: Vec<u64>,
+}
+
+impl<U, V> Iterator for DrainRegion<'_, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // Nothing else can touch the tree while it's borrowed, so every handle is still there.
+        let handle = self.handles.pop()?;
+        self.qt.delete_by_handle(handle)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.handles.len(), Some(self.handles.len()))
+    }
+}
+
+impl<U, V> FusedIterator for DrainRegion<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over all regions and values of a [`Quadtree`], in Z-order.
+///
+/// This struct is created by the [`iter_z_order`] method on [`Quadtree`].
+///
+/// [`iter_z_order`]: ../struct.Quadtree.html#method.iter_z_order
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct ZOrder<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    store: &'a StoreType<U, V>,
+    // Nodes still to visit, the next one on top.
+    qt_stack: Vec<&'a QTInner<U>>,
+    // The handles kept at the node most recently visited.
+    handles: std::slice::Iter<'a, u64>,
+    // A region may be kept at several nodes; it's only yielded from the first.
+    visited: HashSet<u64>,
+}
+
+impl<'a, U, V> ZOrder<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(qt: &'a QTInner<U>, store: &'a StoreType<U, V>) -> ZOrder<'a, U, V> {
+        ZOrder {
+            store,
+            qt_stack: vec![qt],
+            handles: [].iter(),
+            visited: HashSet::new(),
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for ZOrder<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for handle in self.handles.by_ref() {
+                if self.visited.insert(*handle) {
+                    if let Some(entry) = self.store.get(handle) {
+                        return Some(entry);
+                    }
+                }
+            }
+            let qt = self.qt_stack.pop()?;
+            if let Some(sqs) = qt.subquadrants() {
+                // Subquadrants are stored [ne, nw, se, sw]; push them so that they pop off in
+                // [nw, ne, sw, se] order.
+                self.qt_stack
+                    .extend([2, 3, 0, 1].iter().map(|&i| sqs[i].as_ref()));
+            }
+            self.handles = qt.handles().iter();
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.store.len()))
+    }
+}
+
+impl<U, V> FusedIterator for ZOrder<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over all regions and values of a [`Quadtree`], in Hilbert order.
+///
+/// This struct is created by the [`iter_hilbert`] method on [`Quadtree`].
+///
+/// [`iter_hilbert`]: ../struct.Quadtree.html#method.iter_hilbert
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct Hilbert<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    store: &'a StoreType<U, V>,
+    // Nodes still to visit, the next one on top, each with the orientation of the curve through it.
+    qt_stack: Vec<(&'a QTInner<U>, Orientation)>,
+    // The handles kept at the node most recently visited.
+    handles: std::slice::Iter<'a, u64>,
+    // A region may be kept at several nodes; it's only yielded from the first.
+    visited: HashSet<u64>,
+}
+
+// How the Hilbert curve through a node is turned, relative to the curve through the root. That
+// one visits the subquadrants [nw, sw, se, ne], starting in the top-left and ending in the
+// top-right. Any other is that one mirrored across the diagonal (@transpose), turned half way
+// round (@flip), or both, which mirrors it across the anti-diagonal.
+#[derive(Clone, Copy, Debug, Default)]
+struct Orientation {
+    flip: bool,
+    transpose: bool,
+}
+
+impl Orientation {
+    // Each subquadrant's position along the curve through this node, and the orientation of the
+    // curve through it, for subquadrants in the order they are stored: [ne, nw, se, sw].
+    fn children(self) -> [(usize, Orientation); 4] {
+        let child = |x: bool, y: bool| {
+            // Where the subquadrant at (x, y) sits in the root's orientation.
+            let (x, y) = (x ^ self.flip, y ^ self.flip);
+            let (x, y) = if self.transpose { (y, x) } else { (x, y) };
+            let position = match (x, y) {
+                (false, false) => 0,
+                (false, true) => 1,
+                (true, true) => 2,
+                (true, false) => 3,
+            };
+            // The first and last quarters of the curve are turned: the first across the diagonal,
+            // the last across the anti-diagonal.
+            let orientation = Orientation {
+                flip: self.flip ^ (x && !y),
+                transpose: self.transpose ^ !y,
+            };
+            (position, orientation)
+        };
+        [
+            child(true, false),
+            child(false, false),
+            child(true, true),
+            child(false, true),
+        ]
+    }
+}
+
+impl<'a, U, V> Hilbert<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(qt: &'a QTInner<U>, store: &'a StoreType<U, V>) -> Hilbert<'a, U, V> {
+        Hilbert {
+            store,
+            qt_stack: vec![(qt, Orientation::default())],
+            handles: [].iter(),
+            visited: HashSet::new(),
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for Hilbert<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for handle in self.handles.by_ref() {
+                if self.visited.insert(*handle) {
+                    if let Some(entry) = self.store.get(handle) {
+                        return Some(entry);
+                    }
+                }
+            }
+            let (qt, orientation) = self.qt_stack.pop()?;
+            if let Some(sqs) = qt.subquadrants() {
+                // Push the subquadrants so that they pop off in their order along the curve.
+                let mut ordered: [Option<(&'a QTInner<U>, Orientation)>; 4] = [None; 4];
+                for (sq, (position, child)) in sqs.iter().zip(orientation.children().iter()) {
+                    ordered[*position] = Some((sq.as_ref(), *child));
+                }
+                self.qt_stack.extend(ordered.iter().rev().flatten());
+            }
+            self.handles = qt.handles().iter();
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.store.len()))
+    }
+}
+
+impl<U, V> FusedIterator for Hilbert<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the leaves of a [`Quadtree`] which hold something, each with its regions and
+/// values.
+///
+/// This struct is created by the [`leaves`] method on [`Quadtree`].
+///
+/// [`leaves`]: ../struct.Quadtree.html#method.leaves
+/// [`Quadtree`]: ../struct.Quadtree.html
+pub struct Leaves<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    store: &'a StoreType<U, V>,
+    nodes: Nodes<'a, U>,
+}
+
+impl<'a, U, V> Leaves<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(qt: &'a QTInner<U>, store: &'a StoreType<U, V>) -> Leaves<'a, U, V> {
+        Leaves {
+            store,
+            nodes: qt.nodes(),
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for Leaves<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = (Area<U>, Vec<&'a Entry<U, V>>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let store = self.store;
+        self.nodes.by_ref().find_map(|(qt, _)| {
+            if qt.subquadrants().is_some() || qt.handles().is_empty() {
+                return None;
+            }
+            let entries = qt
+                .handles()
+                .iter()
+                .filter_map(|handle| store.get(handle))
+                .collect();
+            Some((qt.region(), entries))
+        })
+    }
+}
+
+impl<U, V> FusedIterator for Leaves<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the regions and values of a [`Quadtree`] which don't fit in any one leaf.
+///
+/// This struct is created by the [`straddling_entries`] method on [`Quadtree`].
+///
+/// [`straddling_entries`]: ../struct.Quadtree.html#method.straddling_entries
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct Straddling<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    qt: &'a QTInner<U>,
+    entries: Iter<'a, U, V>,
+}
+
+impl<'a, U, V> Straddling<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(qt: &'a QTInner<U>, store: &'a StoreType<U, V>) -> Straddling<'a, U, V> {
+        Straddling {
+            qt,
+            entries: Iter::new(store),
+        }
+    }
+}
+
+impl<'a, U, V> Iterator for Straddling<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a Entry<U, V>;
+
+    // A region lies in just one leaf exactly when the leaf holding its top-left corner contains
+    // all of it. A region kept higher up covers a whole node, so it can't fit in a leaf beneath.
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let qt = self.qt;
+        self.entries.by_ref().find(|entry| {
+            let area = entry.area();
+            !qt.leaf_at(area.anchor()).region().contains(area)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.entries.size_hint().1)
+    }
+}
+
+impl<U, V> FusedIterator for Straddling<'_, U, V> where U: PrimInt + Default {}
+
+/// An iterator over the values held within a [`Quadtree`].
+///
+/// This struct is created by the [`values`] method on [`Quadtree`].
+///
+/// [`values`]: ../struct.Quadtree.html#method.values
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Clone, Debug)]
+pub struct Values<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) inner: Iter<'a, U, V>,
+}
+
+impl<'a, U, V> Iterator for Values<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|e| e.value_ref())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<U, V> FusedIterator for Values<'_, U, V> where U: PrimInt + Default {}
+
+/// A mutable iterator over the regions and values of a [`Quadtree`].
+///
+/// Regions can't be changed through the [`&'a mut Entry<U, V>`] items, only values.
+///
+/// This struct is created by the [`iter_mut`] method on [`Quadtree`].
+///
+/// [`&'a mut Entry<U, V>`]: ../entry/struct.Entry.html
+/// [`iter_mut`]: ../struct.Quadtree.html#method.iter_mut
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Debug)]
+pub struct IterMut<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // Walks the store rather than the tree, since a handle may be held by more than one node.
+    pub(crate) inner: std::collections::hash_map::ValuesMut<'a, u64, Entry<U, V>>,
+}
+
+impl<'a, U, V> Iterator for IterMut<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a mut Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<U, V> FusedIterator for IterMut<'_, U, V> where U: PrimInt + Default {}
+
+/// A mutable iterator over the values held within a [`Quadtree`].
+///
+/// This struct is created by the [`values_mut`] method on [`Quadtree`].
+///
+/// [`values_mut`]: ../struct.Quadtree.html#method.values_mut
+/// [`Quadtree`]: ../struct.Quadtree.html
+#[derive(Debug)]
+pub struct ValuesMut<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    // Walks the store rather than the tree, since a handle may be held by more than one node.
+    pub(crate) inner: std::collections::hash_map::ValuesMut<'a, u64, Entry<U, V>>,
+}
+
+impl<'a, U, V> Iterator for ValuesMut<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|e| e.value_mut())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<U, V> FusedIterator for ValuesMut<'_, U, V> where U: PrimInt + Default {}
 
 /// An iterator over the regions held within a [`Quadtree`].
 ///
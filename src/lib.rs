@@ -108,7 +108,32 @@
 //!
 //! For further usage details, see the documentations for the [`Quadtree`] struct.
 //!
+//! # Features
+//!
+//! - `serde`: Implements `Serialize` and `Deserialize` for [`Quadtree`] and the types it is built
+//!   from, whenever `U` and `V` implement them too. The whole tree is written out, including the
+//!   handle counter, so handles issued before serializing stay valid after deserializing and new
+//!   inserts never reuse them.
+//! - `geo`: Converts between [`Point`] and `geo::Coord` (or `geo::Point`), and between [`Area`]
+//!   and `geo::Rect`, so that `geo` geometries can be inserted and queried directly. Since both
+//!   sides hold the same integer coordinates, the conversions are lossless. A `geo::Rect` only
+//!   converts to an `Area` with `TryFrom`, since it may have zero width or height.
+//! - `rayon`: Adds [`.par_query()`] and [`.par_query_strict()`], which spread a query across
+//!   threads, for `U` and `V` which can be shared between them.
+//! - `debug-viz`: Adds [`.to_svg()`], which draws the nodes and entries of a quadtree as an SVG
+//!   image, for debugging.
+//! - `rand`: Adds [`.sample()`], which picks an entry uniformly at random with any `rand::Rng`.
+//! - `validate`: Adds [`.validate()`], which checks the internal structure of a quadtree, for
+//!   catching bugs while developing the crate.
+//!
 //! [`Quadtree`]: struct.Quadtree.html
+//! [`Point`]: point/struct.Point.html
+//! [`Area`]: area/struct.Area.html
+//! [`.par_query()`]: struct.Quadtree.html#method.par_query
+//! [`.par_query_strict()`]: struct.Quadtree.html#method.par_query_strict
+//! [`.to_svg()`]: struct.Quadtree.html#method.to_svg
+//! [`.sample()`]: struct.Quadtree.html#method.sample
+//! [`.validate()`]: struct.Quadtree.html#method.validate
 
 // For extra-pedantic documentation tests.
 #![doc(test(attr(deny(warnings))))]
@@ -118,33 +143,66 @@ extern crate derive_builder;
 extern crate num;
 
 pub mod area;
+pub mod builder;
+pub mod bytes;
 pub mod entry;
+pub mod error;
+pub mod eviction;
+pub mod fixed;
+pub mod halfplane;
 pub mod iter;
+pub mod metric;
+pub mod node;
 pub mod point;
+pub mod shared;
 
+#[cfg(feature = "geo")]
+mod geo;
 mod handle_iter;
+mod neighbor_iter;
+#[cfg(feature = "rayon")]
+mod par_query;
 mod qtinner;
+#[cfg(feature = "debug-viz")]
+mod svg;
 mod traversal;
 mod types;
+#[cfg(any(test, feature = "validate"))]
+mod validate;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use {
     crate::{
         area::{Area, AreaBuilder},
+        builder::QuadtreeBuilder,
+        bytes::{policy_from_tag, policy_tag, Codec, DecodeError, Reader, MAGIC, VERSION},
         entry::Entry,
-        handle_iter::HandleIter,
-        iter::{IntoIter, Iter, Query, Regions, Values},
+        error::QuadtreeError,
+        eviction::EvictionPolicy,
+        halfplane::{Axis, Side},
+        iter::{
+            AdjacentTo, AtPoint, DrainRegion, Hilbert, IntoIter, Iter, IterMut, Leaves, Neighbors,
+            OverlappingPairs, Query, QueryClipped, QueryFilter, QueryGrouped, QueryHalfPlane,
+            QueryPolygon, Regions, Straddling, Touching, Values, ValuesMut, WithinRadius,
+            WithinRing, ZOrder,
+        },
+        neighbor_iter::NeighborIter,
+        node::NodeRef,
         point::Point,
         qtinner::QTInner,
+        shared::SharedQuadtree,
         traversal::Traversal,
         types::StoreType,
     },
     num::PrimInt,
     std::{
-        collections::{HashMap, HashSet},
+        cmp::Ordering,
+        collections::{BTreeMap, HashMap, HashSet},
+        convert::{TryFrom, TryInto},
         default::Default,
-        hash::Hash,
+        iter::FromIterator,
+        ops::ControlFlow,
     },
 };
 
@@ -164,27 +222,48 @@ use {
 /// insertion and comparison. `U` must also implement `std::default` for [`derive_builder`]
 /// semantics.
 ///
+/// Any primitive integer will do, up to `i128` and `u128`, and regions may reach the largest
+/// coordinate `U` can hold. The deepest tree is one level shallower than `U` has bits (or two for
+/// a signed `U`), since the tree's width, `2^depth`, must itself fit in `U`. Areas which are too
+/// large for a `u64` saturate, as in [`.coverage_area()`].
+///
+/// Floating-point coordinates are not supported. Subdivision relies on exact integer midpoints,
+/// and every region is a whole number of unit cells. To index floating-point data, put it on an
+/// integer grid of the precision you need with [`Fixed::point()`] and [`Fixed::area()`] (or
+/// [`Fixed::from_f64()`] for a single coordinate). These round rather than truncate, and return
+/// `None` for input which is out of range or not finite, rather than saturating.
+///
 /// ## Strictness
 ///
-/// Some methods ([`.query()`], [`.modify()`], and [`.delete()`]) have strict variants. While the
-/// default behavior is for any operation to apply to all regions which _intersect_ some
-/// operational region, the strict behavior is for the operation to apply only to those regions
-/// which are _totally contained by_ the operational region.
+/// Some methods ([`.query()`], [`.modify()`], and [`.delete()`], among others) have strict
+/// variants, named with a `_strict` suffix. While the default behavior is for any operation to
+/// apply to all regions which _intersect_ some operational region, the strict behavior is for the
+/// operation to apply only to those regions which are _totally contained by_ the operational
+/// region.
 ///
 /// [`derive_builder`]: https://docs.rs/derive_builder/0.7.0/derive_builder/
 /// [`.query()`]: #method.query
 /// [`.modify()`]: #method.modify
 /// [`.delete()`]: #method.delete
+/// [`.coverage_area()`]: #method.coverage_area
+/// [`Fixed::point()`]: fixed/struct.Fixed.html#method.point
+/// [`Fixed::area()`]: fixed/struct.Fixed.html#method.area
+/// [`Fixed::from_f64()`]: fixed/struct.Fixed.html#method.from_f64
 // TODO(ambuc): Implement `.delete_by(anchor, dimensions, fn)`: `.retain()` is the inverse.
-// TODO(ambuc): Implement `FromIterator<(K, V)>` for `Quadtree`.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug)]
 pub struct Quadtree<U, V>
 where
     U: PrimInt + Default,
 {
     inner: QTInner<U>,
     store: StoreType<U, V>,
+    // Whether an insert which doesn't fit grows the root until it does, rather than failing.
+    #[cfg_attr(feature = "serde", serde(default))]
+    growable: bool,
+    // The most entries the tree holds at once, and which to evict to make room for another.
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_entries: Option<(usize, EvictionPolicy)>,
 }
 
 impl<U, V> Quadtree<U, V>
@@ -223,7 +302,11 @@ where
     /// anchor argument is of type [`point::Point`], and can either be
     /// explicit (`Point {x: 2, y: 4}`) or implicit (`(2, 4).into()`).
     ///
+    /// Panics if the tree's region wouldn't fit in `U`, i.e. if `anchor + 2^depth` overflows. (A
+    /// [`QuadtreeBuilder`] returns an error instead, and sets the other options in one place.)
+    ///
     /// [`point::Point`]: point/struct.Point.html
+    /// [`QuadtreeBuilder`]: builder/struct.QuadtreeBuilder.html
     /// ```
     /// use quadtree_rs::{point::Point, Quadtree};
     ///
@@ -240,7 +323,384 @@ where
         Self {
             inner: QTInner::new(anchor, depth),
             store: HashMap::new(),
+            growable: false,
+            max_entries: None,
+        }
+    }
+
+    /// Creates a new, empty quadtree like [`.new_with_anchor()`], with room for at least
+    /// `capacity` entries before the central store of entries needs to grow.
+    ///
+    /// This only saves reallocating as the quadtree fills up. Its behavior is otherwise the same.
+    /// (Not to be confused with [`.with_capacity()`], which sets how many regions each node holds.)
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u32>::new_with_anchor_and_capacity(Point {x: 0, y: 0}, 8, 1000);
+    /// qt.extend((0..1000).map(|i| ((i % 256, i / 256), i)));
+    /// assert_eq!(qt.len(), 1000);
+    /// ```
+    ///
+    /// [`.new_with_anchor()`]: #method.new_with_anchor
+    /// [`.with_capacity()`]: #method.with_capacity
+    pub fn new_with_anchor_and_capacity(
+        anchor: point::Point<U>,
+        depth: usize,
+        capacity: usize,
+    ) -> Self {
+        Self {
+            inner: QTInner::new(anchor, depth),
+            store: HashMap::with_capacity(capacity),
+            growable: false,
+            max_entries: None,
+        }
+    }
+
+    /// Creates a new, empty quadtree like [`.new_with_anchor()`], whose region grows to hold
+    /// whatever is inserted into it.
+    ///
+    /// Adding a region which doesn't fit (with [`.insert()`], [`.insert_pt()`], [`.upsert()`],
+    /// `.extend()`, [`.merge()`], [`.relocate()`] or [`.remap_regions()`]) doubles the width and
+    /// height of the tree until it does. Each time, the old root becomes one of the four
+    /// subquadrants of a new root one level deeper. The new root extends the old one towards the
+    /// added region: its anchor moves left by the old width if the region lies to the left of the
+    /// tree, and up by the old height if it lies above it, and otherwise stays put.
+    ///
+    /// So coordinates never change meaning: every entry keeps its region and its handle, and
+    /// stays where queries will find it. Only [`.anchor()`], [`.depth()`], [`.width()`] and
+    /// [`.height()`] change, and the region the tree covers only ever gets larger. A region which
+    /// couldn't fit without the tree's edges overflowing `U` is still rejected, and then the tree
+    /// isn't grown at all.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<i32, char>::new_growable(Point {x: 0, y: 0}, 2);
+    /// let a = qt.insert_pt(Point {x: 1, y: 1}, 'a').unwrap();
+    ///
+    /// // Outside the 4x4 tree, to the right and above.
+    /// assert!(qt.insert_pt(Point {x: 5, y: -2}, 'b').is_some());
+    /// assert_eq!(qt.anchor(), Point {x: 0, y: -4});
+    /// assert_eq!((qt.depth(), qt.width()), (3, 8));
+    ///
+    /// // The old entry hasn't moved.
+    /// assert_eq!(qt.get(a).unwrap().anchor(), Point {x: 1, y: 1});
+    /// assert_eq!(qt.get_at_point(Point {x: 1, y: 1}).count(), 1);
+    /// ```
+    ///
+    /// [`.new_with_anchor()`]: #method.new_with_anchor
+    /// [`.insert()`]: #method.insert
+    /// [`.insert_pt()`]: #method.insert_pt
+    /// [`.upsert()`]: #method.upsert
+    /// [`.merge()`]: #method.merge
+    /// [`.relocate()`]: #method.relocate
+    /// [`.remap_regions()`]: #method.remap_regions
+    /// [`.anchor()`]: #method.anchor
+    /// [`.depth()`]: #method.depth
+    /// [`.width()`]: #method.width
+    /// [`.height()`]: #method.height
+    pub fn new_growable(anchor: point::Point<U>, depth: usize) -> Self {
+        Self {
+            growable: true,
+            ..Self::new_with_anchor(anchor, depth)
+        }
+    }
+
+    /// Creates a new, empty quadtree which only subdivides a node once it holds more than
+    /// `node_capacity` regions, down to a depth of at most `max_depth`.
+    ///
+    /// By default (see [`.new_with_anchor()`]) every region is pushed as deep into the tree as it
+    /// can go, so each point costs a full path of nodes to the bottom. Holding a few regions per
+    /// node instead makes for far fewer nodes where the data is sparse, while dense clusters are
+    /// still subdivided. Queries return the same results either way.
+    ///
+    /// A node with a capacity of zero splits as soon as it's needed, so
+    /// `Quadtree::with_capacity(anchor, depth, 0)` behaves just like
+    /// `Quadtree::new_with_anchor(anchor, depth)`.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u8>::with_capacity(Point {x: 0, y: 0}, 8, 4);
+    /// assert_eq!(qt.depth(), 8);
+    ///
+    /// // The root holds up to four points before it needs to split.
+    /// qt.extend(vec![((0, 0), 1), ((255, 0), 2), ((0, 255), 3), ((255, 255), 4)]);
+    /// assert_eq!(qt.node_count(), 1);
+    ///
+    /// qt.insert_pt(Point {x: 128, y: 128}, 5);
+    /// assert_eq!(qt.node_count(), 5);
+    /// ```
+    ///
+    /// [`.new_with_anchor()`]: #method.new_with_anchor
+    pub fn with_capacity(anchor: point::Point<U>, max_depth: usize, node_capacity: usize) -> Self {
+        Self {
+            inner: QTInner::new_with_capacity(anchor, max_depth, node_capacity),
+            store: HashMap::new(),
+            growable: false,
+            max_entries: None,
+        }
+    }
+
+    /// Creates a new, empty quadtree like [`.new_with_anchor()`], which holds at most
+    /// `max_entries` entries at once.
+    ///
+    /// Inserting into a full quadtree first evicts one of its entries, chosen by `policy`, so that
+    /// it never holds more than `max_entries`. [`.insert_evicting()`] hands back the evicted
+    /// entry; [`.insert()`] and the methods built on it just drop it. An insertion which fails
+    /// (because the region doesn't fit) evicts nothing. Finding the entry to evict takes O(n)
+    /// time, under either policy.
+    ///
+    /// Panics if `max_entries` is zero. (A [`QuadtreeBuilder`] returns an error instead.)
+    /// ```
+    /// use quadtree_rs::{eviction::EvictionPolicy, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::with_max_entries(
+    ///     Point {x: 0, y: 0}, 4, 2, EvictionPolicy::Oldest);
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'a');
+    /// qt.insert_pt(Point {x: 2, y: 2}, 'b');
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'c');
+    ///
+    /// assert_eq!(qt.len(), 2);
+    /// assert_eq!(qt.get_at_point(Point {x: 1, y: 1}).count(), 0);
+    /// ```
+    ///
+    /// [`.new_with_anchor()`]: #method.new_with_anchor
+    /// [`.insert_evicting()`]: #method.insert_evicting
+    /// [`.insert()`]: #method.insert
+    /// [`QuadtreeBuilder`]: builder/struct.QuadtreeBuilder.html
+    pub fn with_max_entries(
+        anchor: point::Point<U>,
+        depth: usize,
+        max_entries: usize,
+        policy: EvictionPolicy,
+    ) -> Self {
+        assert!(max_entries > 0, "A quadtree must be able to hold an entry.");
+        Self {
+            max_entries: Some((max_entries, policy)),
+            ..Self::new_with_anchor(anchor, depth)
+        }
+    }
+
+    /// Creates a new quadtree with some depth and an explicit anchor, holding every point in
+    /// `entries`.
+    ///
+    /// The shape of a quadtree depends only on the regions it holds, never on the order they were
+    /// inserted in, so the result is the same tree as calling `.extend()` on an empty one. Bulk
+    /// loading just gets there faster, by partitioning all the points at each level instead of
+    /// descending from the root once per point.
+    ///
+    /// As with `.extend()`, points which do not fit in the region are silently dropped.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let qt = Quadtree::<u32, char>::from_entries(
+    ///     (0, 0).into(),
+    ///     3,
+    ///     vec![((1, 1), 'a'), ((6, 2), 'b'), ((9, 9), 'c')],
+    /// );
+    ///
+    /// // (9, 9) doesn't fit in an 8x8 tree.
+    /// assert_eq!(qt.len(), 2);
+    ///
+    /// let region = AreaBuilder::default()
+    ///     .anchor((4, 0).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap();
+    /// assert_eq!(qt.query(region).next().unwrap().value_ref(), &'b');
+    /// ```
+    pub fn from_entries<I>(anchor: point::Point<U>, depth: usize, entries: I) -> Self
+    where
+        I: IntoIterator<Item = ((U, U), V)>,
+    {
+        let mut qt = Self::new_with_anchor(anchor, depth);
+        qt.inner.insert_vals_at_pts(
+            entries.into_iter().map(|(pt, val)| (pt.into(), val)),
+            &mut qt.store,
+        );
+        qt
+    }
+
+    /// Encodes this quadtree as bytes, which [`.from_bytes()`] can turn back into an equal one.
+    ///
+    /// Only the anchor, depth, node capacity, whether the tree grows (see [`.new_growable()`]),
+    /// its maximum number of entries and eviction policy (see [`.with_max_entries()`]), and the
+    /// region and value of each entry are written out, in a little-endian format with a magic
+    /// header and a version number. Coordinates and values are encoded by their [`Codec`]
+    /// implementations.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, String>::new(4);
+    /// qt.insert_pt((1, 2).into(), "a".to_string());
+    /// qt.insert(
+    ///     AreaBuilder::default()
+    ///         .anchor((3, 3).into())
+    ///         .dimensions((5, 2))
+    ///         .build().unwrap(),
+    ///     "b".to_string(),
+    /// );
+    ///
+    /// let bytes = qt.to_bytes();
+    /// let copy = Quadtree::<u32, String>::from_bytes(&bytes).unwrap();
+    /// assert!(copy == qt);
+    ///
+    /// // Anything short of the whole buffer is an error.
+    /// assert!(Quadtree::<u32, String>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If a value encodes to 2^32 bytes or more.
+    ///
+    /// [`.from_bytes()`]: #method.from_bytes
+    /// [`.new_growable()`]: #method.new_growable
+    /// [`.with_max_entries()`]: #method.with_max_entries
+    /// [`Codec`]: bytes/trait.Codec.html
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        U: Codec,
+        V: Codec,
+    {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(Self::coordinate_width());
+        self.anchor().x().encode(&mut out);
+        self.anchor().y().encode(&mut out);
+        (self.depth() as u64).encode(&mut out);
+        (self.inner.node_capacity() as u64).encode(&mut out);
+        self.growable.encode(&mut out);
+        let (max_entries, policy) = match self.max_entries {
+            Some((max, policy)) => (max as u64, policy_tag(policy)),
+            None => (0, 0),
+        };
+        max_entries.encode(&mut out);
+        policy.encode(&mut out);
+        (self.len() as u64).encode(&mut out);
+
+        // In handle order, so that the same tree always encodes to the same bytes.
+        let mut handles: Vec<u64> = self.store.keys().copied().collect();
+        handles.sort_unstable();
+        let mut value = Vec::new();
+        for handle in handles {
+            let entry = &self.store[&handle];
+            entry.anchor().x().encode(&mut out);
+            entry.anchor().y().encode(&mut out);
+            entry.width().encode(&mut out);
+            entry.height().encode(&mut out);
+            value.clear();
+            entry.value_ref().encode(&mut value);
+            let len: u32 = value
+                .len()
+                .try_into()
+                .expect("Values must encode to fewer than 2^32 bytes.");
+            len.encode(&mut out);
+            out.extend_from_slice(&value);
+        }
+        out
+    }
+
+    /// Decodes a quadtree from the bytes written by [`.to_bytes()`].
+    ///
+    /// The nodes are rebuilt by inserting each entry in turn, so the result is equal to the
+    /// original, but its entries are given fresh handles. Handles into the original are not valid
+    /// afterwards. A buffer which is truncated, corrupt, or was written for a different coordinate
+    /// type is rejected with a [`DecodeError`]; this never panics. Bytes in the first version of
+    /// the format, which didn't record whether a tree grows or its maximum number of entries,
+    /// decode to a tree which does neither.
+    ///
+    /// [`.to_bytes()`]: #method.to_bytes
+    /// [`DecodeError`]: bytes/enum.DecodeError.html
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        U: Codec,
+        V: Codec,
+    {
+        let mut reader = Reader::new(bytes);
+        if reader.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(DecodeError::BadMagic);
+        }
+        let version = reader.u8()?;
+        if version != 1 && version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let found = reader.u8()?;
+        let expected = Self::coordinate_width();
+        if found != expected {
+            return Err(DecodeError::CoordinateWidth { expected, found });
         }
+        let width = usize::from(found);
+
+        let anchor = Point {
+            x: reader.coordinate(width)?,
+            y: reader.coordinate(width)?,
+        };
+        let depth = reader.u64()?;
+        let node_capacity = usize::try_from(reader.u64()?)
+            .map_err(|_| DecodeError::Invalid("node capacity is too large".into()))?;
+        let (growable, max_entries) = if version == 1 {
+            (false, None)
+        } else {
+            let growable = bool::decode(reader.take(1)?)
+                .ok_or_else(|| DecodeError::Invalid("undecodable growable flag".into()))?;
+            let max = usize::try_from(reader.u64()?).map_err(|_| {
+                DecodeError::Invalid("maximum number of entries is too large".into())
+            })?;
+            let policy = policy_from_tag(reader.u8()?)
+                .ok_or_else(|| DecodeError::Invalid("unknown eviction policy".into()))?;
+            (growable, if max == 0 { None } else { Some((max, policy)) })
+        };
+
+        // Built with the builder, since the constructors panic if the region doesn't fit in U.
+        let depth = usize::try_from(depth)
+            .map_err(|_| DecodeError::Invalid("depth is too large".into()))?;
+        let mut builder = QuadtreeBuilder::default();
+        builder
+            .anchor(anchor)
+            .depth(depth)
+            .node_capacity(node_capacity)
+            .growable(growable);
+        if let Some((max, policy)) = max_entries {
+            builder.max_entries(max, policy);
+        }
+        let mut qt = builder.build().map_err(DecodeError::Invalid)?;
+
+        // Not reserving room up front, since a corrupt count could be arbitrarily large.
+        let count = reader.u64()?;
+        if max_entries.is_some_and(|(max, _)| count > max as u64) {
+            return Err(DecodeError::Invalid("more entries than the maximum".into()));
+        }
+        for _ in 0..count {
+            let anchor = Point {
+                x: reader.coordinate(width)?,
+                y: reader.coordinate(width)?,
+            };
+            let dimensions = (reader.coordinate(width)?, reader.coordinate(width)?);
+            let value = reader.value()?;
+            let region = AreaBuilder::default()
+                .anchor(anchor)
+                .dimensions(dimensions)
+                .build()
+                .map_err(DecodeError::Invalid)?;
+            if qt.insert(region, value).is_none() {
+                return Err(DecodeError::Invalid("entry lies outside the tree".into()));
+            }
+        }
+        if !reader.is_empty() {
+            return Err(DecodeError::Invalid("trailing bytes".into()));
+        }
+        Ok(qt)
+    }
+
+    // The number of bytes one coordinate encodes to.
+    fn coordinate_width() -> u8
+    where
+        U: Codec,
+    {
+        let mut buf = Vec::new();
+        U::zero().encode(&mut buf);
+        buf.len()
+            .try_into()
+            .expect("Coordinates must encode to fewer than 256 bytes.")
     }
 
     /// The top-left corner (anchor) of the region which this quadtree represents.
@@ -249,15 +709,40 @@ where
     }
 
     /// The width of the region which this quadtree represents.
+    ///
+    /// Panics if the width doesn't fit in a `usize`, as for a tree over `u128` coordinates deeper
+    /// than `usize::BITS - 1`. The width of [`.root_region()`] is a `U`, so never overflows.
+    ///
+    /// [`.root_region()`]: #method.root_region
     pub fn width(&self) -> usize {
         self.inner.region().width().to_usize().unwrap()
     }
 
     /// The height of the region which this quadtree represents.
+    ///
+    /// Panics if the height doesn't fit in a `usize`; see [`.width()`].
+    ///
+    /// [`.width()`]: #method.width
     pub fn height(&self) -> usize {
         self.inner.region().height().to_usize().unwrap()
     }
 
+    /// The root node of the quadtree, from which the tree can be walked by hand. See
+    /// [`NodeRef`].
+    ///
+    /// [`NodeRef`]: node/struct.NodeRef.html
+    pub fn root_node(&self) -> NodeRef<'_, U, V> {
+        NodeRef::new(&self.inner, &self.store)
+    }
+
+    /// The region which this quadtree represents. Only regions within it can be inserted; see
+    /// [`.in_bounds()`].
+    ///
+    /// [`.in_bounds()`]: #method.in_bounds
+    pub fn root_region(&self) -> Area<U> {
+        self.inner.region()
+    }
+
     /// The depth of the quadtree.
     pub fn depth(&self) -> usize {
         self.inner.depth()
@@ -273,149 +758,1163 @@ where
         self.store.is_empty()
     }
 
-    /// Whether or not some trial region could fit in the region which this quadtree represents.
-    pub fn contains(&self, area: Area<U>) -> bool {
-        self.inner.region().contains(area)
+    /// The deepest level of the tree which holds any values, counting the root as level zero.
+    ///
+    /// Point-sized regions sink to level [`.depth()`], while larger regions are held higher up.
+    /// An empty tree returns zero.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(3);
+    /// assert_eq!(qt.max_depth(), 0);
+    ///
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap(), 'a');
+    /// assert_eq!(qt.max_depth(), 1);
+    ///
+    /// qt.insert_pt(Point {x: 5, y: 6}, 'b');
+    /// assert_eq!(qt.max_depth(), 3);
+    /// ```
+    ///
+    /// [`.depth()`]: #method.depth
+    pub fn max_depth(&self) -> usize {
+        self.inner
+            .nodes()
+            .filter(|(qt, _)| !qt.handles().is_empty())
+            .map(|(_, level)| level)
+            .max()
+            .unwrap_or(0)
     }
 
-    /// Associate some value with a region in the quadtree.
+    /// The number of nodes in the tree, including empty intermediate ones.
     ///
-    /// If insertion is successful, returns a unique handle to the value.
+    /// Subdividing a node always creates all four of its subquadrants, so this is one more than a
+    /// multiple of four.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
     ///
-    /// If the region is too large for, or doesn't overlap with, the region which this quadtree
-    /// represents, returns `None`.
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// assert_eq!(qt.node_count(), 1);
+    ///
+    /// // Reaching the bottom of a depth-2 tree subdivides twice.
+    /// qt.insert_pt(Point {x: 0, y: 0}, 'a');
+    /// assert_eq!(qt.node_count(), 9);
+    /// assert_eq!(qt.leaf_count(), 7);
     /// ```
-    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    pub fn node_count(&self) -> usize {
+        self.inner.nodes().count()
+    }
+
+    /// The number of nodes in the tree which have no subquadrants.
     ///
-    /// let mut qt = Quadtree::<u32, i8>::new(8);
+    /// See [`.node_count()`].
     ///
-    /// let region = AreaBuilder::default()
-    ///     .anchor(Point {x: 4, y: 5})
-    ///     .dimensions((2,3))
-    ///     .build().unwrap();
+    /// [`.node_count()`]: #method.node_count
+    pub fn leaf_count(&self) -> usize {
+        self.inner
+            .nodes()
+            .filter(|(qt, _)| qt.subquadrants().is_none())
+            .count()
+    }
+
+    /// How many handles are kept at each level of the tree, counting the root as level zero (as in
+    /// [`.max_depth()`]). There is one count for every level the tree could have, down to
+    /// [`.depth()`].
     ///
-    /// let handle_a_1 = qt.insert(region, 5).unwrap();
-    /// let handle_a_2 = qt.insert(region, 5).unwrap();
+    /// A region is kept as deep as it fits, so a lot of handles at the top of the tree means a lot
+    /// of large regions, or regions straddling the boundaries between nodes, all of which every
+    /// query has to check. A region pushed down into several nodes is counted once for each.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
     ///
-    /// // Even though we inserted 5 at the same point in the quadtree, the
-    /// // two handles returned were not the same.
-    /// assert_ne!(handle_a_1, handle_a_2);
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// // Straddles the middle of the tree, so each of the four cells it covers keeps it.
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((1, 1).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap(), 'a');
+    /// qt.insert_pt(Point {x: 3, y: 0}, 'b');
+    ///
+    /// assert_eq!(qt.entries_per_depth(), vec![0, 0, 5]);
     /// ```
-    pub fn insert(&mut self, region: Area<U>, val: V) -> Option<u64> {
-        if self.contains(region) {
-            return Some(
-                self.inner
-                    .insert_val_at_region(region, val, &mut self.store),
-            );
+    ///
+    /// [`.max_depth()`]: #method.max_depth
+    /// [`.depth()`]: #method.depth
+    pub fn entries_per_depth(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.depth() + 1];
+        for (qt, level) in self.inner.nodes() {
+            counts[level] += qt.handles().len();
         }
-        None
+        counts
     }
 
-    /// Alias for [`.insert()`] which expects a [`Point`] instead of an [`Area`].
-    ///
-    /// (An [`Area`] is really just a [`Point`] with dimensions `(1, 1)`, so
-    /// the point still has to fit within the region.)
+    /// The area covered by at least one region in the tree, counting overlaps once, saturating at
+    /// `u64::MAX`.
     ///
+    /// Since the nodes of the tree are disjoint, the covered area of each can be found separately.
+    /// A node with subquadrants which keeps any region is wholly covered, so only the regions kept
+    /// by the same leaf are ever combined with one another. That takes O(k² log k) time for a leaf
+    /// with `k` regions, on top of a walk over the whole tree. For a cheaper (but overcounting)
+    /// figure, see [`.total_entry_area()`].
     /// ```
-    /// use quadtree_rs::{point::Point, Quadtree};
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
     ///
-    /// let mut qt = Quadtree::<u32, i8>::new(2);
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// assert_eq!(qt.coverage_area(), 0);
     ///
-    /// assert!(qt.insert_pt(Point { x: 1, y: 2 }, 5_i8).is_some());
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((1, 1).into())
+    ///     .dimensions((4, 3))
+    ///     .build().unwrap(), 'a');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((3, 2).into())
+    ///     .dimensions((5, 5))
+    ///     .build().unwrap(), 'b');
+    ///
+    /// // 12 + 25, less the 2x2 square where they overlap.
+    /// assert_eq!(qt.coverage_area(), 33);
+    /// assert_eq!(qt.total_entry_area(), 37);
     /// ```
     ///
-    /// [`.insert()`]: #method.insert
-    /// [`Area`]: area/struct.Area.html
-    /// [`Point`]: point/struct.Point.html
-    pub fn insert_pt(&mut self, point: Point<U>, val: V) -> Option<u64> {
-        if let Ok(area) = AreaBuilder::default().anchor(point).build() {
-            return self.insert(area, val);
-        }
-        None
+    /// [`.total_entry_area()`]: #method.total_entry_area
+    pub fn coverage_area(&self) -> u64 {
+        u64::try_from(self.inner.covered_area(&self.store)).unwrap_or(u64::MAX)
     }
 
-    /// Given the handle from an [`.insert()`] operation, provides read-only
-    /// access to the associated [`Entry<U, V>`] struct.
+    /// The sum of the areas of every region in the tree, saturating at `u64::MAX`.
     ///
-    /// Handles are unique and never re-used, so lookup of a handle to a now-deleted entry can
-    /// fail and return `None`.
+    /// Where regions overlap, the overlap is counted once for each of them, so this is never less
+    /// than [`.coverage_area()`] and equal to it only if no two regions overlap. It takes O(n) time
+    /// and doesn't look at the tree at all.
+    ///
+    /// [`.coverage_area()`]: #method.coverage_area
+    pub fn total_entry_area(&self) -> u64 {
+        self.store
+            .values()
+            .map(|entry| {
+                let area = entry.area();
+                area.width()
+                    .to_u128()
+                    .unwrap()
+                    .saturating_mul(area.height().to_u128().unwrap())
+            })
+            .fold(0u64, |total, size| {
+                total.saturating_add(u64::try_from(size).unwrap_or(u64::MAX))
+            })
+    }
+
+    /// The smallest region which contains every region in the tree, or `None` if it's empty.
     ///
+    /// After deletions this can be far smaller than the quadtree itself.
     /// ```
     /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
     ///
-    /// let mut qt = Quadtree::<u32, f32>::new(4);
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// assert!(qt.extent().is_none());
     ///
-    /// let region = AreaBuilder::default()
-    ///     .anchor(Point {x: 0, y: 1})
-    ///     .dimensions((2, 3))
-    ///     .build().unwrap();
-    /// let handle = qt.insert(region, 9.87).unwrap();
+    /// qt.insert_pt(Point {x: 2, y: 9}, 'a');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((5, 3).into())
+    ///     .dimensions((4, 2))
+    ///     .build().unwrap(), 'b');
     ///
-    /// let entry = qt.get(handle).unwrap();
-    /// assert_eq!(entry.value_ref(), &9.87);
+    /// let extent = qt.extent().unwrap();
+    /// assert_eq!(extent.anchor(), Point {x: 2, y: 3});
+    /// assert_eq!(extent.bottom_right(), Point {x: 9, y: 10});
     /// ```
-    ///
-    /// [`.insert()`]: #method.insert
-    /// [`Entry<U, V>`]: entry/struct.Entry.html
-    pub fn get(&self, handle: u64) -> Option<&Entry<U, V>> {
-        self.store.get(&handle)
+    pub fn extent(&self) -> Option<Area<U>> {
+        self.store
+            .values()
+            .map(|entry| entry.area())
+            .fold(None, |extent, area| match extent {
+                Some(extent) => Some(area.union(extent)),
+                None => Some(area),
+            })
     }
 
-    /// A mutable variant of [`.get()`] which provides mutable access to the
-    /// associated [`Entry<U, V>`] struct.
+    /// The mean of the centers of every region in the tree, or `None` if it's empty.
     ///
+    /// Unlike [`Area::center()`], each center is exact rather than rounded down, so a point
+    /// inserted at (1, 1) counts as the middle of its cell, (1.5, 1.5). Takes O(n) time and doesn't
+    /// look at the tree at all.
     /// ```
     /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
     ///
-    /// let mut qt = Quadtree::<u32, f32>::new(4);
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// assert_eq!(qt.centroid(), None);
     ///
-    /// let region = AreaBuilder::default()
-    ///     .anchor(Point {x: 0, y: 1})
-    ///     .dimensions((2, 3))
-    ///     .build().unwrap();
-    /// let handle: u64 = qt.insert(region, 9.87).unwrap();
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((2, 4))
+    ///     .build().unwrap(), 'a');
+    /// qt.insert_pt(Point {x: 8, y: 3}, 'b');
     ///
-    /// if let Some(entry) = qt.get_mut(handle) {
-    ///   *entry.value_mut() += 1.0;
-    /// }
+    /// // The centers are (1, 2) and (8.5, 3.5).
+    /// assert_eq!(qt.centroid(), Some((4.75, 2.75)));
+    /// ```
     ///
-    /// assert_eq!(qt.get(handle).unwrap().value_ref(), &10.87);
+    /// [`Area::center()`]: area/struct.Area.html#method.center
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        self.weighted_centroid(|_| 1.0)
+    }
+
+    /// Like [`.centroid()`], but weights each region's center by `w` of its value.
     ///
+    /// Returns `None` if the tree is empty, or if the weights sum to zero. Weights are expected to
+    /// be nonnegative and finite.
     /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
     ///
-    /// [`.get()`]: #method.get
-    /// [`Entry<U, V>`]: entry/struct.Entry.html
-    pub fn get_mut(&mut self, handle: u64) -> Option<&mut Entry<U, V>> {
-        self.store.get_mut(&handle)
+    /// let mut qt = Quadtree::<u32, f64>::new(4);
+    /// qt.insert_pt(Point {x: 0, y: 0}, 3.0);
+    /// qt.insert_pt(Point {x: 4, y: 8}, 1.0);
+    ///
+    /// assert_eq!(qt.weighted_centroid(|&mass| mass), Some((1.5, 2.5)));
+    /// assert_eq!(qt.weighted_centroid(|_| 0.0), None);
+    /// ```
+    ///
+    /// [`.centroid()`]: #method.centroid
+    pub fn weighted_centroid<F>(&self, w: F) -> Option<(f64, f64)>
+    where
+        F: Fn(&V) -> f64,
+    {
+        let (mut total, mut sum_x, mut sum_y) = (0.0, 0.0, 0.0);
+        for entry in self.store.values() {
+            let weight = w(entry.value_ref());
+            let area = entry.area();
+            let x = area.left_edge().to_f64().unwrap() + area.width().to_f64().unwrap() / 2.0;
+            let y = area.top_edge().to_f64().unwrap() + area.height().to_f64().unwrap() / 2.0;
+            total += weight;
+            sum_x += weight * x;
+            sum_y += weight * y;
+        }
+        if total == 0.0 {
+            return None;
+        }
+        Some((sum_x / total, sum_y / total))
     }
 
-    /// Returns an iterator over [`&Entry<U, V>`] structs representing values
-    /// within the query region.
-    /// ```
-    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    /// Returns an iterator ([`Leaves<U, V>`]) over the leaves of the tree (the nodes without
+    /// subquadrants) which hold any regions, each with the region of the leaf and the
+    /// [`&Entry<U, V>`] structs it holds.
     ///
-    /// //   0123456
-    /// // 0 ░░░░░░░
-    /// // 1 ░░▒▒▒░░    (2,1)->3x2
-    /// // 2 ░░▒▒▒░░
-    /// // 3 ░░░░░░░
-    /// // 4 ░▒▒▒░░░    (1,4)->3x1
-    /// // 5 ░░░░░░░
-    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// This groups entries by the cells the tree has already sorted them into, e.g. for processing
+    /// nearby entries together. A region held by a node with subquadrants, because it covers the
+    /// whole node, isn't in any leaf; a region pushed down into several leaves is in each of them.
+    /// Leaves come in the same order as the nodes of [`.visit_nodes()`].
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
     ///
-    /// let region_a = AreaBuilder::default()
-    ///     .anchor((2, 1).into())
-    ///     .dimensions((3, 2))
-    ///     .build().unwrap();
-    /// qt.insert(region_a, 'a');
+    /// let mut qt = Quadtree::<u32, char>::with_capacity(Point {x: 0, y: 0}, 4, 2);
+    /// qt.extend(vec![((0, 0), 'a'), ((1, 1), 'b'), ((2, 2), 'c'), ((12, 1), 'd')]);
     ///
-    /// let region_b = AreaBuilder::default()
-    ///     .anchor((1, 4).into())
-    ///     .dimensions((3, 1))
-    ///     .build().unwrap();
-    /// qt.insert(region_b, 'b');
+    /// let mut leaves: Vec<(u32, String)> = qt.leaves()
+    ///     .map(|(area, entries)| {
+    ///         let mut values: Vec<char> = entries.iter().map(|e| *e.value_ref()).collect();
+    ///         values.sort();
+    ///         (area.width(), values.into_iter().collect())
+    ///     })
+    ///     .collect();
+    /// leaves.sort();
+    /// assert_eq!(leaves, vec![
+    ///     (2, "ab".to_string()),
+    ///     (2, "c".to_string()),
+    ///     (8, "d".to_string()),
+    /// ]);
+    /// ```
     ///
-    /// //   0123456
+    /// [`Leaves<U, V>`]: iter/struct.Leaves.html
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.visit_nodes()`]: #method.visit_nodes
+    pub fn leaves(&self) -> Leaves<'_, U, V> {
+        Leaves::new(&self.inner, &self.store)
+    }
+
+    /// Returns an iterator ([`Straddling<U, V>`]) over the [`&Entry<U, V>`] structs whose regions
+    /// don't fit in any one leaf of the tree, in arbitrary order.
+    ///
+    /// These are the regions which cross a line the tree has been subdivided along. Each is either
+    /// pushed down into every subquadrant it meets, and so kept at several nodes, or held by a
+    /// node with subquadrants because it covers the whole of it. Either way queries near it have
+    /// more to sift through, so this can help find oversized regions worth splitting. Every other
+    /// entry lies in a single leaf, as seen by [`.leaves()`].
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::with_capacity(Point {x: 0, y: 0}, 4, 1);
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'a');
+    /// qt.insert_pt(Point {x: 12, y: 3}, 'b');
+    /// // Crosses the middle of the tree.
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((6, 6).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap(), 'c');
+    ///
+    /// let straddling: Vec<char> = qt.straddling_entries().map(|e| *e.value_ref()).collect();
+    /// assert_eq!(straddling, vec!['c']);
+    /// ```
+    ///
+    /// [`Straddling<U, V>`]: iter/struct.Straddling.html
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.leaves()`]: #method.leaves
+    pub fn straddling_entries(&self) -> Straddling<'_, U, V> {
+        Straddling::new(&self.inner, &self.store)
+    }
+
+    /// Calls `f` once for every node in the tree, with the node's region, the handles it keeps,
+    /// and its level (counting the root as level zero, as in [`.max_depth()`]).
+    ///
+    /// Nodes are visited in pre-order, and a node's subquadrants in a fixed order, so the same
+    /// tree is always walked the same way. This exposes the tree's skeleton for aggregations which
+    /// would be awkward to build out of queries. A region too large for any one node is kept by
+    /// every node it was pushed down into, so it may be seen more than once; look handles up with
+    /// [`.get()`] to find their regions and values.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap(), 'a');
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'b');
+    ///
+    /// // How many handles are kept at each level.
+    /// let mut per_level = vec![0; qt.depth() + 1];
+    /// qt.visit_nodes(|_, handles, level| per_level[level] += handles.len());
+    /// assert_eq!(per_level, vec![0, 1, 1]);
+    /// ```
+    ///
+    /// [`.max_depth()`]: #method.max_depth
+    /// [`.get()`]: #method.get
+    pub fn visit_nodes<F>(&self, mut f: F)
+    where
+        F: FnMut(&Area<U>, &[u64], usize),
+    {
+        for (qt, level) in self.inner.nodes() {
+            f(&qt.region(), qt.handles(), level);
+        }
+    }
+
+    /// Draws the quadtree as an SVG image, for debugging. Only with the `debug-viz` feature.
+    ///
+    /// Each node is outlined, in a color for its level, and each entry is drawn over the nodes
+    /// as a translucent box covering its region. Hovering over a box shows the entry's handle.
+    /// The image is 512 pixels along its longer side, whatever the size of the tree.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(3);
+    /// qt.insert_pt(Point {x: 1, y: 6}, 'a');
+    ///
+    /// let svg = qt.to_svg();
+    /// assert!(svg.starts_with("<svg"));
+    /// // Write it out, then open it in a browser.
+    /// ```
+    #[cfg(feature = "debug-viz")]
+    pub fn to_svg(&self) -> String {
+        svg::to_svg(&self.inner, &self.store)
+    }
+
+    /// Checks the internal structure of the quadtree, returning a description of the first problem
+    /// found. Only with the `validate` feature.
+    ///
+    /// This is for catching bugs while developing the crate; a quadtree only ever changed through
+    /// its public API should always pass. It checks that:
+    ///   - every handle held by a node is in the store, and every entry in the store is held by
+    ///     some node;
+    ///   - the nodes holding each handle are neither above one another nor overlapping, and between
+    ///     them cover exactly its region (which lies within the tree);
+    ///   - a node with subquadrants only holds regions which cover it entirely;
+    ///   - the subquadrants of each node tile it, one level further down.
+    ///
+    /// It visits every node and entry, so is far too slow to call routinely.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'a');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((3, 3).into())
+    ///     .dimensions((7, 2))
+    ///     .build().unwrap(), 'b');
+    /// assert_eq!(qt.validate(), Ok(()));
+    /// ```
+    #[cfg(any(test, feature = "validate"))]
+    pub fn validate(&self) -> Result<(), String>
+    where
+        U: std::fmt::Debug,
+    {
+        validate::validate(&self.inner, &self.store)
+    }
+
+    /// Counts the entries in each cell of a coarse `cols` by `rows` grid laid over the whole tree,
+    /// e.g. for drawing a heatmap. The counts are returned row by row, so the count for column `c`
+    /// of row `r` is at `[r][c]`.
+    ///
+    /// Each entry is counted once, in the cell which holds the center of its region (rounded down,
+    /// as with [`Area::center()`]). When the tree's width or height doesn't divide evenly, each
+    /// column or row starts at the first coordinate at or past its share of the width or height.
+    /// Cells which hold nothing are zero.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// qt.insert_pt(Point {x: 0, y: 0}, 'a');
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'b');
+    /// qt.insert_pt(Point {x: 3, y: 0}, 'c');
+    /// // Centered at (2, 2).
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((1, 1).into())
+    ///     .dimensions((3, 3))
+    ///     .build().unwrap(), 'd');
+    ///
+    /// assert_eq!(qt.density_grid(2, 2), vec![vec![2, 1], vec![0, 1]]);
+    /// ```
+    ///
+    /// [`Area::center()`]: area/struct.Area.html#method.center
+    pub fn density_grid(&self, cols: usize, rows: usize) -> Vec<Vec<usize>> {
+        let mut grid = vec![vec![0; cols]; rows];
+        if cols == 0 || rows == 0 {
+            return grid;
+        }
+        let region = self.inner.region();
+        // Widened, and scaled by mul_div(), so that scaling an offset by the number of cells can't
+        // overflow.
+        let cell = |offset: U, extent: U, cells: usize| -> usize {
+            let offset = offset.to_u128().unwrap();
+            let extent = extent.to_u128().unwrap();
+            mul_div(offset, cells as u128, extent) as usize
+        };
+        for entry in self.store.values() {
+            let center = entry.area().center();
+            let col = cell(center.x() - region.left_edge(), region.width(), cols);
+            let row = cell(center.y() - region.top_edge(), region.height(), rows);
+            grid[row][col] += 1;
+        }
+        grid
+    }
+
+    /// Samples the value at the center of each cell of a `cols` by `rows` grid laid over the whole
+    /// tree, returning them row by row, so the value for column `c` of row `r` is at
+    /// `[r * cols + c]`.
+    ///
+    /// This is a point lookup (as with [`.get_at_point()`]) at each cell's center, rounded down to
+    /// a coordinate. Cells whose center no region covers get `default`. Where several regions cover
+    /// a center, the one inserted first wins, i.e. the one with the lowest handle.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, f32>::new(2);
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((2, 4))
+    ///     .build().unwrap(), 0.5);
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((0, 2).into())
+    ///     .dimensions((4, 2))
+    ///     .build().unwrap(), 1.0);
+    ///
+    /// // The bottom-left cell is covered by both regions; the first one wins.
+    /// assert_eq!(qt.rasterize(2, 2, 0.0), vec![0.5, 0.0, 0.5, 1.0]);
+    /// ```
+    ///
+    /// [`.get_at_point()`]: #method.get_at_point
+    pub fn rasterize(&self, cols: usize, rows: usize, default: V) -> Vec<V>
+    where
+        V: Clone,
+    {
+        let region = self.inner.region();
+        // The offset of the center of cell @i of @cells from the near edge, widened as in
+        // .density_grid().
+        let center = |i: usize, extent: U, cells: usize| -> U {
+            let extent = extent.to_u128().unwrap();
+            let offset = mul_div(2 * i as u128 + 1, extent, 2 * cells as u128);
+            U::from(offset).expect("Unexpected error in Quadtree::rasterize().")
+        };
+        let mut raster = Vec::with_capacity(cols * rows);
+        for r in 0..rows {
+            let y = region.top_edge() + center(r, region.height(), rows);
+            for c in 0..cols {
+                let x = region.left_edge() + center(c, region.width(), cols);
+                let value = self
+                    .get_at_point(Point { x, y })
+                    .min_by_key(|entry| entry.handle())
+                    .map_or_else(|| default.clone(), |entry| entry.value_ref().clone());
+                raster.push(value);
+            }
+        }
+        raster
+    }
+
+    /// Prunes every subtree which holds nothing.
+    ///
+    /// Deleting entries already does this as it goes: a node whose subquadrants have all been
+    /// emptied drops them, so a tree only ever changed through these methods has no empty subtrees
+    /// for `compact()` to find. A tree which came from elsewhere, such as one deserialized from
+    /// data written by an older version of this crate, can still hold them, and traversals of it
+    /// slow down accordingly. (Unless the tree was created [`.with_capacity()`], pruning leaves it
+    /// just as it would be had only the remaining entries ever been inserted.) Entries, handles,
+    /// and query results are unchanged.
+    ///
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// let handle = qt.insert_pt(Point {x: 0, y: 0}, 'a').unwrap();
+    /// assert_eq!(qt.node_count(), 9);
+    ///
+    /// qt.delete_by_handle(handle);
+    /// assert_eq!(qt.node_count(), 1);
+    ///
+    /// qt.compact();
+    /// assert_eq!(qt.node_count(), 1);
+    /// ```
+    ///
+    /// [`.with_capacity()`]: #method.with_capacity
+    pub fn compact(&mut self) {
+        self.inner.compact();
+    }
+
+    /// Releases memory held onto after deletions.
+    ///
+    /// Like [`.compact()`], this prunes every subtree which no longer holds anything. It also
+    /// shrinks the central store and each node's list of handles down to what they hold. Entries,
+    /// handles, and query results are unchanged.
+    ///
+    /// ```
+    /// use quadtree_rs::Quadtree;
+    ///
+    /// let mut qt = Quadtree::<u32, u32>::new(4);
+    /// qt.extend((0..16).map(|i| ((i, i), i)));
+    /// qt.retain(|_, &v| v < 2);
+    ///
+    /// qt.shrink_to_fit();
+    /// assert_eq!(qt.len(), 2);
+    /// assert!(qt.contains_point((1, 1).into()));
+    /// ```
+    ///
+    /// [`.compact()`]: #method.compact
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.compact();
+        self.inner.shrink_to_fit();
+        self.store.shrink_to_fit();
+    }
+
+    /// Reserves room for at least `additional` more entries, so that inserting them won't
+    /// reallocate the central store of entries.
+    ///
+    /// See [`.new_with_anchor_and_capacity()`].
+    ///
+    /// [`.new_with_anchor_and_capacity()`]: #method.new_with_anchor_and_capacity
+    pub fn reserve(&mut self, additional: usize) {
+        self.store.reserve(additional);
+    }
+
+    /// Whether or not some trial region could fit in the region which this quadtree represents.
+    pub fn contains(&self, area: Area<U>) -> bool {
+        self.inner.region().contains(area)
+    }
+
+    /// Whether or not [`.insert()`] would accept `region`, i.e. whether it lies wholly within the
+    /// [`.root_region()`]. The same as [`.contains()`].
+    ///
+    /// Inserting a region which is out of bounds never panics: [`.insert()`] and
+    /// [`.insert_pt()`] return `None` and drop the value, and [`.extend()`] skips it. Checking
+    /// first lets a caller grow the tree or report the problem instead. (A quadtree made by
+    /// [`.new_growable()`] grows itself, so this only says whether it would need to.)
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u8>::new(2);
+    /// let region = AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((3, 1))
+    ///     .build().unwrap();
+    ///
+    /// // Only partly inside the 4x4 tree.
+    /// assert!(!qt.in_bounds(region));
+    /// assert_eq!(qt.insert(region, 1), None);
+    /// assert!(qt.is_empty());
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    /// [`.insert_pt()`]: #method.insert_pt
+    /// [`.extend()`]: #method.extend
+    /// [`.root_region()`]: #method.root_region
+    /// [`.contains()`]: #method.contains
+    /// [`.new_growable()`]: #method.new_growable
+    pub fn in_bounds(&self, region: Area<U>) -> bool {
+        self.contains(region)
+    }
+
+    /// Associate some value with a region in the quadtree.
+    ///
+    /// If insertion is successful, returns a unique handle to the value. The handle stays valid,
+    /// whatever else is inserted or deleted, until the entry itself is removed; pass it to
+    /// [`.get()`], [`.get_mut()`], or [`.delete_by_handle()`] to reach the entry directly, without
+    /// searching by region. Handles are never reused.
+    ///
+    /// Handles are issued in sequence, starting from zero, and a failed insertion doesn't use one
+    /// up. So the same sequence of operations on a new quadtree always yields the same handles,
+    /// e.g. for logging them or comparing them against a snapshot.
+    ///
+    /// If the region doesn't lie wholly within the region which this quadtree represents, returns
+    /// `None` and drops the value. See [`.in_bounds()`]. (Unless the quadtree was made by
+    /// [`.new_growable()`], in which case it grows to hold the region instead.)
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, i8>::new(8);
+    ///
+    /// let region = AreaBuilder::default()
+    ///     .anchor(Point {x: 4, y: 5})
+    ///     .dimensions((2,3))
+    ///     .build().unwrap();
+    ///
+    /// let handle_a_1 = qt.insert(region, 5).unwrap();
+    /// let handle_a_2 = qt.insert(region, 5).unwrap();
+    ///
+    /// // Even though we inserted 5 at the same point in the quadtree, the
+    /// // two handles returned were not the same.
+    /// assert_ne!(handle_a_1, handle_a_2);
+    /// ```
+    ///
+    /// [`.get()`]: #method.get
+    /// [`.get_mut()`]: #method.get_mut
+    /// [`.delete_by_handle()`]: #method.delete_by_handle
+    /// [`.in_bounds()`]: #method.in_bounds
+    /// [`.new_growable()`]: #method.new_growable
+    pub fn insert(&mut self, region: Area<U>, val: V) -> Option<u64> {
+        self.insert_evicting(region, val).map(|(handle, _)| handle)
+    }
+
+    /// Like [`.insert()`], but also returns the entry which was evicted to make room, if any.
+    ///
+    /// Only a quadtree made by [`.with_max_entries()`] (or a [`QuadtreeBuilder`] with
+    /// [`.max_entries()`] set) ever evicts anything; otherwise the evicted entry is always `None`.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, eviction::EvictionPolicy, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<i32, char>::with_max_entries(
+    ///     Point {x: 0, y: 0}, 4, 3, EvictionPolicy::FarthestFromCentroid);
+    /// qt.extend(vec![((1, 1), 'a'), ((2, 1), 'b'), ((15, 15), 'c')]);
+    ///
+    /// // 'c' is the farthest from the others.
+    /// let region = AreaBuilder::default().anchor((1, 2).into()).build().unwrap();
+    /// let (handle, evicted) = qt.insert_evicting(region, 'd').unwrap();
+    /// assert_eq!(evicted.unwrap().value_ref(), &'c');
+    /// assert_eq!(qt.get(handle).unwrap().value_ref(), &'d');
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    /// [`.with_max_entries()`]: #method.with_max_entries
+    /// [`QuadtreeBuilder`]: builder/struct.QuadtreeBuilder.html
+    /// [`.max_entries()`]: builder/struct.QuadtreeBuilder.html#method.max_entries
+    pub fn insert_evicting(
+        &mut self,
+        region: Area<U>,
+        val: V,
+    ) -> Option<(u64, Option<Entry<U, V>>)> {
+        if self.growable && !self.contains(region) {
+            self.inner.grow_to_contain(region);
+        }
+        if self.contains(region) {
+            let evicted = self.evict_if_full();
+            let handle = self
+                .inner
+                .insert_val_at_region(region, val, &mut self.store);
+            return Some((handle, evicted));
+        }
+        None
+    }
+
+    // Makes room for one more entry if the tree is at its maximum, returning the entry evicted.
+    fn evict_if_full(&mut self) -> Option<Entry<U, V>> {
+        let (max, policy) = self.max_entries?;
+        if self.store.len() < max {
+            return None;
+        }
+        let handle = policy.choose(&self.store)?;
+        self.delete_by_handle(handle)
+    }
+
+    /// Moves every entry of `other` into this quadtree.
+    ///
+    /// The two trees may have different anchors and depths; entries keep their regions, so each
+    /// one must fit within this quadtree's own region. If any of them doesn't, nothing is moved
+    /// and `other` is handed back untouched as the `Err` value. (A quadtree made by
+    /// [`.new_growable()`] grows to hold them instead, and only fails if it would have to grow
+    /// past the bounds of `U`.)
+    ///
+    /// Handles are only unique within the tree which issued them, so merged entries are given
+    /// fresh handles by this quadtree, in the order `other` issued theirs. Handles from `other`
+    /// are not valid afterwards.
+    ///
+    /// If this quadtree has a maximum number of entries (see [`.with_max_entries()`]), entries
+    /// are evicted to make room for the merged ones as they're moved in, one at a time.
+    ///
+    /// ```
+    /// use quadtree_rs::Quadtree;
+    ///
+    /// let mut qt_a = Quadtree::<i32, char>::new(3);
+    /// qt_a.insert_pt((1, 1).into(), 'a');
+    ///
+    /// // A smaller tree, anchored elsewhere, which still fits inside qt_a.
+    /// let mut qt_b = Quadtree::<i32, char>::new_with_anchor((4, 4).into(), 2);
+    /// qt_b.insert_pt((5, 6).into(), 'b');
+    ///
+    /// assert!(qt_a.merge(qt_b).is_ok());
+    /// assert_eq!(qt_a.len(), 2);
+    ///
+    /// // This one doesn't fit, so it comes back.
+    /// let mut qt_c = Quadtree::<i32, char>::new(4);
+    /// qt_c.insert_pt((12, 12).into(), 'c');
+    ///
+    /// let qt_c = qt_a.merge(qt_c).unwrap_err();
+    /// assert_eq!(qt_c.len(), 1);
+    /// assert_eq!(qt_a.len(), 2);
+    /// ```
+    ///
+    /// [`.with_max_entries()`]: #method.with_max_entries
+    /// [`.new_growable()`]: #method.new_growable
+    // Handing back the very tree which was passed in is the point; boxing it would only cost an
+    // allocation.
+    #[allow(clippy::result_large_err)]
+    pub fn merge(&mut self, other: Quadtree<U, V>) -> Result<(), Quadtree<U, V>> {
+        if let Some(extent) = other
+            .store
+            .values()
+            .map(|entry| entry.area())
+            .reduce(|a, b| a.union(b))
+        {
+            let fits =
+                self.contains(extent) || (self.growable && self.inner.grow_to_contain(extent));
+            if !fits {
+                return Err(other);
+            }
+        }
+        // In handle order, so that merging the same trees always issues the same handles.
+        let mut entries: Vec<Entry<U, V>> = other.store.into_values().collect();
+        entries.sort_unstable_by_key(|e| e.handle());
+        for entry in entries {
+            let region = entry.area();
+            self.evict_if_full();
+            self.inner
+                .insert_val_at_region(region, entry.into_value(), &mut self.store);
+        }
+        Ok(())
+    }
+
+    /// Compares this quadtree with `other`, returning the entries added in `other` and the
+    /// entries removed from this one, in that order.
+    ///
+    /// Entries are matched by region and value alone; handles, and the shape of each tree, don't
+    /// count, so the trees needn't share an anchor or depth. Matching treats each tree as a
+    /// multiset: if this quadtree holds three entries with some region and value and `other`
+    /// holds two, one of the three is reported as removed. Copies are paired off in handle order,
+    /// so it's the surplus entries with the highest handles which are reported, and each list
+    /// comes out in handle order.
+    ///
+    /// Both lists hold copies, each with the handle it has in its own tree, so neither tree stays
+    /// borrowed: the removed entries can be deleted from this quadtree by handle, and the added
+    /// ones inserted into it, to bring it up to date with `other`.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut old = Quadtree::<u32, char>::new(4);
+    /// old.extend(vec![((1, 1), 'a'), ((2, 2), 'b'), ((2, 2), 'b')]);
+    /// let mut new = Quadtree::<u32, char>::new(4);
+    /// new.extend(vec![((2, 2), 'b'), ((1, 1), 'a'), ((1, 1), 'c')]);
+    ///
+    /// let (added, removed) = old.diff(&new);
+    /// assert_eq!(added.len(), 1);
+    /// assert_eq!(added[0].value_ref(), &'c');
+    /// assert_eq!(removed.len(), 1);
+    /// assert_eq!(removed[0].anchor(), Point {x: 2, y: 2});
+    ///
+    /// // Applying the changes makes the trees match.
+    /// for entry in removed {
+    ///     old.delete_by_handle(entry.handle());
+    /// }
+    /// for entry in added {
+    ///     old.insert(entry.area(), *entry.value_ref());
+    /// }
+    /// assert!(old == new);
+    /// ```
+    // The pair of lists is spelled out here, where it's documented, rather than hidden behind an
+    // alias.
+    #[allow(clippy::type_complexity)]
+    pub fn diff(&self, other: &Quadtree<U, V>) -> (Vec<Entry<U, V>>, Vec<Entry<U, V>>)
+    where
+        V: PartialEq + Clone,
+    {
+        fn in_handle_order<U: PrimInt + Default, V>(store: &StoreType<U, V>) -> Vec<&Entry<U, V>> {
+            let mut entries: Vec<&Entry<U, V>> = store.values().collect();
+            entries.sort_unstable_by_key(|entry| entry.handle());
+            entries
+        }
+        // This tree's entries not yet paired off, grouped by region.
+        let mut unmatched = BTreeMap::new();
+        let key = |area: Area<U>| {
+            (
+                area.left_edge(),
+                area.top_edge(),
+                area.width(),
+                area.height(),
+            )
+        };
+        for entry in in_handle_order(&self.store) {
+            unmatched
+                .entry(key(entry.area()))
+                .or_insert_with(Vec::new)
+                .push(entry);
+        }
+
+        let mut added = Vec::new();
+        for entry in in_handle_order(&other.store) {
+            let matched = unmatched
+                .get_mut(&key(entry.area()))
+                .and_then(|candidates| {
+                    let i = candidates
+                        .iter()
+                        .position(|old| old.value_ref() == entry.value_ref())?;
+                    Some(candidates.remove(i))
+                });
+            if matched.is_none() {
+                added.push(entry.clone());
+            }
+        }
+        let mut removed: Vec<Entry<U, V>> = unmatched.into_values().flatten().cloned().collect();
+        removed.sort_unstable_by_key(|entry| entry.handle());
+        (added, removed)
+    }
+
+    /// Alias for [`.insert()`] which expects a [`Point`] instead of an [`Area`].
+    ///
+    /// (An [`Area`] is really just a [`Point`] with dimensions `(1, 1)`, so
+    /// the point still has to fit within the region.)
+    ///
+    /// Coordinates name the unit cells of the grid, so the point `(x, y)` _is_ the 1x1 region
+    /// anchored there, not an approximation of it. There are no zero-size regions: since regions
+    /// are half-open, one would intersect nothing, not even itself. Point lookups and distance
+    /// queries treat the entry as the point itself.
+    ///
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, i8>::new(2);
+    ///
+    /// assert!(qt.insert_pt(Point { x: 1, y: 2 }, 5_i8).is_some());
+    ///
+    /// assert_eq!(qt.get_at_point(Point { x: 1, y: 2 }).count(), 1);
+    /// assert_eq!(qt.get_at_point(Point { x: 2, y: 2 }).count(), 0);
+    /// assert_eq!(qt.within_radius(Point { x: 1, y: 2 }, 0).count(), 1);
+    /// assert_eq!(qt.within_radius(Point { x: 3, y: 2 }, 2).count(), 1);
+    /// assert_eq!(qt.within_radius(Point { x: 3, y: 2 }, 1).count(), 0);
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    /// [`Area`]: area/struct.Area.html
+    /// [`Point`]: point/struct.Point.html
+    pub fn insert_pt(&mut self, point: Point<U>, val: V) -> Option<u64> {
+        if let Ok(area) = AreaBuilder::default().anchor(point).build() {
+            return self.insert(area, val);
+        }
+        None
+    }
+
+    /// Replaces the value of the entry at exactly `region`, or inserts one if there isn't one.
+    ///
+    /// If some entry's region is equal to `region` (not merely overlapping or containing it), its
+    /// value is replaced by `val` and the old value is returned. The entry keeps its handle. If
+    /// several entries share that region, the one inserted first (the one with the lowest handle)
+    /// is replaced, and the rest are left alone. So for grid-like data, such as a tile map, this
+    /// sets a tile's value whether or not the tile has one yet.
+    ///
+    /// Otherwise this is just [`.insert()`], and returns `None`. As with [`.insert()`], if
+    /// `region` doesn't fit in the quadtree then `val` is dropped, so check with [`.contains()`]
+    /// first if that matters.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, &str>::new(4);
+    /// let cell = AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    ///
+    /// assert_eq!(qt.upsert(cell, "grass"), None);
+    /// assert_eq!(qt.upsert(cell, "water"), Some("grass"));
+    ///
+    /// assert_eq!(qt.len(), 1);
+    /// assert_eq!(qt.query(cell).next().unwrap().value_ref(), &"water");
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    /// [`.contains()`]: #method.contains
+    pub fn upsert(&mut self, region: Area<U>, val: V) -> Option<V> {
+        let existing = self
+            .query_strict(region)
+            .filter(|e| e.area() == region)
+            .map(|e| e.handle())
+            .min();
+        match existing.and_then(|handle| self.store.get_mut(&handle)) {
+            Some(entry) => Some(std::mem::replace(entry.value_mut(), val)),
+            None => {
+                self.insert(region, val);
+                None
+            }
+        }
+    }
+
+    /// Like [`.insert()`], but says why the value couldn't be inserted.
+    ///
+    /// The region is given by its anchor and dimensions rather than as an [`Area`], since an
+    /// `Area` can't be built from bad ones in the first place. Returns the new entry's handle, or:
+    /// - [`QuadtreeError::ZeroArea`] if the width or height is zero or negative,
+    /// - [`QuadtreeError::Overflow`] if the right or bottom edge doesn't fit in `U`, or
+    /// - [`QuadtreeError::OutOfBounds`] if the region doesn't lie wholly within the quadtree (see
+    ///   [`.in_bounds()`]).
+    ///
+    /// In each case `val` is dropped.
+    /// ```
+    /// use quadtree_rs::{error::QuadtreeError, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u8, char>::new(3);
+    ///
+    /// assert!(qt.try_insert(Point {x: 1, y: 1}, (2, 2), 'a').is_ok());
+    /// assert_eq!(qt.try_insert(Point {x: 1, y: 1}, (0, 2), 'b'), Err(QuadtreeError::ZeroArea));
+    /// assert_eq!(qt.try_insert(Point {x: 250, y: 1}, (9, 1), 'c'), Err(QuadtreeError::Overflow));
+    /// assert_eq!(qt.try_insert(Point {x: 7, y: 7}, (2, 2), 'd'), Err(QuadtreeError::OutOfBounds));
+    /// assert_eq!(qt.len(), 1);
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    /// [`Area`]: area/struct.Area.html
+    /// [`QuadtreeError::ZeroArea`]: error/enum.QuadtreeError.html#variant.ZeroArea
+    /// [`QuadtreeError::Overflow`]: error/enum.QuadtreeError.html#variant.Overflow
+    /// [`QuadtreeError::OutOfBounds`]: error/enum.QuadtreeError.html#variant.OutOfBounds
+    /// [`.in_bounds()`]: #method.in_bounds
+    pub fn try_insert(
+        &mut self,
+        anchor: Point<U>,
+        dimensions: (U, U),
+        val: V,
+    ) -> Result<u64, QuadtreeError> {
+        let (width, height) = dimensions;
+        if width <= U::zero() || height <= U::zero() {
+            return Err(QuadtreeError::ZeroArea);
+        }
+        if anchor.x().checked_add(&width).is_none() || anchor.y().checked_add(&height).is_none() {
+            return Err(QuadtreeError::Overflow);
+        }
+        let region = AreaBuilder::default()
+            .anchor(anchor)
+            .dimensions(dimensions)
+            .build()
+            .expect("Unexpected error in Quadtree::try_insert().");
+        self.insert(region, val).ok_or(QuadtreeError::OutOfBounds)
+    }
+
+    /// Inserts each `(anchor, dimensions, value)` in turn, and returns the new entries' handles in
+    /// the same order.
+    ///
+    /// Like [`.extend()`], but for regions, and keeping the handles. An item which
+    /// [`.try_insert()`] would refuse is skipped and its value dropped, so the result has one
+    /// handle per item actually inserted, and may be shorter than the input.
+    /// ```
+    /// use quadtree_rs::Quadtree;
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// let handles = qt.insert_batch(vec![
+    ///     ((0, 0), (2, 2), 'a'),
+    ///     ((3, 3), (0, 1), 'b'),
+    ///     ((14, 14), (2, 2), 'c'),
+    /// ]);
+    ///
+    /// // 'b' has no width, so isn't inserted.
+    /// assert_eq!(handles.len(), 2);
+    /// assert_eq!(qt.get(handles[0]).unwrap().value_ref(), &'a');
+    /// assert_eq!(qt.get(handles[1]).unwrap().value_ref(), &'c');
+    /// ```
+    ///
+    /// [`.extend()`]: #method.extend
+    /// [`.try_insert()`]: #method.try_insert
+    pub fn insert_batch<I>(&mut self, items: I) -> Vec<u64>
+    where
+        I: IntoIterator<Item = ((U, U), (U, U), V)>,
+    {
+        items
+            .into_iter()
+            .filter_map(|((x, y), dimensions, val)| {
+                self.try_insert(Point { x, y }, dimensions, val).ok()
+            })
+            .collect()
+    }
+
+    /// Inserts `val` at `region`, unless an entry with exactly that region and an equal value is
+    /// already there.
+    ///
+    /// Returns whether the value was added. If it wasn't, because there was already such an entry
+    /// or because `region` doesn't fit in the quadtree, `val` is dropped. Entries which only
+    /// overlap `region`, or hold a different value, don't count. Unlike [`.insert()`], no handle
+    /// is returned; find the entry by region if one is needed.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, &str>::new(4);
+    /// let cell = AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    ///
+    /// assert!(qt.insert_dedup(cell, "grass"));
+    /// assert!(!qt.insert_dedup(cell, "grass"));
+    /// assert!(qt.insert_dedup(cell, "water"));
+    /// assert_eq!(qt.len(), 2);
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    pub fn insert_dedup(&mut self, region: Area<U>, val: V) -> bool
+    where
+        V: PartialEq,
+    {
+        let duplicate = self
+            .query_strict(region)
+            .any(|e| e.area() == region && *e.value_ref() == val);
+        !duplicate && self.insert(region, val).is_some()
+    }
+
+    /// Given the handle from an [`.insert()`] operation, provides read-only
+    /// access to the associated [`Entry<U, V>`] struct.
+    ///
+    /// Handles are unique and never re-used, so lookup of a handle to a now-deleted entry can
+    /// fail and return `None`.
+    ///
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, f32>::new(4);
+    ///
+    /// let region = AreaBuilder::default()
+    ///     .anchor(Point {x: 0, y: 1})
+    ///     .dimensions((2, 3))
+    ///     .build().unwrap();
+    /// let handle = qt.insert(region, 9.87).unwrap();
+    ///
+    /// let entry = qt.get(handle).unwrap();
+    /// assert_eq!(entry.value_ref(), &9.87);
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    /// [`Entry<U, V>`]: entry/struct.Entry.html
+    pub fn get(&self, handle: u64) -> Option<&Entry<U, V>> {
+        self.store.get(&handle)
+    }
+
+    /// A mutable variant of [`.get()`] which provides mutable access to the
+    /// associated [`Entry<U, V>`] struct.
+    ///
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, f32>::new(4);
+    ///
+    /// let region = AreaBuilder::default()
+    ///     .anchor(Point {x: 0, y: 1})
+    ///     .dimensions((2, 3))
+    ///     .build().unwrap();
+    /// let handle: u64 = qt.insert(region, 9.87).unwrap();
+    ///
+    /// if let Some(entry) = qt.get_mut(handle) {
+    ///   *entry.value_mut() += 1.0;
+    /// }
+    ///
+    /// assert_eq!(qt.get(handle).unwrap().value_ref(), &10.87);
+    ///
+    /// ```
+    ///
+    /// [`.get()`]: #method.get
+    /// [`Entry<U, V>`]: entry/struct.Entry.html
+    pub fn get_mut(&mut self, handle: u64) -> Option<&mut Entry<U, V>> {
+        self.store.get_mut(&handle)
+    }
+
+    /// Swaps the values of the entries with handles `a` and `b`, leaving their regions (and
+    /// handles) where they were. Returns `false`, changing nothing, if either handle isn't in the
+    /// quadtree.
+    ///
+    /// Swapping an entry with itself does nothing, and returns `true`.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// let a = qt.insert_pt(Point {x: 1, y: 1}, 'a').unwrap();
+    /// let b = qt.insert_pt(Point {x: 5, y: 5}, 'b').unwrap();
+    ///
+    /// assert!(qt.swap_values(a, b));
+    /// assert_eq!(qt.get(a).unwrap().value_ref(), &'b');
+    /// assert_eq!(qt.get(b).unwrap().value_ref(), &'a');
+    /// assert_eq!(qt.get(a).unwrap().anchor(), Point {x: 1, y: 1});
+    ///
+    /// qt.delete_by_handle(b);
+    /// assert!(!qt.swap_values(a, b));
+    /// ```
+    pub fn swap_values(&mut self, a: u64, b: u64) -> bool {
+        if !self.store.contains_key(&a) || !self.store.contains_key(&b) {
+            return false;
+        }
+        if a == b {
+            return true;
+        }
+        // Only one entry can be borrowed mutably from the store at a time, so @a is taken out
+        // while the values are swapped.
+        let mut first = self
+            .store
+            .remove(&a)
+            .expect("Unexpected error in Quadtree::swap_values().");
+        let second = self
+            .store
+            .get_mut(&b)
+            .expect("Unexpected error in Quadtree::swap_values().");
+        std::mem::swap(first.value_mut(), second.value_mut());
+        self.store.insert(a, first);
+        true
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs representing values
+    /// within the query region.
+    ///
+    /// Since an [`Area`] always has a positive width and height, so does the query region. To
+    /// query along a line or at a single grid point, see [`.query_touching()`].
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// //   0123456
+    /// // 0 ░░░░░░░
+    /// // 1 ░░▒▒▒░░    (2,1)->3x2
+    /// // 2 ░░▒▒▒░░
+    /// // 3 ░░░░░░░
+    /// // 4 ░▒▒▒░░░    (1,4)->3x1
+    /// // 5 ░░░░░░░
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    ///
+    /// let region_a = AreaBuilder::default()
+    ///     .anchor((2, 1).into())
+    ///     .dimensions((3, 2))
+    ///     .build().unwrap();
+    /// qt.insert(region_a, 'a');
+    ///
+    /// let region_b = AreaBuilder::default()
+    ///     .anchor((1, 4).into())
+    ///     .dimensions((3, 1))
+    ///     .build().unwrap();
+    /// qt.insert(region_b, 'b');
+    ///
+    /// //   0123456
     /// // 0 ░░░░░░░
     /// // 1 ░░▓▒▒░░  <-- Query over the region
     /// // 2 ░░▒▒▒░░      (2,1)->1x1
@@ -426,49 +1925,1158 @@ where
     ///     .anchor((2, 1).into()).build().unwrap();
     /// let mut query_a = qt.query(region_c);
     ///
-    /// // We can use the Entry API to destructure the result.
-    /// let entry = query_a.next().unwrap();
-    /// assert_eq!(entry.area().height(), 2);
-    /// assert_eq!(entry.value_ref(), &'a');
+    /// // We can use the Entry API to destructure the result.
+    /// let entry = query_a.next().unwrap();
+    /// assert_eq!(entry.area().height(), 2);
+    /// assert_eq!(entry.value_ref(), &'a');
+    ///
+    /// // But that was the only result.
+    /// assert!(query_a.next().is_none());
+    ///
+    /// //   0123456
+    /// // 0 ░░░░░░░
+    /// // 1 ░▒▓▓▓▒░  <-- query over the region
+    /// // 2 ░▒▓▓▓▒░      (0,0)->6x6.
+    /// // 3 ░▒▒▒▒▒░
+    /// // 4 ░▓▓▓▒▒░
+    /// // 5 ░░░░░░░
+    /// let region_d = AreaBuilder::default()
+    ///     .anchor((1, 1).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap();
+    /// let query_b = qt.query(region_d);
+    ///
+    /// // It's unspecified what order the regions should
+    /// // return in, but there will be two of them.
+    /// assert_eq!(query_b.count(), 2);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`Area`]: area/struct.Area.html
+    /// [`.query()`]: #method.query
+    /// [`.query_touching()`]: #method.query_touching
+    // TODO(ambuc): Settle on a stable return order to avoid breaking callers.
+    pub fn query(&self, area: Area<U>) -> Query<'_, U, V> {
+        Query::new(area, &self.inner, &self.store, Traversal::Overlapping)
+    }
+
+    /// A strict variant of [`.query()`].
+    ///
+    /// Only returns entries whose regions lie wholly within `area`. A region exactly equal to
+    /// `area` counts as contained; one which pokes outside it, even by a single cell, is skipped.
+    ///
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(3);
+    ///
+    /// let inside = AreaBuilder::default()
+    ///     .anchor((1, 1).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// let straddling = AreaBuilder::default()
+    ///     .anchor((3, 3).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// qt.insert(inside, 'a');
+    /// qt.insert(straddling, 'b');
+    ///
+    /// let query = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap();
+    ///
+    /// assert_eq!(qt.query(query).count(), 2);
+    ///
+    /// let mut strict = qt.query_strict(query);
+    /// assert_eq!(strict.next().unwrap().value_ref(), &'a');
+    /// assert!(strict.next().is_none());
+    /// ```
+    ///
+    /// [`.query()`]: #method.query
+    pub fn query_strict(&self, area: Area<U>) -> Query<'_, U, V> {
+        Query::new(area, &self.inner, &self.store, Traversal::Strict)
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions touch the rectangle at
+    /// `anchor` with the given `(width, height)`, including along its edges.
+    ///
+    /// Unlike an [`Area`], the rectangle may have a width or height of zero: `(x, y)` with
+    /// dimensions `(0, 0)` is the grid point at the corner of up to four cells, and `(x, y)` with
+    /// dimensions `(0, h)` is a vertical line along the left edges of the cells from `(x, y)` down.
+    /// Every region which touches the rectangle, even only at a corner, is returned. A rectangle
+    /// with a negative width or height is malformed, and matches nothing.
+    ///
+    /// For a rectangle with a positive width and height, this is [`.query()`] with the region
+    /// grown by one cell on each side.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<i32, char>::new(3);
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'a');
+    /// qt.insert_pt(Point {x: 2, y: 2}, 'b');
+    /// qt.insert_pt(Point {x: 2, y: 4}, 'c');
+    ///
+    /// // The corner shared by 'a' and 'b'.
+    /// let mut at_corner: Vec<char> = qt.query_touching(Point {x: 2, y: 2}, (0, 0))
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// at_corner.sort();
+    /// assert_eq!(at_corner, vec!['a', 'b']);
+    ///
+    /// // The line down the left side of 'b' and 'c'.
+    /// assert_eq!(qt.query_touching(Point {x: 2, y: 3}, (0, 2)).count(), 2);
+    ///
+    /// assert_eq!(qt.query_touching(Point {x: 2, y: 2}, (-1, 0)).count(), 0);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`Area`]: area/struct.Area.html
+    /// [`.query()`]: #method.query
+    pub fn query_touching(&self, anchor: Point<U>, dimensions: (U, U)) -> Touching<'_, U, V> {
+        Touching::new(anchor, dimensions, &self.inner, &self.store)
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions are adjacent to `area`:
+    /// they share part of an edge with it, but don't overlap it (as in [`Area::touches()`]).
+    ///
+    /// Regions which only meet `area` at a corner aren't adjacent to it, so aren't returned. To
+    /// include them, keep the regions from [`.query_touching()`] which don't intersect `area`.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(3);
+    /// let region = AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// qt.insert(region, 'a');
+    /// qt.insert_pt(Point {x: 4, y: 3}, 'b');
+    /// // Only the corner.
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'c');
+    /// // Overlapping.
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'd');
+    ///
+    /// let adjacent: Vec<char> = qt.adjacent_to(region).map(|e| *e.value_ref()).collect();
+    /// assert_eq!(adjacent, vec!['b']);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`Area::touches()`]: area/struct.Area.html#method.touches
+    /// [`.query_touching()`]: #method.query_touching
+    pub fn adjacent_to(&self, area: Area<U>) -> AdjacentTo<'_, U, V> {
+        AdjacentTo::new(area, &self.inner, &self.store)
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions overlap `area`, and whose
+    /// values satisfy `pred`.
+    ///
+    /// The same as filtering the results of [`.query()`], with `pred` run once on each entry the
+    /// query finds. The tree is pruned by `area` alone, so a predicate which rejects everything
+    /// costs no more than the query itself.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u32>::new(4);
+    /// qt.extend((0..16).map(|i| ((i, i), i)));
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((8, 8))
+    ///     .build().unwrap();
+    ///
+    /// let mut evens: Vec<u32> = qt.query_filter(area, |&v| v % 2 == 0)
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// evens.sort();
+    /// assert_eq!(evens, vec![0, 2, 4, 6]);
+    ///
+    /// assert!(qt.query_filter(area, |_| false).next().is_none());
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.query()`]: #method.query
+    pub fn query_filter<F>(&self, area: Area<U>, pred: F) -> QueryFilter<'_, U, V, F>
+    where
+        F: Fn(&V) -> bool,
+    {
+        QueryFilter::new(self.query(area), pred)
+    }
+
+    /// A strict variant of [`.query_filter()`].
+    ///
+    /// [`.query_filter()`]: #method.query_filter
+    pub fn query_filter_strict<F>(&self, area: Area<U>, pred: F) -> QueryFilter<'_, U, V, F>
+    where
+        F: Fn(&V) -> bool,
+    {
+        QueryFilter::new(self.query_strict(area), pred)
+    }
+
+    /// Collects the [`&Entry<U, V>`] structs whose regions overlap `area` into a `Vec`, sorted by
+    /// `cmp`.
+    ///
+    /// The same as collecting the results of [`.query()`] and sorting them, but in one call. The
+    /// sort is stable over handle order, so entries which `cmp` considers equal come out in the
+    /// order they were inserted. If nothing overlaps `area`, the `Vec` is empty.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    /// use std::cmp::Ordering;
+    ///
+    /// let mut qt = Quadtree::<u32, &str>::new(4);
+    /// qt.extend(vec![((1, 1), "gold"), ((6, 2), "ash"), ((3, 5), "iron"), ((12, 12), "lead")]);
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((8, 8))
+    ///     .build().unwrap();
+    ///
+    /// let by_name = qt.query_sorted_by(area, |a, b| a.value_ref().cmp(b.value_ref()));
+    /// let names: Vec<&str> = by_name.iter().map(|e| *e.value_ref()).collect();
+    /// assert_eq!(names, vec!["ash", "gold", "iron"]);
+    ///
+    /// // Bottom to top.
+    /// let by_row = qt.query_sorted_by(area, |a, b| b.anchor().y().cmp(&a.anchor().y()));
+    /// assert_eq!(by_row[0].value_ref(), &"iron");
+    ///
+    /// assert!(qt.query_sorted_by(area.scaled(0.25), |_, _| Ordering::Equal).is_empty());
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.query()`]: #method.query
+    pub fn query_sorted_by<F>(&self, area: Area<U>, cmp: F) -> Vec<&Entry<U, V>>
+    where
+        F: FnMut(&&Entry<U, V>, &&Entry<U, V>) -> Ordering,
+    {
+        Self::sorted_by(self.query(area), cmp)
+    }
+
+    /// A strict variant of [`.query_sorted_by()`].
+    ///
+    /// [`.query_sorted_by()`]: #method.query_sorted_by
+    pub fn query_sorted_by_strict<F>(&self, area: Area<U>, cmp: F) -> Vec<&Entry<U, V>>
+    where
+        F: FnMut(&&Entry<U, V>, &&Entry<U, V>) -> Ordering,
+    {
+        Self::sorted_by(self.query_strict(area), cmp)
+    }
+
+    // Collects @entries in handle order, then stably sorts them by @cmp.
+    fn sorted_by<'a, F>(entries: Query<'a, U, V>, mut cmp: F) -> Vec<&'a Entry<U, V>>
+    where
+        F: FnMut(&&Entry<U, V>, &&Entry<U, V>) -> Ordering,
+    {
+        let mut entries: Vec<&Entry<U, V>> = entries.collect();
+        entries.sort_unstable_by_key(|e| e.handle());
+        entries.sort_by(|a, b| cmp(a, b));
+        entries
+    }
+
+    /// Like [`.query()`], but spread across threads with `rayon`. Only with the `rayon` feature.
+    ///
+    /// Yields the same entries as [`.query()`], in no particular order. The first few levels of
+    /// the tree beneath the root are each searched as a separate task, so this pays off for
+    /// large trees and large query regions.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let mut qt = Quadtree::<u32, u32>::new(6);
+    /// qt.extend((0..64).map(|i| ((i, i), i)));
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((32, 32))
+    ///     .build().unwrap();
+    /// let sum: u32 = qt.par_query(area).map(|e| *e.value_ref()).sum();
+    /// assert_eq!(sum, (0..32).sum());
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.query()`]: #method.query
+    #[cfg(feature = "rayon")]
+    pub fn par_query(
+        &self,
+        area: Area<U>,
+    ) -> impl rayon::iter::ParallelIterator<Item = &Entry<U, V>> + '_
+    where
+        U: Send + Sync,
+        V: Sync,
+    {
+        par_query::par_query(&self.inner, &self.store, area, Traversal::Overlapping)
+    }
+
+    /// A strict variant of [`.par_query()`]. Only with the `rayon` feature.
+    ///
+    /// [`.par_query()`]: #method.par_query
+    #[cfg(feature = "rayon")]
+    pub fn par_query_strict(
+        &self,
+        area: Area<U>,
+    ) -> impl rayon::iter::ParallelIterator<Item = &Entry<U, V>> + '_
+    where
+        U: Send + Sync,
+        V: Sync,
+    {
+        par_query::par_query(&self.inner, &self.store, area, Traversal::Strict)
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions overlap `area`, each paired
+    /// with the [`Area`] where the two overlap.
+    ///
+    /// Yields the same entries as [`.query()`]. An entry which lies wholly within `area` is
+    /// paired with its own region; one which straddles the edge of `area` is paired with only the
+    /// part inside it, which is useful for weighting results by how much of them was covered.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// let region = AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap();
+    /// qt.insert(region, 'a');
+    ///
+    /// let query = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap();
+    /// let (entry, clipped) = qt.query_clipped(query).next().unwrap();
+    /// assert_eq!(entry.area(), region);
+    /// assert_eq!(clipped.anchor(), (2, 2).into());
+    /// assert_eq!((clipped.width(), clipped.height()), (2, 2));
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`Area`]: area/struct.Area.html
+    /// [`.query()`]: #method.query
+    pub fn query_clipped(&self, area: Area<U>) -> QueryClipped<'_, U, V> {
+        QueryClipped::new(self.query(area))
+    }
+
+    /// A strict variant of [`.query_clipped()`].
+    ///
+    /// Since every entry found lies wholly within `area`, each is paired with its own region.
+    ///
+    /// [`.query_clipped()`]: #method.query_clipped
+    pub fn query_clipped_strict(&self, area: Area<U>) -> QueryClipped<'_, U, V> {
+        QueryClipped::new(self.query_strict(area))
+    }
+
+    /// The number of entries which [`.query()`] would return for `area`.
+    ///
+    /// Walks the same part of the tree as the query does, but only counts what it finds.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u8>::new(4);
+    /// qt.extend(vec![((0, 0), 1), ((1, 1), 2), ((9, 9), 3)]);
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// assert_eq!(qt.count_in_region(area), 2);
+    /// ```
+    ///
+    /// [`.query()`]: #method.query
+    pub fn count_in_region(&self, area: Area<U>) -> usize {
+        self.query(area).count()
+    }
+
+    /// A strict variant of [`.count_in_region()`].
+    ///
+    /// [`.count_in_region()`]: #method.count_in_region
+    pub fn count_in_region_strict(&self, area: Area<U>) -> usize {
+        self.query_strict(area).count()
+    }
+
+    /// Any one [`&Entry<U, V>`] whose region overlaps `area`, or `None` if there isn't one.
+    ///
+    /// Cheaper than taking the first result of a [`.query()`]: the search stops at the first
+    /// overlapping entry it comes across, and allocates nothing. Which entry that is, when there
+    /// are several, is unspecified.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((3, 3))
+    ///     .build().unwrap(), 'a');
+    ///
+    /// let near = AreaBuilder::default()
+    ///     .anchor((4, 4).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// assert_eq!(qt.any_in_region(near).unwrap().value_ref(), &'a');
+    ///
+    /// let far = AreaBuilder::default()
+    ///     .anchor((5, 5).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// assert!(qt.any_in_region(far).is_none());
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.query()`]: #method.query
+    pub fn any_in_region(&self, area: Area<U>) -> Option<&Entry<U, V>> {
+        self.inner
+            .find_handle_intersecting(area, &self.store)
+            .and_then(|handle| self.store.get(&handle))
+    }
+
+    /// Calls `f` on each [`&Entry<U, V>`] whose region intersects `area`, until `f` returns
+    /// [`ControlFlow::Break`].
+    ///
+    /// The same entries are visited as by [`.query()`], each exactly once, but the walk over the
+    /// tree is driven from inside: as soon as `f` breaks, the descent stops, and nodes not yet
+    /// reached are never visited. The result is the `Break` value, or `Continue(())` if `f` never
+    /// broke. Nothing is allocated along the way.
+    /// ```
+    /// use quadtree_rs::Quadtree;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let mut qt = Quadtree::<u32, u32>::new(4);
+    /// qt.extend((0..16).map(|i| ((i, i), i)));
+    ///
+    /// let everything = qt.root_region();
+    /// let mut seen = 0;
+    /// let found = qt.try_visit(everything, |e| {
+    ///     seen += 1;
+    ///     if *e.value_ref() % 5 == 4 {
+    ///         ControlFlow::Break(e.anchor())
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    /// assert!(matches!(found, ControlFlow::Break(_)));
+    /// assert!(seen < qt.len());
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`ControlFlow::Break`]: https://doc.rust-lang.org/std/ops/enum.ControlFlow.html
+    /// [`.query()`]: #method.query
+    pub fn try_visit<B, F>(&self, area: Area<U>, mut f: F) -> ControlFlow<B>
+    where
+        F: FnMut(&Entry<U, V>) -> ControlFlow<B>,
+    {
+        self.inner
+            .try_visit(area, Traversal::Overlapping, &self.store, &mut f)
+    }
+
+    /// A strict variant of [`.try_visit()`], which only visits entries whose regions lie wholly
+    /// within `area`, as in [`.query_strict()`].
+    ///
+    /// [`.try_visit()`]: #method.try_visit
+    /// [`.query_strict()`]: #method.query_strict
+    pub fn try_visit_strict<B, F>(&self, area: Area<U>, mut f: F) -> ControlFlow<B>
+    where
+        F: FnMut(&Entry<U, V>) -> ControlFlow<B>,
+    {
+        self.inner
+            .try_visit(area, Traversal::Strict, &self.store, &mut f)
+    }
+
+    /// Moves the entry with the given handle to a new region, in place.
+    ///
+    /// The value is neither cloned nor moved, and the handle stays the same. Returns `false`, and
+    /// changes nothing, if there's no such entry or if the new region doesn't fit in the quadtree.
+    /// (A quadtree made by [`.new_growable()`] grows to hold the new region instead, and only
+    /// fails if it would have to grow past the bounds of `U`.)
+    ///
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, String>::new(4);
+    /// let handle = qt.insert_pt(Point {x: 1, y: 1}, "particle".to_string()).unwrap();
+    ///
+    /// let region = AreaBuilder::default()
+    ///     .anchor(Point {x: 12, y: 9})
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// assert!(qt.relocate(handle, region));
+    ///
+    /// assert_eq!(qt.get(handle).unwrap().area(), region);
+    /// assert_eq!(qt.query(region).next().unwrap().value_ref(), "particle");
+    /// assert!(!qt.contains_point(Point {x: 1, y: 1}));
+    /// ```
+    ///
+    /// [`.new_growable()`]: #method.new_growable
+    pub fn relocate(&mut self, handle: u64, region: Area<U>) -> bool {
+        if !self.store.contains_key(&handle) {
+            return false;
+        }
+        let fits = self.contains(region) || (self.growable && self.inner.grow_to_contain(region));
+        if !fits {
+            return false;
+        }
+        let old_region = match self.store.get_mut(&handle) {
+            Some(entry) => {
+                let old_region = entry.area();
+                entry.set_area(region);
+                old_region
+            }
+            None => return false,
+        };
+        self.inner
+            .relocate_handle(handle, old_region, region, &mut self.store);
+        true
+    }
+
+    /// Replaces every entry's region with `f` of it, then rebuilds the tree around the new
+    /// regions.
+    ///
+    /// Values stay where they are in the central store, neither cloned nor moved, and every handle
+    /// stays the same. `f` is called once per entry, in handle order.
+    ///
+    /// Nothing is dropped: if any new region falls outside the [`.root_region()`], this fails
+    /// with [`QuadtreeError::OutOfBounds`] and changes nothing. (A quadtree made by
+    /// [`.new_growable()`] grows to hold the new regions instead, and only fails if it would have
+    /// to grow past the bounds of `U`.)
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, error::QuadtreeError, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// let a = qt.insert_pt(Point {x: 1, y: 1}, 'a').unwrap();
+    /// let b = qt.insert_pt(Point {x: 2, y: 5}, 'b').unwrap();
+    ///
+    /// // Doubles every region, about the origin.
+    /// let scale = |region: quadtree_rs::area::Area<u32>| AreaBuilder::default()
+    ///     .anchor(Point {x: region.left_edge() * 2, y: region.top_edge() * 2})
+    ///     .dimensions((region.width() * 2, region.height() * 2))
+    ///     .build().unwrap();
+    /// qt.remap_regions(scale).unwrap();
+    /// assert_eq!(qt.get(a).unwrap().anchor(), Point {x: 2, y: 2});
+    /// assert_eq!(qt.get(b).unwrap().anchor(), Point {x: 4, y: 10});
+    /// assert!(qt.contains_point(Point {x: 5, y: 11}));
+    ///
+    /// // 'b' would now land outside the 16x16 tree.
+    /// assert_eq!(qt.remap_regions(scale), Err(QuadtreeError::OutOfBounds));
+    /// assert_eq!(qt.get(b).unwrap().anchor(), Point {x: 4, y: 10});
+    /// ```
+    ///
+    /// [`.root_region()`]: #method.root_region
+    /// [`QuadtreeError::OutOfBounds`]: error/enum.QuadtreeError.html#variant.OutOfBounds
+    /// [`.new_growable()`]: #method.new_growable
+    pub fn remap_regions<F>(&mut self, mut f: F) -> Result<(), QuadtreeError>
+    where
+        F: FnMut(Area<U>) -> Area<U>,
+    {
+        let mut handles: Vec<u64> = self.store.keys().copied().collect();
+        handles.sort_unstable();
+        let remapped: Vec<(u64, Area<U>)> = handles
+            .into_iter()
+            .map(|handle| (handle, f(self.store[&handle].area())))
+            .collect();
+
+        if let Some(extent) = remapped
+            .iter()
+            .map(|&(_, region)| region)
+            .reduce(|a, b| a.union(b))
+        {
+            let fits =
+                self.contains(extent) || (self.growable && self.inner.grow_to_contain(extent));
+            if !fits {
+                return Err(QuadtreeError::OutOfBounds);
+            }
+        }
+
+        for (handle, region) in remapped {
+            if let Some(entry) = self.store.get_mut(&handle) {
+                entry.set_area(region);
+            }
+        }
+        self.inner.rebuild(&mut self.store);
+        Ok(())
+    }
+
+    /// Shifts the whole quadtree, and every region in it, by `(dx, dy)`.
+    ///
+    /// This moves the tree as it stands, without rebuilding it: the anchor and every node and
+    /// entry move together, so the shape of the tree and every handle stay the same, and a query
+    /// shifted by the same amount finds the same entries. Fails with
+    /// [`QuadtreeError::Overflow`], changing nothing, if the tree's edges would be shifted past
+    /// the range of `U`.
+    /// ```
+    /// use quadtree_rs::{error::QuadtreeError, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<i32, char>::new(4);
+    /// let handle = qt.insert_pt(Point {x: 3, y: 5}, 'a').unwrap();
+    ///
+    /// qt.translate(-8, 100).unwrap();
+    /// assert_eq!(qt.anchor(), Point {x: -8, y: 100});
+    /// assert_eq!(qt.get(handle).unwrap().anchor(), Point {x: -5, y: 105});
+    /// assert!(qt.contains_point(Point {x: -5, y: 105}));
+    ///
+    /// assert_eq!(qt.translate(i32::MAX, 0), Err(QuadtreeError::Overflow));
+    /// assert_eq!(qt.anchor(), Point {x: -8, y: 100});
+    /// ```
+    ///
+    /// [`QuadtreeError::Overflow`]: error/enum.QuadtreeError.html#variant.Overflow
+    pub fn translate(&mut self, dx: U, dy: U) -> Result<(), QuadtreeError> {
+        let region = self.inner.region();
+        let shifted = |pt: Point<U>| pt.x().checked_add(&dx).and(pt.y().checked_add(&dy));
+        if shifted(region.top_left()).is_none() || shifted(region.bottom_right()).is_none() {
+            return Err(QuadtreeError::Overflow);
+        }
+        let by = Point { x: dx, y: dy };
+        self.inner.translate(by);
+        for entry in self.store.values_mut() {
+            entry.set_area(entry.area().translated(by));
+        }
+        Ok(())
+    }
+
+    /// Returns up to `k` [`&Entry<U, V>`] structs, sorted by their distance from `pt`.
+    ///
+    /// Distance is measured from `pt` to the closest point of each entry's region, so an entry
+    /// whose region covers `pt` is at distance zero. Entries at equal distances are returned in an
+    /// unspecified order.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    ///
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'a');
+    /// qt.insert_pt(Point {x: 8, y: 8}, 'b');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((4, 0).into())
+    ///     .dimensions((2, 6))
+    ///     .build().unwrap(), 'c');
+    ///
+    /// let nearest: Vec<char> = qt.nearest_neighbors(Point {x: 3, y: 2}, 2)
+    ///     .iter()
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// assert_eq!(nearest, vec!['c', 'a']);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    pub fn nearest_neighbors(&self, pt: Point<U>, k: usize) -> Vec<&Entry<U, V>> {
+        NeighborIter::new(&self.inner, &self.store, |area| Some(area.distance_sq(pt)))
+            .take(k)
+            .collect()
+    }
+
+    /// Like [`.nearest_neighbors()`], but with distances measured by `dist` instead.
+    ///
+    /// `dist` is given two points and returns the distance between them; [`metric`] has the usual
+    /// ones. The distance to a region is `dist` from `pt` to the point of the region closest to
+    /// `pt` along each axis, which is what the search uses to rule out whole nodes at once. So for
+    /// the results to be right, `dist(pt, q)` must never grow as `q` moves towards `pt` along
+    /// either axis. Every metric in [`metric`] satisfies this. (A wrap-around distance doesn't;
+    /// search from each wrapped copy of `pt` instead, and merge the results.)
+    ///
+    /// Distances are compared as `f64`, so ones beyond 2^53 may tie when they shouldn't.
+    /// ```
+    /// use quadtree_rs::{metric, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'a');
+    /// qt.insert_pt(Point {x: 5, y: 0}, 'b');
+    ///
+    /// let origin = Point {x: 0, y: 0};
+    /// // 'a' is 6 away and 'b' is 5 away by taxicab, but the other way around by chessboard.
+    /// let nearest = |dist: fn(Point<u32>, Point<u32>) -> u64| {
+    ///     *qt.nearest_neighbors_by(origin, 1, dist)[0].value_ref()
+    /// };
+    /// assert_eq!(nearest(metric::manhattan), 'b');
+    /// assert_eq!(nearest(metric::chebyshev), 'a');
+    /// ```
+    ///
+    /// [`.nearest_neighbors()`]: #method.nearest_neighbors
+    /// [`metric`]: metric/index.html
+    pub fn nearest_neighbors_by<D>(&self, pt: Point<U>, k: usize, dist: D) -> Vec<&Entry<U, V>>
+    where
+        D: Fn(Point<U>, Point<U>) -> u64,
+    {
+        NeighborIter::new(&self.inner, &self.store, |area| {
+            Some(dist(pt, area.closest_pt(pt)) as f64)
+        })
+        .take(k)
+        .collect()
+    }
+
+    /// The [`&Entry<U, V>`] nearest to `pt`, or `None` if the quadtree is empty.
+    ///
+    /// Distance is measured as for [`.nearest_neighbors()`], and the search stops as soon as the
+    /// nearest entry is known. If several entries are equally near, which one is returned depends
+    /// only on the shape and contents of the tree, so asking again gives the same answer.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// assert!(qt.closest(Point {x: 3, y: 2}).is_none());
+    ///
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'a');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((4, 0).into())
+    ///     .dimensions((2, 6))
+    ///     .build().unwrap(), 'c');
+    ///
+    /// assert_eq!(qt.closest(Point {x: 3, y: 2}).unwrap().value_ref(), &'c');
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.nearest_neighbors()`]: #method.nearest_neighbors
+    pub fn closest(&self, pt: Point<U>) -> Option<&Entry<U, V>> {
+        NeighborIter::new(&self.inner, &self.store, |area| Some(area.distance_sq(pt))).next()
+    }
+
+    /// The [`&Entry<U, V>`] farthest from `pt`, or `None` if the quadtree is empty. The mirror
+    /// image of [`.closest()`].
+    ///
+    /// Distance is measured as for [`.nearest_neighbors()`], to the closest point of each entry's
+    /// region, so this finds the entry which is hardest to reach from `pt`. The search starts from
+    /// the nodes whose farthest corners are farthest from `pt`, and stops once no node left could
+    /// hold anything farther. Ties are resolved as for [`.closest()`].
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// assert!(qt.furthest(Point {x: 3, y: 2}).is_none());
+    ///
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'a');
+    /// qt.insert_pt(Point {x: 9, y: 7}, 'b');
+    /// // Reaches further than 'b' does, but its closest point is nearer.
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((4, 0).into())
+    ///     .dimensions((12, 16))
+    ///     .build().unwrap(), 'c');
+    ///
+    /// assert_eq!(qt.furthest(Point {x: 3, y: 2}).unwrap().value_ref(), &'b');
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.closest()`]: #method.closest
+    /// [`.nearest_neighbors()`]: #method.nearest_neighbors
+    pub fn furthest(&self, pt: Point<U>) -> Option<&Entry<U, V>> {
+        // Negated, so that the farthest comes first. A node reaches no further than its farthest
+        // point, so that bounds how far every region beneath it can be.
+        NeighborIter::with_bound(
+            &self.inner,
+            &self.store,
+            |area| Some(-area.distance_sq(pt)),
+            |area| Some(-area.farthest_distance_sq(pt)),
+        )
+        .next()
+    }
+
+    /// Returns an iterator over every [`&Entry<U, V>`] in the quadtree, in nondecreasing order of
+    /// distance from `pt`.
+    ///
+    /// Distance is measured as for [`.nearest_neighbors()`], which takes a fixed number of entries
+    /// from the front of this. The search only goes as far as it needs to for each entry it yields,
+    /// so it's cheap to stop early, e.g. at the first entry satisfying some condition. Each entry
+    /// is yielded exactly once.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
     ///
-    /// // But that was the only result.
-    /// assert!(query_a.next().is_none());
+    /// let mut qt = Quadtree::<u32, u32>::new(4);
+    /// qt.insert_pt(Point {x: 1, y: 1}, 7);
+    /// qt.insert_pt(Point {x: 5, y: 5}, 12);
+    /// qt.insert_pt(Point {x: 9, y: 9}, 20);
     ///
-    /// //   0123456
-    /// // 0 ░░░░░░░
-    /// // 1 ░▒▓▓▓▒░  <-- query over the region
-    /// // 2 ░▒▓▓▓▒░      (0,0)->6x6.
-    /// // 3 ░▒▒▒▒▒░
-    /// // 4 ░▓▓▓▒▒░
-    /// // 5 ░░░░░░░
-    /// let region_d = AreaBuilder::default()
-    ///     .anchor((1, 1).into())
-    ///     .dimensions((4, 4))
-    ///     .build().unwrap();
-    /// let query_b = qt.query(region_d);
+    /// // The nearest even value.
+    /// let even = qt.neighbors_iter(Point {x: 0, y: 0})
+    ///     .find(|e| e.value_ref() % 2 == 0)
+    ///     .unwrap();
+    /// assert_eq!(even.value_ref(), &12);
+    /// ```
     ///
-    /// // It's unspecified what order the regions should
-    /// // return in, but there will be two of them.
-    /// assert_eq!(query_b.count(), 2);
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.nearest_neighbors()`]: #method.nearest_neighbors
+    pub fn neighbors_iter(&self, pt: Point<U>) -> Neighbors<'_, U, V> {
+        Neighbors::new(pt, &self.inner, &self.store)
+    }
+
+    /// Casts a ray from `origin` in the direction `dir`, and returns the [`&Entry<U, V>`] whose
+    /// region the ray enters first.
+    ///
+    /// The ray starts from the center of the cell at `origin`, so an entry whose region covers
+    /// `origin` is hit immediately. Subquadrants are searched in the order the ray crosses them,
+    /// so the search stops as soon as it finds a hit. Regions which the ray only grazes at a
+    /// corner are not hit. If two regions are entered at the same point, either may be returned.
+    ///
+    /// Returns `None` if the ray hits nothing, or if `dir` is `(0, 0)`.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// //   01234567
+    /// // 0 ........
+    /// // 1 .o..a...
+    /// // 2 ........
+    /// // 3 ...bbb..
+    /// let mut qt = Quadtree::<u32, char>::new(3);
+    /// qt.insert_pt(Point {x: 4, y: 1}, 'a');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((3, 3).into())
+    ///     .dimensions((3, 1))
+    ///     .build().unwrap(), 'b');
+    ///
+    /// let origin = Point {x: 1, y: 1};
+    /// assert_eq!(qt.raycast(origin, (1, 0)).unwrap().value_ref(), &'a');
+    /// assert_eq!(qt.raycast(origin, (1, 1)).unwrap().value_ref(), &'b');
+    /// assert!(qt.raycast(origin, (-1, 0)).is_none());
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    pub fn raycast(&self, origin: Point<U>, dir: (i32, i32)) -> Option<&Entry<U, V>> {
+        if dir == (0, 0) {
+            return None;
+        }
+        NeighborIter::new(&self.inner, &self.store, |area| area.ray_entry(origin, dir)).next()
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions lie within `radius` of
+    /// `center`.
+    ///
+    /// As with [`.nearest_neighbors()`], distance is measured from `center` to the closest point of
+    /// each entry's region. Regions which only touch the edge of the circle are included.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    ///
+    /// qt.insert_pt(Point {x: 5, y: 5}, 'a');
+    /// qt.insert_pt(Point {x: 8, y: 8}, 'b');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((0, 2).into())
+    ///     .dimensions((2, 6))
+    ///     .build().unwrap(), 'c');
+    ///
+    /// // 'c' is exactly 4 away, on the edge of the circle. 'b' is sqrt(18) away, outside it.
+    /// let mut found: Vec<char> = qt.within_radius(Point {x: 5, y: 5}, 4)
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// found.sort();
+    /// assert_eq!(found, vec!['a', 'c']);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.nearest_neighbors()`]: #method.nearest_neighbors
+    pub fn within_radius(&self, center: Point<U>, radius: U) -> WithinRadius<'_, U, V> {
+        WithinRadius::new(center, radius, &self.inner, &self.store)
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions reach into the ring around
+    /// `center` between the radii `inner` and `outer`, inclusive.
+    ///
+    /// These are the regions found by [`.within_radius()`] with the outer radius, less those lying
+    /// wholly closer to `center` than `inner`: a region is only left out if every one of its cells
+    /// is less than `inner` away. So with an `inner` of zero this is exactly
+    /// `.within_radius(center, outer)`. If `inner` is greater than `outer`, the ring is empty.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert_pt(Point {x: 5, y: 5}, 'a');
+    /// qt.insert_pt(Point {x: 8, y: 5}, 'b');
+    /// qt.insert_pt(Point {x: 12, y: 5}, 'c');
+    /// // Reaches from 1 to 4 away.
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((5, 6).into())
+    ///     .dimensions((1, 4))
+    ///     .build().unwrap(), 'd');
+    ///
+    /// let mut found: Vec<char> = qt.within_ring(Point {x: 5, y: 5}, 2, 4)
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// found.sort();
+    /// assert_eq!(found, vec!['b', 'd']);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.within_radius()`]: #method.within_radius
+    pub fn within_ring(&self, center: Point<U>, inner: U, outer: U) -> WithinRing<'_, U, V> {
+        WithinRing::new(center, inner, outer, &self.inner, &self.store)
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions lie within `radius` of
+    /// `center`, as measured by `dist`, nearest first.
+    ///
+    /// Distances are measured as for [`.nearest_neighbors_by()`], which has the same requirements
+    /// on `dist`. Regions exactly `radius` away are included.
+    /// ```
+    /// use quadtree_rs::{metric, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'a');
+    /// qt.insert_pt(Point {x: 5, y: 0}, 'b');
+    ///
+    /// // A 9x9 square around the origin.
+    /// let found: Vec<char> = qt.within_radius_by(Point {x: 0, y: 0}, 4, metric::chebyshev)
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// assert_eq!(found, vec!['a']);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.nearest_neighbors_by()`]: #method.nearest_neighbors_by
+    pub fn within_radius_by<'a, D>(
+        &'a self,
+        center: Point<U>,
+        radius: u64,
+        dist: D,
+    ) -> Neighbors<'a, U, V>
+    where
+        D: Fn(Point<U>, Point<U>) -> u64 + 'a,
+    {
+        Neighbors::with_distance(
+            &self.inner,
+            &self.store,
+            Box::new(move |area: Area<U>| {
+                let d = dist(center, area.closest_pt(center));
+                if d <= radius {
+                    Some(d as f64)
+                } else {
+                    None
+                }
+            }),
+        )
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions overlap the polygon with
+    /// vertices `verts`, taken in order around its boundary.
+    ///
+    /// Vertices sit on the same grid lines as the edges of regions, so a point-sized entry at
+    /// `(x, y)` fills the square from `(x, y)` to `(x + 1, y + 1)`. As with [`.query()`], regions
+    /// which only touch the polygon's boundary don't overlap it. The polygon may be convex or
+    /// concave, but shouldn't cross itself. With fewer than three vertices there's no polygon, and
+    /// nothing is returned.
+    ///
+    /// Candidates are first found with a query over the polygon's bounding box, and then each is
+    /// checked against the polygon itself.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert_pt(Point {x: 0, y: 0}, 'a');
+    /// qt.insert_pt(Point {x: 1, y: 2}, 'b');
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'c');
+    ///
+    /// // The triangle covers 'a' and 'b', but stops short of 'c'.
+    /// let triangle = [(0, 0), (4, 0), (0, 4)];
+    ///
+    /// let mut found: Vec<char> = qt.query_polygon(&triangle)
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// found.sort();
+    /// assert_eq!(found, vec!['a', 'b']);
     /// ```
     ///
     /// [`&Entry<U, V>`]: entry/struct.Entry.html
     /// [`.query()`]: #method.query
-    // TODO(ambuc): Settle on a stable return order to avoid breaking callers.
-    pub fn query(&self, area: Area<U>) -> Query<U, V> {
-        Query::new(area, &self.inner, &self.store, Traversal::Overlapping)
+    pub fn query_polygon(&self, verts: &[(U, U)]) -> QueryPolygon<'_, U, V> {
+        QueryPolygon::new(verts, &self.inner, &self.store, Traversal::Overlapping)
     }
 
-    /// A strict variant of [`.query()`].
+    /// A strict variant of [`.query_polygon()`], which only returns regions lying wholly within
+    /// the polygon.
+    ///
+    /// [`.query_polygon()`]: #method.query_polygon
+    pub fn query_polygon_strict(&self, verts: &[(U, U)]) -> QueryPolygon<'_, U, V> {
+        QueryPolygon::new(verts, &self.inner, &self.store, Traversal::Strict)
+    }
+
+    /// Returns an iterator over [`&Entry<U, V>`] structs whose regions overlap the half-plane on
+    /// `side` of `threshold` along `axis`.
+    ///
+    /// For everything right of `x = 100`, say, where there's no far edge to bound a query region.
+    /// Regions straddling the boundary are included. As with regions, the boundary is half-open:
+    /// the cells at `threshold` itself lie on the [`Side::GreaterOrEqual`] side, so a region
+    /// whose right edge is `threshold` doesn't reach into it. The half-plane is clipped to the
+    /// tree and queried as a region, so subtrees wholly on the other side are never visited.
+    /// ```
+    /// use quadtree_rs::{
+    ///     halfplane::{Axis, Side},
+    ///     Quadtree,
+    /// };
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.extend(vec![((1, 1), 'a'), ((5, 9), 'b'), ((6, 2), 'c'), ((12, 12), 'd')]);
+    ///
+    /// let mut right: Vec<char> = qt.query_halfplane(Axis::X, 6, Side::GreaterOrEqual)
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// right.sort();
+    /// assert_eq!(right, vec!['c', 'd']);
+    ///
+    /// let mut above: Vec<char> = qt.query_halfplane(Axis::Y, 9, Side::Less)
+    ///     .map(|e| *e.value_ref())
+    ///     .collect();
+    /// above.sort();
+    /// assert_eq!(above, vec!['a', 'c']);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`Side::GreaterOrEqual`]: halfplane/enum.Side.html#variant.GreaterOrEqual
+    pub fn query_halfplane(
+        &self,
+        axis: Axis,
+        threshold: U,
+        side: Side,
+    ) -> QueryHalfPlane<'_, U, V> {
+        QueryHalfPlane::new(axis, threshold, side, &self.inner, &self.store)
+    }
+
+    /// Whether or not any value is associated with a region covering `pt`.
     ///
+    /// Not to be confused with [`.contains()`], which checks whether a region would fit in the
+    /// quadtree. This is cheaper than running a [`.query()`], since it only descends through the
+    /// nodes which contain `pt` and stops at the first match. Points outside the quadtree are
+    /// never covered.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((3, 3))
+    ///     .build().unwrap(), 'a');
+    ///
+    /// assert!(qt.contains_point(Point {x: 4, y: 3}));
+    /// assert!(!qt.contains_point(Point {x: 5, y: 3}));
+    /// assert!(!qt.contains_point(Point {x: 40, y: 40}));
+    /// ```
+    ///
+    /// [`.contains()`]: #method.contains
     /// [`.query()`]: #method.query
-    pub fn query_strict(&self, area: Area<U>) -> Query<U, V> {
-        Query::new(area, &self.inner, &self.store, Traversal::Strict)
+    pub fn contains_point(&self, pt: Point<U>) -> bool {
+        self.get_at_point(pt).next().is_some()
+    }
+
+    /// Whether or not every point of `region` is covered by some entry's region.
+    ///
+    /// The entries may overlap each other, and may reach past `region`; all that matters is that
+    /// together they leave no gap inside it. Only the nodes which `region` intersects are looked
+    /// at, and the search stops at the first gap. A node with subquadrants keeps only regions which
+    /// cover it entirely, so any one of them settles that node at once; the regions kept by each
+    /// leaf are swept across to check that they tile its part of `region` between them. A region
+    /// reaching outside the quadtree is never fully covered, and neither is any region of an empty
+    /// quadtree. (An [`Area`] always covers at least one point, since one with a zero width or
+    /// height can't be built, so there is no empty region to ask about.)
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// let region = |x, y, w, h| AreaBuilder::default()
+    ///     .anchor((x, y).into())
+    ///     .dimensions((w, h))
+    ///     .build().unwrap();
+    /// qt.insert(region(0, 0, 5, 3), 'a');
+    /// qt.insert(region(2, 2, 3, 4), 'b');
+    ///
+    /// assert!(qt.region_fully_covered(region(2, 1, 3, 5)));
+    /// // The cell at (1, 3) is in neither.
+    /// assert!(!qt.region_fully_covered(region(1, 1, 4, 4)));
+    /// ```
+    ///
+    /// [`Area`]: area/struct.Area.html
+    pub fn region_fully_covered(&self, region: Area<U>) -> bool {
+        self.contains(region) && self.inner.covers(region, &self.store)
+    }
+
+    /// Returns an iterator over every [`&Entry<U, V>`] whose region covers `pt`.
+    ///
+    /// Like [`.contains_point()`], this only descends through the nodes which contain `pt`. Each
+    /// covering entry is returned exactly once, in an unspecified order. Points outside the
+    /// quadtree are never covered.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((8, 8))
+    ///     .build().unwrap(), 'a');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((3, 3))
+    ///     .build().unwrap(), 'b');
+    ///
+    /// assert_eq!(qt.get_at_point(Point {x: 3, y: 3}).count(), 2);
+    /// assert_eq!(qt.get_at_point(Point {x: 6, y: 6}).count(), 1);
+    /// assert_eq!(qt.get_at_point(Point {x: 9, y: 9}).count(), 0);
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.contains_point()`]: #method.contains_point
+    pub fn get_at_point(&self, pt: Point<U>) -> AtPoint<'_, U, V> {
+        AtPoint::new(pt, &self.inner, &self.store)
+    }
+
+    /// Returns an iterator ([`OverlappingPairs<U, V>`]) over every pair of [`&Entry<U, V>`]
+    /// structs whose regions overlap, for broad-phase collision detection.
+    ///
+    /// Each overlapping pair is yielded exactly once, in no particular order within the pair.
+    /// Entries are only compared with those kept by the same node or by the nodes above it, so
+    /// entries in separate parts of the tree are never compared at all. Regions which merely touch
+    /// don't overlap.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// for (anchor, value) in vec![((0, 0), 'a'), ((2, 2), 'b'), ((3, 3), 'c'), ((9, 9), 'd')] {
+    ///     qt.insert(AreaBuilder::default()
+    ///         .anchor(anchor.into())
+    ///         .dimensions((3, 3))
+    ///         .build().unwrap(), value);
+    /// }
+    ///
+    /// let mut pairs: Vec<(char, char)> = qt.overlapping_pairs()
+    ///     .map(|(x, y)| {
+    ///         let (x, y) = (*x.value_ref(), *y.value_ref());
+    ///         (x.min(y), x.max(y))
+    ///     })
+    ///     .collect();
+    /// pairs.sort();
+    /// assert_eq!(pairs, vec![('a', 'b'), ('b', 'c')]);
+    /// ```
+    ///
+    /// [`OverlappingPairs<U, V>`]: iter/struct.OverlappingPairs.html
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    pub fn overlapping_pairs(&self) -> OverlappingPairs<'_, U, V> {
+        OverlappingPairs::new(&self.inner, &self.store)
+    }
+
+    /// Whether any entry's region in this quadtree overlaps any entry's region in `other`.
+    ///
+    /// Like [`.overlapping_pairs()`], but between two trees, and stopping at the first overlap
+    /// found. The trees are walked together, and only pairs of nodes whose regions intersect are
+    /// looked into, so trees which lie apart, or only meet at their edges, are told apart in a
+    /// handful of steps. They need not share an anchor or depth, or hold the same kind of value.
+    /// Regions which merely touch don't overlap.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut walls = Quadtree::<u32, char>::new(5);
+    /// walls.insert(AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((32, 2))
+    ///     .build().unwrap(), '#');
+    ///
+    /// let mut players = Quadtree::<u32, u8>::new(5);
+    /// players.insert_pt((4, 2).into(), 1);
+    /// assert!(!walls.intersects(&players));
+    ///
+    /// players.insert_pt((20, 1).into(), 2);
+    /// assert!(walls.intersects(&players));
+    /// ```
+    ///
+    /// [`.overlapping_pairs()`]: #method.overlapping_pairs
+    pub fn intersects<W>(&self, other: &Quadtree<U, W>) -> bool {
+        self.inner
+            .intersects_tree(&other.inner, &self.store, &other.store)
     }
 
     /// Accepts a modification lambda and applies it to all elements in the
     /// quadtree which intersecting the described region.
     ///
+    /// Only the part of the tree which `area` overlaps is searched, as for [`.query()`], and the
+    /// lambda is run exactly once on each value found. Values outside `area` are never touched.
+    ///
     /// ```
     /// use quadtree_rs::{area::AreaBuilder, Quadtree};
     ///
@@ -484,12 +3092,22 @@ where
     ///
     /// // ...and verify that the value was applied.
     /// assert_eq!(qt.get(handle).unwrap().value_ref(), &false);
+    ///
+    /// // The lambda may keep state of its own.
+    /// let mut calls = 0;
+    /// qt.modify(region_a, |i| {
+    ///     calls += 1;
+    ///     *i = !*i;
+    /// });
+    /// assert_eq!(calls, 1);
     /// ```
+    ///
+    /// [`.query()`]: #method.query
     pub fn modify<F>(&mut self, area: Area<U>, f: F)
     where
-        F: Fn(&mut V) + Copy,
+        F: FnMut(&mut V),
     {
-        self.modify_region(|a| a.intersects(area), f);
+        self.modify_region(area, Traversal::Overlapping, f);
     }
 
     /// A strict variant of [`.modify()`].
@@ -497,18 +3115,18 @@ where
     /// [`.modify()`]: #method.modify
     pub fn modify_strict<F>(&mut self, area: Area<U>, f: F)
     where
-        F: Fn(&mut V) + Copy,
+        F: FnMut(&mut V),
     {
-        self.modify_region(|a| area.contains(a), f);
+        self.modify_region(area, Traversal::Strict, f);
     }
 
     /// Alias for [`.modify()`] which runs over the entire
     /// quadtree.
     ///
     /// [`.modify()`]: #method.modify
-    pub fn modify_all<F>(&mut self, f: F)
+    pub fn modify_all<F>(&mut self, mut f: F)
     where
-        F: Fn(&mut V) + Copy,
+        F: FnMut(&mut V),
     {
         for entry in self.store.values_mut() {
             f(entry.value_mut());
@@ -568,9 +3186,227 @@ where
 
     /// A strict variant of [`.delete()`].
     ///
-    /// [`.delete()`]: #method.delete
-    pub fn delete_strict(&mut self, area: Area<U>) -> IntoIter<U, V> {
-        self.delete_handles_and_return(self.query_strict(area).map(|e| e.handle()).collect())
+    /// Only deletes the entries whose regions lie wholly within `area`. Regions which straddle its
+    /// boundary are left in the tree.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.insert_pt((1, 1).into(), 'a');
+    /// qt.insert(AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap(), 'b');
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap();
+    ///
+    /// let deleted: Vec<char> = qt.delete_strict(area).map(|e| *e.value_ref()).collect();
+    /// assert_eq!(deleted, vec!['a']);
+    /// assert_eq!(qt.len(), 1);
+    /// ```
+    ///
+    /// [`.delete()`]: #method.delete
+    pub fn delete_strict(&mut self, area: Area<U>) -> IntoIter<U, V> {
+        self.delete_handles_and_return(self.query_strict(area).map(|e| e.handle()).collect())
+    }
+
+    /// Deletes all value associations which overlap a region in the tree, and returns them all at
+    /// once, in handle order (i.e. the order they were inserted in).
+    ///
+    /// This is [`.delete()`] collected into a `Vec`, so the number of entries removed is known
+    /// straight away, e.g. for sizing a buffer to hand them on to.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// qt.extend(vec![((5, 5), 'a'), ((1, 1), 'b'), ((9, 9), 'c')]);
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((8, 8))
+    ///     .build().unwrap();
+    ///
+    /// let taken = qt.take_region(area);
+    /// assert_eq!(taken.len(), 2);
+    /// assert_eq!(*taken[0].value_ref(), 'a');
+    /// assert_eq!(*taken[1].value_ref(), 'b');
+    /// assert_eq!(qt.len(), 1);
+    /// ```
+    ///
+    /// [`.delete()`]: #method.delete
+    pub fn take_region(&mut self, area: Area<U>) -> Vec<Entry<U, V>> {
+        Self::in_handle_order(self.delete(area))
+    }
+
+    /// A strict variant of [`.take_region()`], which only takes the entries whose regions lie
+    /// wholly within `area`, as with [`.delete_strict()`].
+    ///
+    /// [`.take_region()`]: #method.take_region
+    /// [`.delete_strict()`]: #method.delete_strict
+    pub fn take_region_strict(&mut self, area: Area<U>) -> Vec<Entry<U, V>> {
+        Self::in_handle_order(self.delete_strict(area))
+    }
+
+    // The entries removed by .delete() or .delete_strict(), sorted by handle.
+    fn in_handle_order(deleted: IntoIter<U, V>) -> Vec<Entry<U, V>> {
+        let mut entries = deleted.entries;
+        entries.sort_unstable_by_key(|entry| entry.handle());
+        entries
+    }
+
+    /// Deletes all value associations which overlap a region in the tree, and drops them.
+    ///
+    /// Like [`.delete()`], but without collecting the removed entries for the caller, so prefer
+    /// this whenever they would just be thrown away.
+    ///
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, String>::new(4);
+    /// qt.extend(vec![((0, 0), "a".to_string()), ((1, 1), "b".to_string()), ((5, 5), "c".to_string())]);
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    /// qt.clear_region(area);
+    ///
+    /// assert_eq!(qt.len(), 1);
+    /// assert_eq!(qt.query(area).count(), 0);
+    /// ```
+    ///
+    /// [`.delete()`]: #method.delete
+    pub fn clear_region(&mut self, area: Area<U>) {
+        let handles: Vec<u64> = self.query(area).map(|e| e.handle()).collect();
+        self.remove_many(handles);
+    }
+
+    /// A strict variant of [`.clear_region()`].
+    ///
+    /// [`.clear_region()`]: #method.clear_region
+    pub fn clear_region_strict(&mut self, area: Area<U>) {
+        let handles: Vec<u64> = self.query_strict(area).map(|e| e.handle()).collect();
+        self.remove_many(handles);
+    }
+
+    /// Lazily deletes every region/value association which intersects `area`.
+    ///
+    /// Returns a [`DrainRegion<U, V>`] iterator which removes one [`Entry<U, V>`] from the tree
+    /// each time it's advanced, and yields it. Unlike [`.delete()`], which removes every match up
+    /// front, dropping the iterator partway through leaves the matches it didn't reach in the
+    /// tree. Which entries match is decided when `.drain_region()` is called.
+    ///
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u8>::new(4);
+    /// qt.extend(vec![((0, 0), 1), ((1, 1), 2), ((2, 2), 3), ((9, 9), 4)]);
+    ///
+    /// let area = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((4, 4))
+    ///     .build().unwrap();
+    ///
+    /// // Take one of the three matches, and stop.
+    /// let removed = qt.drain_region(area).next().unwrap();
+    /// assert!(removed.value_ref() <= &3);
+    ///
+    /// assert_eq!(qt.len(), 3);
+    /// assert_eq!(qt.query(area).count(), 2);
+    /// ```
+    ///
+    /// [`DrainRegion<U, V>`]: iter/struct.DrainRegion.html
+    /// [`Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.delete()`]: #method.delete
+    pub fn drain_region(&mut self, area: Area<U>) -> DrainRegion<'_, U, V> {
+        DrainRegion {
+            handles: self.query(area).map(|e| e.handle()).collect(),
+            qt: self,
+        }
+    }
+
+    /// Moves every entry whose region overlaps `area` out of this quadtree, and into a new one
+    /// with the same anchor, depth and node capacity, which is returned.
+    ///
+    /// Entries which straddle the edge of `area` count as overlapping it, so they move too, just
+    /// as [`.delete()`] would remove them. Everything else stays behind, and keeps its handle.
+    /// The moved entries are given fresh handles by the new quadtree, so their old handles are
+    /// not valid in either one afterwards.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u32>::new(4);
+    /// qt.extend((0..16).map(|i| ((i, i), i)));
+    ///
+    /// let west = AreaBuilder::default()
+    ///     .anchor((0, 0).into())
+    ///     .dimensions((8, 16))
+    ///     .build().unwrap();
+    /// let west_qt = qt.split_off_region(west);
+    ///
+    /// assert_eq!((west_qt.len(), qt.len()), (8, 8));
+    /// assert_eq!(west_qt.anchor(), qt.anchor());
+    /// assert!(qt.values().all(|&v| v >= 8));
+    /// ```
+    ///
+    /// [`.delete()`]: #method.delete
+    pub fn split_off_region(&mut self, area: Area<U>) -> Self {
+        let mut other =
+            Self::with_capacity(self.anchor(), self.depth(), self.inner.node_capacity());
+        other.growable = self.growable;
+        other.max_entries = self.max_entries;
+
+        let mut entries: Vec<Entry<U, V>> = self.delete(area).collect();
+        // In handle order, so that the entries keep their relative order in the new tree.
+        entries.sort_unstable_by_key(|e| e.handle());
+        for entry in entries {
+            other
+                .inner
+                .insert_val_at_region(entry.area(), entry.into_value(), &mut other.store);
+        }
+        other
+    }
+
+    /// Copies the quadtree, giving every entry in the copy a fresh handle.
+    ///
+    /// The copy has the same regions and values as the original, so the two compare equal, but
+    /// none of the handles the original has issued so far resolve in the copy: the copy's handles
+    /// pick up where the original's left off. (Handles the original issues later may still
+    /// coincide with the copy's.) Entries are copied in handle order, so that their relative
+    /// order carries over.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// let handle = qt.insert_pt(Point {x: 1, y: 1}, 'a').unwrap();
+    ///
+    /// let copy = qt.clone_with_new_handles();
+    /// assert_eq!(copy, qt);
+    /// assert!(copy.get(handle).is_none());
+    /// ```
+    pub fn clone_with_new_handles(&self) -> Self
+    where
+        V: Clone,
+    {
+        let mut other =
+            Self::with_capacity(self.anchor(), self.depth(), self.inner.node_capacity());
+        other.growable = self.growable;
+        other.max_entries = self.max_entries;
+        other.inner.set_handle_counter(self.inner.handle_counter());
+
+        let mut entries: Vec<&Entry<U, V>> = self.store.values().collect();
+        entries.sort_unstable_by_key(|e| e.handle());
+        for entry in entries {
+            other.inner.insert_val_at_region(
+                entry.area(),
+                entry.value_ref().clone(),
+                &mut other.store,
+            );
+        }
+        other
     }
 
     #[allow(clippy::needless_pass_by_value)]
@@ -582,7 +3418,10 @@ where
         handles.iter().for_each(|u| {
             // We were just passed a hashset of handles taken from this quadtree, so it is safe to
             // assume they all still exist.
-            entries.push(self.store.remove(u).expect(error));
+            let entry = self.store.remove(u).expect(error);
+            // Clear the handle out of the tree too, so that later traversals don't trip over it.
+            self.inner.delete_by_handle(*u, entry.area());
+            entries.push(entry);
         });
 
         IntoIter { entries }
@@ -593,6 +3432,29 @@ where
     /// `delete_by_handle()` returns an `Entry<U, V>`
     /// containing its former region and value. Otherwise,
     /// returns `None`.
+    ///
+    /// Unlike [`.delete()`], this removes exactly one entry even when several share a region.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// let region = AreaBuilder::default()
+    ///     .anchor((1, 1).into())
+    ///     .dimensions((2, 2))
+    ///     .build().unwrap();
+    ///
+    /// let handle_a = qt.insert(region, 'a').unwrap();
+    /// let handle_b = qt.insert(region, 'b').unwrap();
+    ///
+    /// assert_eq!(qt.delete_by_handle(handle_a).unwrap().value_ref(), &'a');
+    /// assert!(qt.delete_by_handle(handle_a).is_none());
+    ///
+    /// // The other entry in the same region is untouched.
+    /// assert_eq!(qt.get(handle_b).unwrap().value_ref(), &'b');
+    /// assert_eq!(qt.query(region).count(), 1);
+    /// ```
+    ///
+    /// [`.delete()`]: #method.delete
     pub fn delete_by_handle(&mut self, handle: u64) -> Option<Entry<U, V>> {
         // Pop the Entry<U, V> out of the @store,
         if let Some(entry) = self.store.remove(&handle) {
@@ -605,42 +3467,296 @@ where
         None
     }
 
-    // TODO(ambuc): Test this fn.
-    /// Retains only the elements specified by the predicate.
+    /// Deletes every entry whose handle is in `handles`, and returns how many were deleted.
     ///
-    /// In other words, remove all items such that `f(&mut v)` returns `false`.
-    pub fn retain<F>(&mut self, mut f: F) -> IntoIter<U, V>
+    /// Handles which aren't in the quadtree (or which appear twice) are skipped. All the deletions
+    /// share one descent into the tree, so this is cheaper than calling [`.delete_by_handle()`] for
+    /// each handle in turn.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(4);
+    /// let a = qt.insert_pt(Point {x: 1, y: 1}, 'a').unwrap();
+    /// let b = qt.insert_pt(Point {x: 2, y: 2}, 'b').unwrap();
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'c');
+    ///
+    /// assert_eq!(qt.remove_many(vec![a, b, a, 1000]), 2);
+    /// assert_eq!(qt.len(), 1);
+    /// ```
+    ///
+    /// [`.delete_by_handle()`]: #method.delete_by_handle
+    pub fn remove_many<I>(&mut self, handles: I) -> usize
     where
-        F: FnMut(&mut V) -> bool,
-        U: Hash,
+        I: IntoIterator<Item = u64>,
     {
-        // TODO(ambuc): I think this is technically correct but it seems to be interweaving three
-        // routines. Is there a way to simplify this?
-        let mut doomed: HashSet<(u64, Area<U>)> = HashSet::new();
-        for (handle, entry) in &mut self.store {
-            if f(entry.value_mut()) {
-                doomed.insert((*handle, entry.area()));
+        let mut doomed: HashSet<u64> = HashSet::new();
+        let mut regions: Vec<Area<U>> = Vec::new();
+        for handle in handles {
+            if let Some(entry) = self.store.remove(&handle) {
+                doomed.insert(handle);
+                regions.push(entry.area());
             }
         }
-        // TODO(ambuc): There is an optimization here to do one traversal with many matches, over
-        // many traversals i.e. one per match.
-        let mut entries: Vec<Entry<U, V>> = vec![];
-        for (handle, region) in doomed {
-            entries.push(self.store.remove(&handle).unwrap());
-            self.inner.delete_by_handle(handle, region);
+        if !doomed.is_empty() {
+            self.inner.delete_by_handles(&doomed, &regions);
+        }
+        doomed.len()
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, removes all entries such that `f(&region, &value)` returns `false`, in a
+    /// single pass over the tree. Any subtrees left empty are pruned. Returns an
+    /// [`IntoIter<U, V>`] over the removed entries.
+    ///
+    /// ```
+    /// use quadtree_rs::Quadtree;
+    ///
+    /// let mut qt = Quadtree::<u32, i32>::new(4);
+    /// for i in 0..8 {
+    ///     qt.insert_pt((i, i).into(), i as i32);
+    /// }
+    ///
+    /// // Keep only the even values.
+    /// let removed = qt.retain(|_, &v| v % 2 == 0);
+    ///
+    /// assert_eq!(removed.count(), 4);
+    /// assert_eq!(qt.len(), 4);
+    /// assert!(qt.values().all(|v| v % 2 == 0));
+    /// ```
+    ///
+    /// [`IntoIter<U, V>`]: iter/struct.IntoIter.html
+    pub fn retain<F>(&mut self, mut f: F) -> IntoIter<U, V>
+    where
+        F: FnMut(&Area<U>, &V) -> bool,
+    {
+        let doomed: HashSet<u64> = self
+            .store
+            .iter()
+            .filter(|(_, entry)| !f(&entry.area(), entry.value_ref()))
+            .map(|(&handle, _)| handle)
+            .collect();
+        if doomed.is_empty() {
+            return IntoIter { entries: vec![] };
         }
 
+        self.inner
+            .retain_handles(&mut |handle| !doomed.contains(&handle));
+
+        let entries = doomed
+            .iter()
+            .filter_map(|handle| self.store.remove(handle))
+            .collect();
+
         IntoIter { entries }
     }
     // TODO(ambuc): retain_within
 
+    /// Builds a quadtree with the same regions as this one, and the values `f` makes from this
+    /// one's values.
+    ///
+    /// The new quadtree has exactly the same shape as this one, so nothing needs to be inserted
+    /// again. Each entry keeps its handle, which then looks up the mapped entry in the new
+    /// quadtree.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, f32>::new(4);
+    /// let handle = qt.insert_pt(Point {x: 1, y: 2}, 1.5).unwrap();
+    ///
+    /// let labels: Quadtree<u32, String> = qt.map(|v| format!("{:.1}", v * 2.0));
+    ///
+    /// assert_eq!(labels.get(handle).unwrap().value_ref(), "3.0");
+    /// assert_eq!(labels.get(handle).unwrap().area(), qt.get(handle).unwrap().area());
+    /// ```
+    pub fn map<W, F>(&self, mut f: F) -> Quadtree<U, W>
+    where
+        F: FnMut(&V) -> W,
+    {
+        Quadtree {
+            inner: self.inner.clone(),
+            store: self
+                .store
+                .iter()
+                .map(|(&handle, entry)| {
+                    (
+                        handle,
+                        Entry::new((entry.area(), f(entry.value_ref())), handle),
+                    )
+                })
+                .collect(),
+            growable: self.growable,
+            max_entries: self.max_entries,
+        }
+    }
+
+    /// Wraps the quadtree in a [`SharedQuadtree`], which many threads can read at once while
+    /// another writes to it.
+    ///
+    /// [`SharedQuadtree`]: shared/struct.SharedQuadtree.html
+    pub fn into_shared(self) -> SharedQuadtree<U, V> {
+        SharedQuadtree::new(self)
+    }
+
     /// Returns an iterator ([`Iter<U, V>`]) over all [`&'a Entry<U, V>`]
     /// region/value associations in the Quadtree.
     ///
+    /// The order is unspecified. For a spatial order, see [`.iter_z_order()`].
+    ///
     /// [`Iter<U, V>`]: iter/struct.Iter.html
     /// [`&'a Entry<U, V>`]: entry/struct.Entry.html
-    pub fn iter(&self) -> Iter<U, V> {
-        Iter::new(&self.inner, &self.store)
+    /// [`.iter_z_order()`]: #method.iter_z_order
+    pub fn iter(&self) -> Iter<'_, U, V> {
+        Iter::new(&self.store)
+    }
+
+    /// Returns a mutable iterator ([`IterMut<U, V>`]) over all [`&'a mut Entry<U, V>`]
+    /// region/value associations in the Quadtree.
+    ///
+    /// Each entry is yielded exactly once. Only values can be changed; regions stay put.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, u32>::new(4);
+    /// qt.insert_pt(Point {x: 1, y: 1}, 10);
+    /// qt.insert_pt(Point {x: 5, y: 2}, 20);
+    ///
+    /// for entry in &mut qt {
+    ///     *entry.value_mut() += entry.anchor().x();
+    /// }
+    ///
+    /// let mut values: Vec<u32> = qt.values().copied().collect();
+    /// values.sort();
+    /// assert_eq!(values, vec![11, 25]);
+    /// ```
+    ///
+    /// [`IterMut<U, V>`]: iter/struct.IterMut.html
+    /// [`&'a mut Entry<U, V>`]: entry/struct.Entry.html
+    pub fn iter_mut(&mut self) -> IterMut<'_, U, V> {
+        IterMut {
+            inner: self.store.values_mut(),
+        }
+    }
+
+    /// Returns an iterator ([`ZOrder<U, V>`]) over all [`&'a Entry<U, V>`]
+    /// region/value associations in the Quadtree, in Z-order.
+    ///
+    /// The tree is walked depth-first, visiting subquadrants in the order northwest, northeast,
+    /// southwest, southeast. Each entry is yielded at the first node which holds it, and a node's
+    /// own entries (in the order they were inserted) come before those of its subquadrants. So
+    /// points come out in Morton order, and a larger region comes out just ahead of the smaller
+    /// ones it overlaps. Unlike [`.iter()`], the order is fully determined by the tree.
+    ///
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'd');
+    /// qt.insert_pt(Point {x: 0, y: 3}, 'c');
+    /// qt.insert_pt(Point {x: 3, y: 0}, 'b');
+    /// qt.insert_pt(Point {x: 0, y: 0}, 'a');
+    ///
+    /// let order: String = qt.iter_z_order().map(|e| e.value_ref()).collect();
+    /// assert_eq!(order, "abcd");
+    /// ```
+    ///
+    /// [`ZOrder<U, V>`]: iter/struct.ZOrder.html
+    /// [`&'a Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.iter()`]: #method.iter
+    pub fn iter_z_order(&self) -> ZOrder<'_, U, V> {
+        ZOrder::new(&self.inner, &self.store)
+    }
+
+    /// The [`&Entry<U, V>`] at position `n` of the Z-order given by [`.iter_z_order()`], or `None`
+    /// if `n` is at least [`.len()`].
+    ///
+    /// Since the order only depends on the regions in the tree, so does the entry at each
+    /// position, which makes this useful for reproducible subsampling. It walks the tree from the
+    /// start of the order each time, so takes O(n) time; to visit many positions in turn, step
+    /// through [`.iter_z_order()`] instead.
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(2);
+    /// qt.insert_pt(Point {x: 3, y: 3}, 'd');
+    /// qt.insert_pt(Point {x: 0, y: 3}, 'c');
+    /// qt.insert_pt(Point {x: 3, y: 0}, 'b');
+    /// qt.insert_pt(Point {x: 0, y: 0}, 'a');
+    ///
+    /// assert_eq!(qt.nth_z(0).unwrap().value_ref(), &'a');
+    /// assert_eq!(qt.nth_z(2).unwrap().value_ref(), &'c');
+    /// assert!(qt.nth_z(4).is_none());
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.iter_z_order()`]: #method.iter_z_order
+    /// [`.len()`]: #method.len
+    pub fn nth_z(&self, n: usize) -> Option<&Entry<U, V>> {
+        if n >= self.len() {
+            return None;
+        }
+        self.iter_z_order().nth(n)
+    }
+
+    /// An [`&Entry<U, V>`] chosen uniformly at random with `rng`, or `None` if the quadtree is
+    /// empty. Only with the `rand` feature.
+    ///
+    /// Every entry is equally likely, whatever its region or where it's stored: one index is
+    /// drawn uniformly from `0..len()`, and the entry at that index of the store is returned. The
+    /// store can't be indexed directly, so this takes O(n) time (but no allocation).
+    /// ```
+    /// use quadtree_rs::Quadtree;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut qt = Quadtree::<u32, u8>::new(4);
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// assert!(qt.sample(&mut rng).is_none());
+    ///
+    /// qt.extend(vec![((1, 1), 1), ((5, 2), 2), ((9, 9), 3)]);
+    /// let value = *qt.sample(&mut rng).unwrap().value_ref();
+    /// assert!((1..=3).contains(&value));
+    /// ```
+    ///
+    /// [`&Entry<U, V>`]: entry/struct.Entry.html
+    #[cfg(feature = "rand")]
+    pub fn sample<R>(&self, rng: &mut R) -> Option<&Entry<U, V>>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        if self.store.is_empty() {
+            return None;
+        }
+        let index = rng.gen_range(0..self.store.len());
+        self.store.values().nth(index)
+    }
+
+    /// Returns an iterator ([`Hilbert<U, V>`]) over all [`&'a Entry<U, V>`]
+    /// region/value associations in the Quadtree, in Hilbert order.
+    ///
+    /// Like [`.iter_z_order()`], but the subquadrants of each node are visited along a Hilbert
+    /// curve: the root's in the order northwest, southwest, southeast, northeast, and each
+    /// subquadrant's turned so that consecutive cells along the curve always share an edge. That
+    /// avoids the long jumps Z-order makes between quadrants. Each entry is yielded at the first
+    /// node which holds it, and a node's own entries come before those of its subquadrants.
+    ///
+    /// ```
+    /// use quadtree_rs::{point::Point, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, char>::new(1);
+    /// qt.insert_pt(Point {x: 1, y: 0}, 'd');
+    /// qt.insert_pt(Point {x: 1, y: 1}, 'c');
+    /// qt.insert_pt(Point {x: 0, y: 1}, 'b');
+    /// qt.insert_pt(Point {x: 0, y: 0}, 'a');
+    ///
+    /// let order: String = qt.iter_hilbert().map(|e| e.value_ref()).collect();
+    /// assert_eq!(order, "abcd");
+    /// ```
+    ///
+    /// [`Hilbert<U, V>`]: iter/struct.Hilbert.html
+    /// [`&'a Entry<U, V>`]: entry/struct.Entry.html
+    /// [`.iter_z_order()`]: #method.iter_z_order
+    pub fn iter_hilbert(&self) -> Hilbert<'_, U, V> {
+        Hilbert::new(&self.inner, &self.store)
     }
 
     /// Returns an iterator ([`Regions<U, V>`]) over all [`Area<U>`] regions
@@ -648,9 +3764,9 @@ where
     ///
     /// [`Regions<U, V>`]: iter/struct.Regions.html
     /// [`Area<U>`]: area/struct.Area.html
-    pub fn regions(&self) -> Regions<U, V> {
+    pub fn regions(&self) -> Regions<'_, U, V> {
         Regions {
-            inner: Iter::new(&self.inner, &self.store),
+            inner: Iter::new(&self.store),
         }
     }
 
@@ -658,31 +3774,258 @@ where
     /// Quadtree.
     ///
     /// [`Values<U, V>`]: iter/struct.Values.html
-    pub fn values(&self) -> Values<U, V> {
+    pub fn values(&self) -> Values<'_, U, V> {
         Values {
-            inner: Iter::new(&self.inner, &self.store),
+            inner: Iter::new(&self.store),
+        }
+    }
+
+    /// Returns an iterator ([`ValuesMut<U, V>`]) over all `&'a mut V` values in the
+    /// Quadtree.
+    ///
+    /// Each value is yielded exactly once, even if its region is held by more than one node.
+    ///
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, i32>::new(4);
+    /// qt.insert(AreaBuilder::default().anchor((0, 0).into()).build().unwrap(), 1);
+    /// qt.insert(AreaBuilder::default().anchor((5, 5).into()).dimensions((6, 6)).build().unwrap(), 2);
+    ///
+    /// for v in qt.values_mut() {
+    ///     *v *= 10;
+    /// }
+    ///
+    /// let mut values: Vec<i32> = qt.values().copied().collect();
+    /// values.sort();
+    /// assert_eq!(values, vec![10, 20]);
+    /// ```
+    ///
+    /// [`ValuesMut<U, V>`]: iter/struct.ValuesMut.html
+    pub fn values_mut(&mut self) -> ValuesMut<'_, U, V> {
+        ValuesMut {
+            inner: self.store.values_mut(),
         }
     }
 
     // fn
 
-    fn modify_region<F, M>(&mut self, filter: F, modify: M)
+    fn modify_region<M>(&mut self, area: Area<U>, traversal: Traversal, mut modify: M)
     where
-        F: Fn(Area<U>) -> bool,
-        M: Fn(&mut V) + Copy,
+        M: FnMut(&mut V),
     {
-        let relevant_handles: Vec<u64> =
-            HandleIter::new(&self.inner, self.inner.region()).collect();
+        // The query borrows the store, so the handles are collected before any value is touched.
+        let relevant_handles: Vec<u64> = Query::new(area, &self.inner, &self.store, traversal)
+            .map(|entry| entry.handle())
+            .collect();
         for i in relevant_handles {
             if let Some(entry) = self.store.get_mut(&i) {
-                if filter(entry.area()) {
-                    modify(entry.value_mut());
+                modify(entry.value_mut());
+            }
+        }
+    }
+}
+
+/// Grouping many values under one region.
+///
+/// A quadtree of `Vec<V>` can hold a bucket of values per region, instead of an entry per value,
+/// which saves a handle and a place in the tree for every value after the first. This is opt-in:
+/// only [`.insert_grouped()`] groups values together, and the rest of the API treats each bucket
+/// as a single entry with a `Vec<V>` value.
+///
+/// [`.insert_grouped()`]: #method.insert_grouped
+impl<U, V> Quadtree<U, Vec<V>>
+where
+    U: PrimInt + Default,
+{
+    /// Adds `val` to the bucket of values at exactly `region`, creating the bucket if there isn't
+    /// one yet.
+    ///
+    /// Returns the handle of the bucket, or `None` if `region` doesn't fit in the quadtree (in
+    /// which case `val` is dropped). Buckets are only shared between regions which are exactly
+    /// equal; overlapping regions get buckets of their own. If several entries already lie at
+    /// `region` (say, from [`.insert()`]), `val` is added to one of them.
+    /// ```
+    /// use quadtree_rs::{area::AreaBuilder, Quadtree};
+    ///
+    /// let mut qt = Quadtree::<u32, Vec<&str>>::new(4);
+    /// let door = AreaBuilder::default()
+    ///     .anchor((2, 2).into())
+    ///     .build().unwrap();
+    ///
+    /// let handle = qt.insert_grouped(door, "opened").unwrap();
+    /// assert_eq!(qt.insert_grouped(door, "closed"), Some(handle));
+    /// assert_eq!(qt.len(), 1);
+    ///
+    /// let (area, events) = qt.query_grouped(door).next().unwrap();
+    /// assert_eq!(area, door);
+    /// assert_eq!(events, &["opened", "closed"]);
+    /// ```
+    ///
+    /// [`.insert()`]: #method.insert
+    pub fn insert_grouped(&mut self, region: Area<U>, val: V) -> Option<u64> {
+        let existing = self
+            .query_strict(region)
+            .find(|e| e.area() == region)
+            .map(|e| e.handle());
+        match existing {
+            Some(handle) => {
+                self.get_mut(handle)?.value_mut().push(val);
+                Some(handle)
+            }
+            None => self.insert(region, vec![val]),
+        }
+    }
+
+    /// Returns an iterator ([`QueryGrouped<U, V>`]) over the regions which intersect `area`,
+    /// each with the values grouped there by [`.insert_grouped()`].
+    ///
+    /// The same regions are found as by [`.query()`].
+    ///
+    /// [`QueryGrouped<U, V>`]: iter/struct.QueryGrouped.html
+    /// [`.insert_grouped()`]: #method.insert_grouped
+    /// [`.query()`]: #method.query
+    pub fn query_grouped(&self, area: Area<U>) -> QueryGrouped<'_, U, V> {
+        QueryGrouped::new(self.query(area))
+    }
+
+    /// Returns an iterator ([`QueryGrouped<U, V>`]) over the regions which lie wholly within
+    /// `area`, each with the values grouped there by [`.insert_grouped()`].
+    ///
+    /// The same regions are found as by [`.query_strict()`].
+    ///
+    /// [`QueryGrouped<U, V>`]: iter/struct.QueryGrouped.html
+    /// [`.insert_grouped()`]: #method.insert_grouped
+    /// [`.query_strict()`]: #method.query_strict
+    pub fn query_grouped_strict(&self, area: Area<U>) -> QueryGrouped<'_, U, V> {
+        QueryGrouped::new(self.query_strict(area))
+    }
+}
+
+/// The depth of a [`Quadtree`] created with `Default::default()`.
+///
+/// This is the deepest tree which every coordinate type can hold: at depth 6 the tree is 64x64,
+/// and a depth-7 tree would already be too wide for `i8`. For anything larger, use
+/// [`Quadtree::new()`] or [`Quadtree::new_with_anchor()`].
+///
+/// [`Quadtree`]: struct.Quadtree.html
+/// [`Quadtree::new()`]: struct.Quadtree.html#method.new
+/// [`Quadtree::new_with_anchor()`]: struct.Quadtree.html#method.new_with_anchor
+pub const DEFAULT_DEPTH: usize = 6;
+
+/// Creates an empty quadtree anchored at the origin, with a depth of [`DEFAULT_DEPTH`].
+///
+/// ```
+/// use quadtree_rs::{point::Point, Quadtree, DEFAULT_DEPTH};
+///
+/// #[derive(Default)]
+/// struct World {
+///     entities: Quadtree<i32, String>,
+/// }
+///
+/// let world = World::default();
+/// assert_eq!(world.entities.anchor(), Point {x: 0, y: 0});
+/// assert_eq!(world.entities.depth(), DEFAULT_DEPTH);
+/// assert!(world.entities.is_empty());
+/// ```
+///
+/// [`DEFAULT_DEPTH`]: constant.DEFAULT_DEPTH.html
+impl<U, V> Default for Quadtree<U, V>
+where
+    U: PrimInt + Default,
+{
+    fn default() -> Self {
+        Self::new_with_anchor(
+            Point {
+                x: U::zero(),
+                y: U::zero(),
+            },
+            DEFAULT_DEPTH,
+        )
+    }
+}
+
+/// Two quadtrees are equal if they hold the same region/value associations, the same number of
+/// times each.
+///
+/// This compares contents, not structure: handles, the order entries were inserted in, and the
+/// shape of the tree (including its anchor and depth) are all ignored. Entries are sorted by
+/// region to line them up, so comparing takes `O(n log n)` time, plus time quadratic in the number
+/// of entries which share any one region.
+///
+/// ```
+/// use quadtree_rs::{point::Point, Quadtree};
+///
+/// let mut qt_a = Quadtree::<u32, char>::new(4);
+/// qt_a.insert_pt(Point {x: 1, y: 1}, 'a');
+/// qt_a.insert_pt(Point {x: 2, y: 2}, 'b');
+///
+/// let mut qt_b = Quadtree::<u32, char>::new(4);
+/// qt_b.insert_pt(Point {x: 2, y: 2}, 'b');
+/// qt_b.insert_pt(Point {x: 1, y: 1}, 'a');
+///
+/// assert_eq!(qt_a, qt_b);
+///
+/// qt_b.insert_pt(Point {x: 1, y: 1}, 'a');
+/// assert_ne!(qt_a, qt_b);
+/// ```
+impl<U, V> PartialEq for Quadtree<U, V>
+where
+    U: PrimInt + Default,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        fn sorted<U: PrimInt + Default, V>(store: &StoreType<U, V>) -> Vec<&Entry<U, V>> {
+            let mut entries: Vec<&Entry<U, V>> = store.values().collect();
+            entries.sort_by_key(|e| {
+                let area = e.area();
+                (
+                    area.left_edge(),
+                    area.top_edge(),
+                    area.width(),
+                    area.height(),
+                )
+            });
+            entries
+        }
+        let (ours, theirs) = (sorted(&self.store), sorted(&other.store));
+        if ours.iter().zip(&theirs).any(|(a, b)| a.area() != b.area()) {
+            return false;
+        }
+        // Now both sides have the same run of entries for each region. Match up their values.
+        let mut start = 0;
+        while start < ours.len() {
+            let area = ours[start].area();
+            let end = start
+                + ours[start..]
+                    .iter()
+                    .take_while(|e| e.area() == area)
+                    .count();
+            let mut unmatched: Vec<&V> = theirs[start..end].iter().map(|e| e.value_ref()).collect();
+            for entry in &ours[start..end] {
+                match unmatched.iter().position(|&v| v == entry.value_ref()) {
+                    Some(i) => {
+                        unmatched.swap_remove(i);
+                    }
+                    None => return false,
                 }
             }
+            start = end;
         }
+        true
     }
 }
 
+impl<U, V> Eq for Quadtree<U, V>
+where
+    U: PrimInt + Default,
+    V: Eq,
+{
+}
+
 /// `Extend<((U, U), V)>` will silently drop values whose coordinates do not fit in the region
 /// represented by the Quadtree. It is the responsibility of the callsite to ensure these points
 /// fit.
@@ -696,17 +4039,92 @@ where
     {
         for ((x, y), val) in iter {
             // Ignore errors.
-            self.insert(
-                AreaBuilder::default()
-                    .anchor(point::Point { x, y })
-                    .build()
-                    .unwrap(),
-                val,
-            );
+            self.insert_pt(point::Point { x, y }, val);
         }
     }
 }
 
+/// Like `Extend<((U, U), V)>`, but for `Copy` values held by reference. Mirrors the equivalent
+/// impl on `std::collections::HashMap`.
+impl<'a, U, V> Extend<(&'a (U, U), &'a V)> for Quadtree<U, V>
+where
+    U: PrimInt + Default,
+    V: Copy + 'a,
+{
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = (&'a (U, U), &'a V)>,
+    {
+        self.extend(iter.into_iter().map(|(&pt, &val)| (pt, val)));
+    }
+}
+
+/// `FromIterator<((U, U), V)>` has no anchor or depth to work with, so it picks them from the
+/// points themselves. The points are buffered, and the quadtree is anchored at their top-left-most
+/// corner `(min x, min y)` with the smallest depth whose width covers all of them. Every point is
+/// kept (duplicates included) unless the points span so much of `U` that no such quadtree fits,
+/// in which case the quadtree stops growing and, as with `Extend`, the points beyond it are
+/// dropped.
+///
+/// The resulting quadtree is sized to its initial points, so later insertions outside of them
+/// will fail.
+///
+/// ```
+/// use quadtree_rs::{point::Point, Quadtree};
+///
+/// let qt: Quadtree<i32, char> = vec![((-3, 4), 'a'), ((2, 1), 'b'), ((2, 1), 'c')]
+///     .into_iter()
+///     .collect();
+///
+/// assert_eq!(qt.len(), 3);
+/// assert_eq!(qt.anchor(), Point {x: -3, y: 1});
+/// assert_eq!(qt.width(), 8);
+/// ```
+impl<U, V> FromIterator<((U, U), V)> for Quadtree<U, V>
+where
+    U: PrimInt + Default,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = ((U, U), V)>,
+    {
+        let entries: Vec<((U, U), V)> = iter.into_iter().collect();
+
+        let mut pts = entries.iter().map(|(pt, _)| *pt);
+        let (anchor, depth) = match pts.next() {
+            None => (point::Point::from((U::zero(), U::zero())), 0),
+            Some(first) => {
+                let (min_x, min_y, max_x, max_y) = pts.fold(
+                    (first.0, first.1, first.0, first.1),
+                    |(min_x, min_y, max_x, max_y), (x, y)| {
+                        (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                    },
+                );
+                // The span is measured in cells beyond the anchor's own, so a single point needs a
+                // depth of zero.
+                let span = (max_x.checked_sub(&min_x).unwrap_or_else(U::max_value))
+                    .max(max_y.checked_sub(&min_y).unwrap_or_else(U::max_value));
+                let two = U::one() + U::one();
+                let mut width = U::one();
+                let mut depth = 0;
+                while width <= span {
+                    // Stop growing if the next width, or the region's far edges, would overflow U.
+                    match width.checked_mul(&two).filter(|w| {
+                        min_x.checked_add(w).is_some() && min_y.checked_add(w).is_some()
+                    }) {
+                        Some(w) => width = w,
+                        None => break,
+                    }
+                    depth += 1;
+                }
+                (point::Point { x: min_x, y: min_y }, depth)
+            }
+        };
+
+        Self::from_entries(anchor, depth, entries)
+    }
+}
+
 // Immutable iterator for the Quadtree, returning by-reference.
 impl<'a, U, V> IntoIterator for &'a Quadtree<U, V>
 where
@@ -716,7 +4134,20 @@ where
     type IntoIter = Iter<'a, U, V>;
 
     fn into_iter(self) -> Iter<'a, U, V> {
-        Iter::new(&self.inner, &self.store)
+        Iter::new(&self.store)
+    }
+}
+
+// Mutable iterator for the Quadtree, returning by-mutable-reference.
+impl<'a, U, V> IntoIterator for &'a mut Quadtree<U, V>
+where
+    U: PrimInt + Default,
+{
+    type Item = &'a mut Entry<U, V>;
+    type IntoIter = IterMut<'a, U, V>;
+
+    fn into_iter(self) -> IterMut<'a, U, V> {
+        self.iter_mut()
     }
 }
 
@@ -733,3 +4164,29 @@ where
         }
     }
 }
+
+// ⌊a * b / c⌋, for a < c, without overflowing even when every argument is near u128::MAX. Built up
+// one bit of @b at a time, keeping the quotient and the remainder (which stays below @c) apart.
+fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    debug_assert!(a < c);
+    let (mut quotient, mut remainder) = (0, 0);
+    for bit in (0..128).rev() {
+        // Doubles both, carrying into the quotient whenever the remainder reaches @c.
+        quotient <<= 1;
+        if remainder >= c - remainder {
+            remainder -= c - remainder;
+            quotient += 1;
+        } else {
+            remainder <<= 1;
+        }
+        if b >> bit & 1 == 1 {
+            if remainder >= c - a {
+                remainder -= c - a;
+                quotient += 1;
+            } else {
+                remainder += a;
+            }
+        }
+    }
+    quotient
+}
@@ -0,0 +1,77 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Distance functions for [`.nearest_neighbors_by()`] and [`.within_radius_by()`].
+//!
+//! Each measures the distance between the cells at two points, saturating at `u64::MAX`.
+//!
+//! [`.nearest_neighbors_by()`]: ../struct.Quadtree.html#method.nearest_neighbors_by
+//! [`.within_radius_by()`]: ../struct.Quadtree.html#method.within_radius_by
+
+use {
+    crate::{area::offset, point::Point},
+    num::PrimInt,
+    std::default::Default,
+};
+
+// The distance between @a and @b along one axis. Computed in f64 (via area::offset()) so that it
+// can't overflow U, whose range may be too wide for any signed integer type to hold the difference.
+fn axis<U>(a: U, b: U) -> u64
+where
+    U: PrimInt,
+{
+    // Casting a float to an integer saturates.
+    offset(a, b).abs() as u64
+}
+
+/// The squared Euclidean distance. (Squared, so that it stays an integer; it orders points the
+/// same way the Euclidean distance does.)
+/// ```
+/// use quadtree_rs::{metric::euclidean_sq, point::Point};
+///
+/// assert_eq!(euclidean_sq(Point {x: 1, y: 1}, Point {x: 4, y: 5}), 25);
+/// ```
+pub fn euclidean_sq<U>(a: Point<U>, b: Point<U>) -> u64
+where
+    U: PrimInt + Default,
+{
+    let (dx, dy) = (axis(a.x(), b.x()), axis(a.y(), b.y()));
+    dx.saturating_mul(dx).saturating_add(dy.saturating_mul(dy))
+}
+
+/// The Manhattan (or taxicab) distance: the sum of the distances along each axis.
+/// ```
+/// use quadtree_rs::{metric::manhattan, point::Point};
+///
+/// assert_eq!(manhattan(Point {x: 1, y: 1}, Point {x: 4, y: 5}), 7);
+/// ```
+pub fn manhattan<U>(a: Point<U>, b: Point<U>) -> u64
+where
+    U: PrimInt + Default,
+{
+    axis(a.x(), b.x()).saturating_add(axis(a.y(), b.y()))
+}
+
+/// The Chebyshev (or chessboard) distance: the larger of the distances along each axis.
+/// ```
+/// use quadtree_rs::{metric::chebyshev, point::Point};
+///
+/// assert_eq!(chebyshev(Point {x: 1, y: 1}, Point {x: 4, y: 5}), 4);
+/// ```
+pub fn chebyshev<U>(a: Point<U>, b: Point<U>) -> u64
+where
+    U: PrimInt + Default,
+{
+    axis(a.x(), b.x()).max(axis(a.y(), b.y()))
+}
@@ -0,0 +1,239 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use {
+    crate::{area::Area, entry::Entry, qtinner::QTInner, types::StoreType},
+    num::PrimInt,
+    std::{
+        cmp::Ordering,
+        collections::{BinaryHeap, HashSet},
+        default::Default,
+        iter::FusedIterator,
+    },
+};
+
+// A best-first search over the tree, yielding entries in nondecreasing order of @priority, which
+// maps each node's region or entry's region to a key (lower is nearer), or to None to rule it out.
+//
+// Nodes and handles share one priority queue. For the order to be correct, a node's key must be a
+// lower bound on the key of every region stored beneath it. (Distance from a point to the closest
+// point of a region has this property: if some point of a region is at distance d, so is a point in
+// one of the nodes holding it, and that node is no further than d.) Then by the time a handle is
+// popped, nothing nearer can remain unexplored.
+//
+// Where no one function has that property, .with_bound() takes a separate @bound for nodes.
+pub(crate) struct NeighborIter<'a, U, V, F, G = F>
+where
+    U: PrimInt + Default,
+    F: Fn(Area<U>) -> Option<f64>,
+    G: Fn(Area<U>) -> Option<f64>,
+{
+    priority: F,
+    bound: Option<G>,
+    store: &'a StoreType<U, V>,
+    frontier: BinaryHeap<Candidate<'a, U>>,
+    visited: HashSet<u64>,
+}
+
+impl<'a, U, V, F> NeighborIter<'a, U, V, F>
+where
+    U: PrimInt + Default,
+    F: Fn(Area<U>) -> Option<f64>,
+{
+    pub(crate) fn new(
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+        priority: F,
+    ) -> NeighborIter<'a, U, V, F> {
+        NeighborIter::start(qt, store, priority, None)
+    }
+}
+
+impl<'a, U, V, F, G> NeighborIter<'a, U, V, F, G>
+where
+    U: PrimInt + Default,
+    F: Fn(Area<U>) -> Option<f64>,
+    G: Fn(Area<U>) -> Option<f64>,
+{
+    // As new(), but keys each node by @bound instead, which must be a lower bound on the @priority
+    // of every region stored beneath it.
+    pub(crate) fn with_bound(
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+        priority: F,
+        bound: G,
+    ) -> NeighborIter<'a, U, V, F, G> {
+        NeighborIter::start(qt, store, priority, Some(bound))
+    }
+
+    fn start(
+        qt: &'a QTInner<U>,
+        store: &'a StoreType<U, V>,
+        priority: F,
+        bound: Option<G>,
+    ) -> NeighborIter<'a, U, V, F, G> {
+        let mut iter = NeighborIter {
+            priority,
+            bound,
+            store,
+            frontier: BinaryHeap::new(),
+            visited: HashSet::new(),
+        };
+        if !store.is_empty() {
+            if let Some(key) = iter.node_key(qt.region()) {
+                iter.frontier.push(Candidate {
+                    key,
+                    kind: CandidateKind::Node(qt),
+                });
+            }
+        }
+        iter
+    }
+
+    fn node_key(&self, region: Area<U>) -> Option<f64> {
+        match &self.bound {
+            Some(bound) => bound(region),
+            None => (self.priority)(region),
+        }
+    }
+
+    fn expand(&mut self, qt: &'a QTInner<U>) {
+        for handle in qt.handles() {
+            if self.visited.contains(handle) {
+                continue;
+            }
+            // Only queue handles which are still in the store.
+            if let Some(entry) = self.store.get(handle) {
+                if let Some(key) = (self.priority)(entry.area()) {
+                    self.frontier.push(Candidate {
+                        key,
+                        kind: CandidateKind::Handle(*handle),
+                    });
+                }
+            }
+        }
+        if let Some(subquadrants) = qt.subquadrants().as_ref() {
+            for subquadrant in subquadrants.iter() {
+                if subquadrant.handles().is_empty() && subquadrant.subquadrants().is_none() {
+                    continue;
+                }
+                if let Some(key) = self.node_key(subquadrant.region()) {
+                    self.frontier.push(Candidate {
+                        key,
+                        kind: CandidateKind::Node(subquadrant),
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<'a, U, V, F, G> Iterator for NeighborIter<'a, U, V, F, G>
+where
+    U: PrimInt + Default,
+    F: Fn(Area<U>) -> Option<f64>,
+    G: Fn(Area<U>) -> Option<f64>,
+{
+    type Item = &'a Entry<U, V>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(candidate) = self.frontier.pop() {
+            match candidate.kind {
+                CandidateKind::Node(qt) => self.expand(qt),
+                CandidateKind::Handle(handle) => {
+                    if self.visited.insert(handle) {
+                        return self.store.get(&handle);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.store.len()))
+    }
+}
+
+impl<U, V, F, G> FusedIterator for NeighborIter<'_, U, V, F, G>
+where
+    U: PrimInt + Default,
+    F: Fn(Area<U>) -> Option<f64>,
+    G: Fn(Area<U>) -> Option<f64>,
+{
+}
+
+enum CandidateKind<'a, U>
+where
+    U: PrimInt + Default,
+{
+    Node(&'a QTInner<U>),
+    Handle(u64),
+}
+
+struct Candidate<'a, U>
+where
+    U: PrimInt + Default,
+{
+    key: f64,
+    kind: CandidateKind<'a, U>,
+}
+
+// BinaryHeap is a max-heap, so the ordering is reversed to pop the nearest candidate first. At
+// equal keys handles sort ahead of nodes, since they can be yielded immediately.
+impl<U> Ord for Candidate<'_, U>
+where
+    U: PrimInt + Default,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .total_cmp(&self.key)
+            .then_with(|| self.is_handle().cmp(&other.is_handle()))
+    }
+}
+
+impl<U> PartialOrd for Candidate<'_, U>
+where
+    U: PrimInt + Default,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<U> PartialEq for Candidate<'_, U>
+where
+    U: PrimInt + Default,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<U> Eq for Candidate<'_, U> where U: PrimInt + Default {}
+
+impl<U> Candidate<'_, U>
+where
+    U: PrimInt + Default,
+{
+    fn is_handle(&self) -> bool {
+        match self.kind {
+            CandidateKind::Handle(_) => true,
+            CandidateKind::Node(_) => false,
+        }
+    }
+}
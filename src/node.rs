@@ -0,0 +1,119 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A read-only view into a single node of the Quadtree, for walking the tree by hand.
+
+use {
+    crate::{area::Area, entry::Entry, qtinner::QTInner, types::StoreType},
+    num::PrimInt,
+    std::default::Default,
+};
+
+/// A node in the [`Quadtree`], covering some region, and holding the entries kept at that level.
+///
+/// Created by [`.root_node()`], and from there by [`.children()`]. A node with children only
+/// keeps the regions which cover it entirely, and pushes any other region down into each child it
+/// overlaps. So a region may be held by several nodes, and walks over the tree which need each
+/// entry once should skip the ones they've already seen.
+///
+/// [`Quadtree`]: ../struct.Quadtree.html
+/// [`.root_node()`]: ../struct.Quadtree.html#method.root_node
+/// [`.children()`]: #method.children
+/// ```
+/// use quadtree_rs::{node::NodeRef, Quadtree};
+///
+/// let mut qt = Quadtree::<u32, u32>::new(3);
+/// qt.extend(vec![((0, 0), 1), ((7, 7), 2)]);
+///
+/// // The depth of the deepest node holding anything.
+/// fn deepest(node: NodeRef<'_, u32, u32>, level: usize) -> usize {
+///     let here = if node.entries().next().is_some() { level } else { 0 };
+///     match node.children() {
+///         Some(children) => children
+///             .iter()
+///             .map(|child| deepest(*child, level + 1))
+///             .fold(here, usize::max),
+///         None => here,
+///     }
+/// }
+/// assert_eq!(deepest(qt.root_node(), 0), 3);
+/// ```
+pub struct NodeRef<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    node: &'a QTInner<U>,
+    store: &'a StoreType<U, V>,
+}
+
+impl<'a, U, V> NodeRef<'a, U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(node: &'a QTInner<U>, store: &'a StoreType<U, V>) -> Self {
+        NodeRef { node, store }
+    }
+
+    /// The region this node covers.
+    pub fn region(&self) -> Area<U> {
+        self.node.region()
+    }
+
+    /// How many levels of the tree there are beneath this node. Zero means this node is a single
+    /// cell, so it can have no children.
+    pub fn depth(&self) -> usize {
+        self.node.depth()
+    }
+
+    /// The four children of this node, or `None` if it hasn't been split. Each covers one
+    /// quarter of this node's region, in the order northeast, northwest, southeast, southwest
+    /// (where north is towards smaller `y`).
+    pub fn children(&self) -> Option<[NodeRef<'a, U, V>; 4]> {
+        let store = self.store;
+        self.node.subquadrants().as_ref().map(|[ne, nw, se, sw]| {
+            [
+                NodeRef::new(ne, store),
+                NodeRef::new(nw, store),
+                NodeRef::new(se, store),
+                NodeRef::new(sw, store),
+            ]
+        })
+    }
+
+    /// Whether this node has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.node.subquadrants().is_none()
+    }
+
+    /// The entries kept at this node, in the order they were kept.
+    pub fn entries(&self) -> impl Iterator<Item = &'a Entry<U, V>> + 'a {
+        let store = self.store;
+        self.node
+            .handles()
+            .iter()
+            .filter_map(move |handle| store.get(handle))
+    }
+}
+
+// Implemented by hand, since deriving these would needlessly require `U` and `V` to be `Clone`.
+impl<U, V> Clone for NodeRef<'_, U, V>
+where
+    U: PrimInt + Default,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<U, V> Copy for NodeRef<'_, U, V> where U: PrimInt + Default {}
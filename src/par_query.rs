@@ -0,0 +1,113 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A query spread across threads, behind the `rayon` feature.
+//
+// A handle is held by every node its region was pushed down into. Like the sequential query (see
+// handle_iter.rs), this relies on those nodes tiling the region: they never overlap, and between
+// them they cover all of it, so exactly one of them holds the top-left corner of the part of the
+// region inside the query region, and only that one yields the handle. Nothing needs remembering
+// across nodes, which leaves each subtree free to be searched on its own.
+
+use {
+    crate::{area::Area, entry::Entry, qtinner::QTInner, traversal::Traversal, types::StoreType},
+    num::PrimInt,
+    rayon::iter::{IntoParallelIterator, ParallelIterator},
+    std::default::Default,
+};
+
+// How many levels of the tree are split into separate tasks, at most 4^SPLIT_LEVELS of them.
+// Below that, each subtree is searched sequentially.
+const SPLIT_LEVELS: usize = 3;
+
+pub(crate) fn par_query<'a, U, V>(
+    qt: &'a QTInner<U>,
+    store: &'a StoreType<U, V>,
+    query_region: Area<U>,
+    traversal: Traversal,
+) -> impl ParallelIterator<Item = &'a Entry<U, V>> + 'a
+where
+    U: PrimInt + Default + Send + Sync,
+    V: Sync,
+{
+    let mut tasks = Vec::new();
+    if !store.is_empty() {
+        split(qt, query_region, SPLIT_LEVELS, &mut tasks);
+    }
+    tasks.into_par_iter().flat_map_iter(move |(node, recurse)| {
+        let mut found = Vec::new();
+        search(node, recurse, store, query_region, traversal, &mut found);
+        found
+    })
+}
+
+// Collects the nodes overlapping @query_region down to @levels beneath @qt. Nodes above the
+// bottom level are searched without their subtrees, which are tasks of their own.
+fn split<'a, U>(
+    qt: &'a QTInner<U>,
+    query_region: Area<U>,
+    levels: usize,
+    tasks: &mut Vec<(&'a QTInner<U>, bool)>,
+) where
+    U: PrimInt + Default,
+{
+    if !qt.region().intersects(query_region) {
+        return;
+    }
+    match qt.subquadrants().as_ref() {
+        Some(subquadrants) if levels > 0 => {
+            if !qt.handles().is_empty() {
+                tasks.push((qt, false));
+            }
+            for sq in subquadrants.iter() {
+                split(sq, query_region, levels - 1, tasks);
+            }
+        }
+        _ => tasks.push((qt, true)),
+    }
+}
+
+fn search<'a, U, V>(
+    qt: &'a QTInner<U>,
+    recurse: bool,
+    store: &'a StoreType<U, V>,
+    query_region: Area<U>,
+    traversal: Traversal,
+    found: &mut Vec<&'a Entry<U, V>>,
+) where
+    U: PrimInt + Default,
+{
+    for handle in qt.handles() {
+        if let Some(entry) = store.get(handle) {
+            if !traversal.eval(entry.area(), query_region) {
+                continue;
+            }
+            if let Some(overlap) = entry.area().intersection(query_region) {
+                if qt.region().contains_pt(overlap.anchor()) {
+                    found.push(entry);
+                }
+            }
+        }
+    }
+    if !recurse {
+        return;
+    }
+    if let Some(subquadrants) = qt.subquadrants().as_ref() {
+        for sq in subquadrants.iter() {
+            if sq.region().intersects(query_region) {
+                search(sq, true, store, query_region, traversal, found);
+            }
+        }
+    }
+}
@@ -28,6 +28,29 @@ use {
 pub(crate) type Type<U> = (U, U);
 
 /// A type representing a point in space. Should be passed by value.
+///
+/// Methods which take a position take a `Point`, while dimensions stay plain `(width, height)`
+/// tuples, so the two can't be mixed up by accident. Converting from and to an `(x, y)` tuple is
+/// free:
+///
+/// ```
+/// use quadtree_rs::{point::Point, Quadtree};
+///
+/// let mut qt = Quadtree::<u32, char>::new(4);
+///
+/// qt.insert_pt(Point {x: 1, y: 2}, 'a');
+/// qt.insert_pt((3, 4).into(), 'b');
+///
+/// let pt: Point<u32> = (3, 4).into();
+/// assert_eq!((pt.x(), pt.y()), (3, 4));
+/// assert!(qt.contains_point(pt));
+///
+/// let (x, y) = pt.into();
+/// assert_eq!((x, y), (3, 4));
+/// ```
+///
+/// Those methods take a `Point` itself, rather than anything which is `Into<Point<U>>`.
+/// Otherwise a call like `qt.insert_pt((3, 4).into(), 'b')` couldn't infer what to convert into.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Point<U> {
@@ -19,10 +19,11 @@ use {
         area::{Area, AreaBuilder},
         entry::Entry,
         point::Point,
+        traversal::Traversal,
         types::StoreType,
     },
     num::PrimInt,
-    std::{default::Default, fmt::Debug},
+    std::{collections::HashSet, default::Default, fmt::Debug, ops::ControlFlow},
 };
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -45,6 +46,10 @@ where
     // entire list could be None.
     subquadrants: Option<[Box<QTInner<U>>; 4]>,
 
+    // How many handles a cell without subquadrants may keep before it's split. Zero means a cell
+    // is split as soon as a region needs it, so that every region sinks as deep as it can.
+    node_capacity: usize,
+
     // The last-inserted handle. This is a monotonically increasing counter.
     handle_counter: u64,
 }
@@ -54,13 +59,11 @@ where
     U: PrimInt + Default + Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if self.subquadrants.is_some() {
+        if let Some(subquadrants) = self.subquadrants.as_ref() {
             write!(
                 f,
                 "{:?} :: {:?} {:#?}",
-                self.region,
-                self.kept_handles,
-                self.subquadrants.as_ref().unwrap()
+                self.region, self.kept_handles, subquadrants
             )
         } else {
             write!(f, "{:?} :: {:?}", self.region, self.kept_handles,)
@@ -75,8 +78,13 @@ where
     // pub
 
     pub fn new(anchor: Point<U>, depth: usize) -> Self {
-        #[allow(clippy::cast_possible_truncation)]
-        let width: U = Self::two().pow(depth as u32);
+        Self::new_with_capacity(anchor, depth, 0)
+    }
+
+    pub fn new_with_capacity(anchor: Point<U>, depth: usize, node_capacity: usize) -> Self {
+        // Checked, since U::pow() silently wraps around in release builds.
+        let width: U = num::checked_pow(Self::two(), depth)
+            .expect("Unexpected error in QTInner::new(): the depth is too large for U.");
         let height: U = width;
         Self::new_with_area(
             AreaBuilder::default()
@@ -85,6 +93,7 @@ where
                 .build()
                 .expect("Unexpected error in QTInner::new()."),
             depth,
+            node_capacity,
         )
     }
 
@@ -92,10 +101,25 @@ where
         self.depth
     }
 
+    pub fn node_capacity(&self) -> usize {
+        self.node_capacity
+    }
+
     pub fn region(&self) -> Area<U> {
         self.region
     }
 
+    // The next handle this tree will issue. Only meaningful at the root.
+    pub fn handle_counter(&self) -> u64 {
+        self.handle_counter
+    }
+
+    // Makes this tree issue handles from @handle_counter onwards. Only meaningful at the root, and
+    // never to be moved backwards past a handle already issued.
+    pub fn set_handle_counter(&mut self, handle_counter: u64) {
+        self.handle_counter = handle_counter;
+    }
+
     pub fn handles(&self) -> &Vec<u64> {
         &self.kept_handles
     }
@@ -104,12 +128,90 @@ where
         &self.subquadrants
     }
 
+    // The leaf beneath this node whose region contains @pt, which must lie within this node.
+    pub fn leaf_at(&self, pt: Point<U>) -> &Self {
+        let mut qt = self;
+        while let Some(sqs) = qt.subquadrants.as_ref() {
+            qt = sqs
+                .iter()
+                .find(|sq| sq.region.contains_pt(pt))
+                .expect("Unexpected error in QTInner::leaf_at().");
+        }
+        qt
+    }
+
+    // Every node in this subtree in pre-order, each paired with its level beneath this node.
+    pub fn nodes(&self) -> Nodes<'_, U> {
+        Nodes {
+            stack: vec![(self, 0)],
+        }
+    }
+
     // Resets this quadtree.
     pub fn reset(&mut self) {
         self.kept_handles.clear();
         self.subquadrants = None;
     }
 
+    // Wraps this tree in ever larger roots, each twice as wide as the last, until one contains
+    // @target. Each new root extends the old one towards @target, so that the old root becomes one
+    // of its subquadrants, with every handle still where it was. (No region can cover the new root
+    // entirely, so it keeps none itself.) Returns false, leaving the tree as it was, if the root
+    // would have to grow past the bounds of U first.
+    pub fn grow_to_contain(&mut self, target: Area<U>) -> bool {
+        let mut region = self.region;
+        let mut steps = 0;
+        while !region.contains(target) {
+            match Self::grown_towards(region, target) {
+                Some(grown) => region = grown,
+                None => return false,
+            }
+            steps += 1;
+        }
+        for _ in 0..steps {
+            let grown = Self::grown_towards(self.region, target)
+                .expect("Unexpected error in QTInner::grow_to_contain().");
+            let mut root = Self::new_with_area(grown, self.depth + 1, self.node_capacity);
+            root.handle_counter = self.handle_counter;
+            // An empty root is just replaced, so that the tree is only split where it holds
+            // something.
+            let is_empty = self.kept_handles.is_empty() && self.subquadrants.is_none();
+            if !is_empty {
+                root.expand_subquadrants_by_pt(grown.center());
+            }
+            let old = std::mem::replace(self, root);
+            if let Some(subquadrants) = self.subquadrants.as_mut() {
+                let slot = subquadrants
+                    .iter()
+                    .position(|sq| sq.region == old.region)
+                    .expect("Unexpected error in QTInner::grow_to_contain().");
+                *subquadrants[slot] = old;
+            }
+        }
+        true
+    }
+
+    // The region twice as wide and tall as @region, and holding it in one corner, which extends
+    // towards @target.
+    fn grown_towards(region: Area<U>, target: Area<U>) -> Option<Area<U>> {
+        let (width, height) = (region.width(), region.height());
+        let x = if target.left_edge() < region.left_edge() {
+            region.left_edge().checked_sub(&width)?
+        } else {
+            region.left_edge()
+        };
+        let y = if target.top_edge() < region.top_edge() {
+            region.top_edge().checked_sub(&height)?
+        } else {
+            region.top_edge()
+        };
+        AreaBuilder::default()
+            .anchor(Point { x, y })
+            .dimensions((width.checked_add(&width)?, height.checked_add(&height)?))
+            .build()
+            .ok()
+    }
+
     // Attempts to insert the value at the requested region. Returns false if the region was too
     // large.
     pub fn insert_val_at_region<V>(
@@ -125,7 +227,281 @@ where
         handle
     }
 
-    // Delete all instances of @handle from this level's @kept_handles.
+    // Inserts every point-sized value which fits in this region at once. Values which don't fit are
+    // dropped. Produces exactly the tree that inserting each value in turn would have.
+    pub fn insert_vals_at_pts<V, I>(&mut self, vals: I, store: &mut StoreType<U, V>)
+    where
+        I: IntoIterator<Item = (Point<U>, V)>,
+    {
+        let mut pts: Vec<(Point<U>, u64)> = Vec::new();
+        for (pt, val) in vals {
+            if !self.region.contains_pt(pt) {
+                continue;
+            }
+            let region = AreaBuilder::default()
+                .anchor(pt)
+                .build()
+                .expect("Unexpected error in QTInner::insert_vals_at_pts().");
+            let handle = self.handle_counter;
+            self.handle_counter += 1;
+            store.insert(handle, Entry::new((region, val), handle));
+            pts.push((pt, handle));
+        }
+        self.partition_pts(pts);
+    }
+
+    // The first handle found whose region (as looked up in @store) intersects @area. Only the
+    // nodes which @area intersects are visited, and nothing is allocated along the way.
+    pub fn find_handle_intersecting<V>(
+        &self,
+        area: Area<U>,
+        store: &StoreType<U, V>,
+    ) -> Option<u64> {
+        if !self.region.intersects(area) {
+            return None;
+        }
+        for handle in &self.kept_handles {
+            if let Some(entry) = store.get(handle) {
+                if entry.area().intersects(area) {
+                    return Some(*handle);
+                }
+            }
+        }
+        self.subquadrants
+            .as_ref()?
+            .iter()
+            .find_map(|sq| sq.find_handle_intersecting(area, store))
+    }
+
+    // The area of this node covered by at least one entry (as looked up in @store). The nodes are
+    // disjoint, so their covered areas add up. A node with subquadrants only keeps regions which
+    // cover it entirely, so it's either wholly covered or as covered as its subquadrants are; only
+    // the regions kept by a leaf, clipped to it, have to be combined, by sweeping across them.
+    pub fn covered_area<V>(&self, store: &StoreType<U, V>) -> u128 {
+        let anchor = self.region.anchor();
+        let offset = |from: U, to: U| (to - from).to_u128().unwrap();
+        let clipped: Vec<(u128, u128, u128, u128)> = self
+            .kept_handles
+            .iter()
+            .filter_map(|handle| store.get(handle))
+            .filter_map(|entry| entry.area().intersection(self.region))
+            .map(|area| {
+                (
+                    offset(anchor.x(), area.left_edge()),
+                    offset(anchor.x(), area.right_edge()),
+                    offset(anchor.y(), area.top_edge()),
+                    offset(anchor.y(), area.bottom_edge()),
+                )
+            })
+            .collect();
+        if let Some(sqs) = self.subquadrants.as_ref() {
+            if clipped.is_empty() {
+                return sqs.iter().fold(0, |covered, sq| {
+                    covered.saturating_add(sq.covered_area(store))
+                });
+            }
+            let (width, height) = self.region.dimensions();
+            return width
+                .to_u128()
+                .unwrap()
+                .saturating_mul(height.to_u128().unwrap());
+        }
+
+        // Cut the leaf into vertical strips at every left and right edge, then within each strip
+        // merge the spans of the regions crossing it.
+        let mut xs: Vec<u128> = clipped.iter().flat_map(|r| vec![r.0, r.1]).collect();
+        xs.sort_unstable();
+        xs.dedup();
+        let mut covered = 0;
+        for strip in xs.windows(2) {
+            let mut spans: Vec<(u128, u128)> = clipped
+                .iter()
+                .filter(|r| r.0 <= strip[0] && strip[1] <= r.1)
+                .map(|r| (r.2, r.3))
+                .collect();
+            spans.sort_unstable();
+            let mut height: u128 = 0;
+            let mut reached = 0;
+            for (top, bottom) in spans {
+                let top = top.max(reached);
+                if bottom > top {
+                    height += bottom - top;
+                    reached = bottom;
+                }
+            }
+            covered = height
+                .saturating_mul(strip[1] - strip[0])
+                .saturating_add(covered);
+        }
+        covered
+    }
+
+    // Whether the entries (as looked up in @store) cover every point of @area, which must lie
+    // within this node. A node with subquadrants only keeps regions which cover it entirely, so
+    // any one of them covers @area; otherwise each subquadrant must cover its part of @area. A
+    // leaf's regions, clipped to @area, are swept across as in covered_area(), but working with
+    // edges rather than areas, so that nothing can overflow.
+    pub fn covers<V>(&self, area: Area<U>, store: &StoreType<U, V>) -> bool {
+        let clipped: Vec<Area<U>> = self
+            .kept_handles
+            .iter()
+            .filter_map(|handle| store.get(handle))
+            .filter_map(|entry| entry.area().intersection(area))
+            .collect();
+        if clipped.contains(&area) {
+            return true;
+        }
+        if let Some(sqs) = self.subquadrants.as_ref() {
+            return sqs.iter().all(|sq| match sq.region.intersection(area) {
+                Some(part) => sq.covers(part, store),
+                None => true,
+            });
+        }
+
+        // Cut @area into vertical strips at every left and right edge, then check that within
+        // each strip the spans of the regions crossing it leave no gap from top to bottom.
+        let mut xs: Vec<U> = clipped
+            .iter()
+            .flat_map(|r| vec![r.left_edge(), r.right_edge()])
+            .chain(vec![area.left_edge(), area.right_edge()])
+            .collect();
+        xs.sort_unstable();
+        xs.dedup();
+        xs.windows(2).all(|strip| {
+            let mut spans: Vec<(U, U)> = clipped
+                .iter()
+                .filter(|r| r.left_edge() <= strip[0] && strip[1] <= r.right_edge())
+                .map(|r| (r.top_edge(), r.bottom_edge()))
+                .collect();
+            spans.sort_unstable();
+            let mut reached = area.top_edge();
+            for (top, bottom) in spans {
+                if top > reached {
+                    return false;
+                }
+                reached = reached.max(bottom);
+            }
+            reached >= area.bottom_edge()
+        })
+    }
+
+    // Whether some region in this tree (as looked up in @store) overlaps some region in @other
+    // (as looked up in @other_store). The trees are walked in tandem, one pair of intersecting
+    // nodes at a time, so the parts of each which lie away from the other are never visited.
+    pub fn intersects_tree<V, W>(
+        &self,
+        other: &Self,
+        store: &StoreType<U, V>,
+        other_store: &StoreType<U, W>,
+    ) -> bool {
+        self.region.intersects(other.region)
+            && self.intersects_tree_step(
+                other,
+                (store, other_store),
+                &mut Vec::new(),
+                &mut Vec::new(),
+            )
+    }
+
+    // One pair of intersecting nodes of intersects_tree(), with the regions kept by the nodes
+    // above each in @above and @other_above. Each step descends into the subquadrants of only one
+    // of the pair (the larger), so the regions kept by any two overlapping entries' nodes are
+    // compared at whichever step the later of those nodes is reached, as the walk closes in on
+    // some cell where the entries overlap.
+    fn intersects_tree_step<V, W>(
+        &self,
+        other: &Self,
+        stores: (&StoreType<U, V>, &StoreType<U, W>),
+        above: &mut Vec<Area<U>>,
+        other_above: &mut Vec<Area<U>>,
+    ) -> bool {
+        let kept: Vec<Area<U>> = self
+            .kept_handles
+            .iter()
+            .filter_map(|handle| stores.0.get(handle))
+            .map(|entry| entry.area())
+            .collect();
+        let other_kept: Vec<Area<U>> = other
+            .kept_handles
+            .iter()
+            .filter_map(|handle| stores.1.get(handle))
+            .map(|entry| entry.area())
+            .collect();
+        let overlap = |these: &[Area<U>], those: &[Area<U>]| {
+            these.iter().any(|a| those.iter().any(|b| a.intersects(*b)))
+        };
+        if overlap(&kept, &other_kept) || overlap(&kept, other_above) || overlap(above, &other_kept)
+        {
+            return true;
+        }
+
+        match (self.subquadrants.as_ref(), other.subquadrants.as_ref()) {
+            (Some(sqs), theirs)
+                if theirs.is_none() || self.region.width() >= other.region.width() =>
+            {
+                let depth = above.len();
+                above.extend(kept);
+                let found = sqs
+                    .iter()
+                    .filter(|sq| sq.region.intersects(other.region))
+                    .any(|sq| sq.intersects_tree_step(other, stores, above, other_above));
+                above.truncate(depth);
+                found
+            }
+            (_, Some(other_sqs)) => {
+                let depth = other_above.len();
+                other_above.extend(other_kept);
+                let found = other_sqs
+                    .iter()
+                    .filter(|sq| self.region.intersects(sq.region))
+                    .any(|sq| self.intersects_tree_step(sq, stores, above, other_above));
+                other_above.truncate(depth);
+                found
+            }
+            // Two leaves.
+            (_, None) => false,
+        }
+    }
+
+    // Calls @f on each entry (as looked up in @store) whose region satisfies @traversal against
+    // @area, stopping as soon as it breaks. Only the nodes which @area intersects are visited.
+    // Rather than remembering which handles have been seen, each is only passed to @f by the one
+    // node holding the top-left corner of the part of its region inside @area, as in par_query.rs.
+    pub fn try_visit<'a, V, B, F>(
+        &'a self,
+        area: Area<U>,
+        traversal: Traversal,
+        store: &'a StoreType<U, V>,
+        f: &mut F,
+    ) -> ControlFlow<B>
+    where
+        F: FnMut(&'a Entry<U, V>) -> ControlFlow<B>,
+    {
+        if !self.region.intersects(area) {
+            return ControlFlow::Continue(());
+        }
+        for handle in &self.kept_handles {
+            if let Some(entry) = store.get(handle) {
+                if !traversal.eval(entry.area(), area) {
+                    continue;
+                }
+                if let Some(overlap) = entry.area().intersection(area) {
+                    if self.region.contains_pt(overlap.anchor()) {
+                        f(entry)?;
+                    }
+                }
+            }
+        }
+        if let Some(sqs) = self.subquadrants.as_ref() {
+            for sq in sqs.iter() {
+                sq.try_visit(area, traversal, store, f)?;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    // Delete all instances of @handle from this level's @kept_handles, pruning the subquadrants
+    // that leaves empty.
     pub fn delete_by_handle(&mut self, handle: u64, req: Area<U>) {
         self.kept_handles.retain(|&x| x != handle);
         // And potentially recurse into the subquadrants...
@@ -137,27 +513,133 @@ where
                 }
             }
         }
+        self.prune();
     }
 
-    // fn
+    // Delete every handle in @doomed, whose regions are @regions, in one descent. Only subtrees
+    // which some of @regions intersect are visited, and each of them only once.
+    pub fn delete_by_handles(&mut self, doomed: &HashSet<u64>, regions: &[Area<U>]) {
+        self.kept_handles.retain(|x| !doomed.contains(x));
+        if let Some(sqs) = self.subquadrants.as_mut() {
+            for sq in sqs.iter_mut() {
+                let within: Vec<Area<U>> = regions
+                    .iter()
+                    .filter(|req| sq.region.intersects(**req))
+                    .copied()
+                    .collect();
+                if !within.is_empty() {
+                    sq.delete_by_handles(doomed, &within);
+                }
+            }
+        }
+        self.prune();
+    }
 
-    fn new_with_area(region: Area<U>, depth: usize) -> Self {
+    // Moves @handle from everywhere it was kept for the region @from to wherever the region @to
+    // belongs.
+    pub fn relocate_handle<V>(
+        &mut self,
+        handle: u64,
+        from: Area<U>,
+        to: Area<U>,
+        store: &mut StoreType<U, V>,
+    ) {
+        self.delete_by_handle(handle, from);
+        self.insert_handle_at_region(to, handle, store);
+    }
+
+    // Empties this tree and re-inserts every handle in @store at its entry's region, in handle
+    // order, leaving the tree the shape that inserting them afresh would have.
+    pub fn rebuild<V>(&mut self, store: &mut StoreType<U, V>) {
+        self.reset();
+        let mut placed: Vec<(u64, Area<U>)> = store
+            .values()
+            .map(|entry| (entry.handle(), entry.area()))
+            .collect();
+        placed.sort_unstable_by_key(|&(handle, _)| handle);
+        for (handle, region) in placed {
+            self.insert_handle_at_region(region, handle, store);
+        }
+    }
+
+    // Keep only those handles for which @f returns true, at every level of the tree, pruning the
+    // subquadrants that leaves empty.
+    pub fn retain_handles<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(u64) -> bool,
+    {
+        self.kept_handles.retain(|&x| f(x));
+        if let Some(sqs) = self.subquadrants.as_mut() {
+            for sq in sqs.iter_mut() {
+                sq.retain_handles(f);
+            }
+        }
+        self.prune();
+    }
+
+    // Prune every subtree which holds no handles, so that the tree has the same shape as one
+    // built from scratch with just the remaining regions. (Every expanded node has all four of its
+    // subquadrants, so there are never single-child chains to collapse.) Returns whether this node
+    // is left empty.
+    //
+    // The deletions above already prune as they go, but only along the paths they descend, so this
+    // is still needed for a tree which came from elsewhere (e.g. was deserialized).
+    pub fn compact(&mut self) -> bool {
+        if let Some(sqs) = self.subquadrants.as_mut() {
+            let mut all_empty = true;
+            for sq in sqs.iter_mut() {
+                // Not short-circuited: every subquadrant should be compacted.
+                all_empty &= sq.compact();
+            }
+            if all_empty {
+                self.subquadrants = None;
+            }
+        }
+        self.is_empty()
+    }
+
+    // Shifts the region of every node by @by, leaving the shape of the tree alone. The caller must
+    // check that the root's edges won't overflow U; every other node lies within the root.
+    pub fn translate(&mut self, by: Point<U>) {
+        self.region = self.region.translated(by);
+        if let Some(sqs) = self.subquadrants.as_mut() {
+            for sq in sqs.iter_mut() {
+                sq.translate(by);
+            }
+        }
+    }
+
+    // Releases the spare capacity of @kept_handles at every level of the tree.
+    pub fn shrink_to_fit(&mut self) {
+        self.kept_handles.shrink_to_fit();
+        if let Some(sqs) = self.subquadrants.as_mut() {
+            for sq in sqs.iter_mut() {
+                sq.shrink_to_fit();
+            }
+        }
+    }
+
+    fn new_with_area(region: Area<U>, depth: usize, node_capacity: usize) -> Self {
         Self {
             depth,
             region,
             kept_handles: Vec::new(),
             subquadrants: None,
+            node_capacity,
             handle_counter: 0_u64,
         }
     }
 
     // Attempts to insert the value at the requested region. Returns false if the region was too
     // large.
+    //
+    // A cell with subquadrants only ever keeps the regions which cover it entirely; the rest are
+    // pushed down into every subquadrant they intersect. Queries rely on this.
     fn insert_handle_at_region<V>(
         &mut self,
         req: Area<U>,
         handle: u64,
-        _store: &mut StoreType<U, V>,
+        store: &mut StoreType<U, V>,
     ) {
         // If we're at the bottom depth, it had better fit.
         if self.depth == 0 {
@@ -176,7 +658,13 @@ where
         }
 
         if self.subquadrants.is_none() {
-            self.expand_subquadrants_by_pt(self.region.center_pt());
+            // There's still room to hold the region here, unsplit.
+            if self.kept_handles.len() < self.node_capacity {
+                self.kept_handles.push(handle);
+                return;
+            }
+            self.expand_subquadrants_by_pt(self.region.center());
+            self.push_down_kept_handles(store);
         }
 
         assert!(self.subquadrants.is_some()); // We should have Someified this in .split().
@@ -184,12 +672,66 @@ where
         if let Some(sqs) = self.subquadrants.as_mut() {
             for sq in sqs.iter_mut() {
                 if sq.region.intersects(req) {
-                    sq.insert_handle_at_region(req, handle, _store);
+                    sq.insert_handle_at_region(req, handle, store);
+                }
+            }
+        }
+    }
+
+    // Having just been split, re-inserts every kept region which doesn't cover this whole cell into
+    // the new subquadrants.
+    fn push_down_kept_handles<V>(&mut self, store: &mut StoreType<U, V>) {
+        let region = self.region;
+        let kept = std::mem::take(&mut self.kept_handles);
+        for handle in kept {
+            let req = match store.get(&handle) {
+                Some(entry) => entry.area(),
+                None => continue,
+            };
+            if req.contains(region) {
+                self.kept_handles.push(handle);
+                continue;
+            }
+            if let Some(sqs) = self.subquadrants.as_mut() {
+                for sq in sqs.iter_mut() {
+                    if sq.region.intersects(req) {
+                        sq.insert_handle_at_region(req, handle, store);
+                    }
                 }
             }
         }
     }
 
+    // Sorts @pts into the subquadrants which contain them, and recurses. Every point must be within
+    // this region.
+    fn partition_pts(&mut self, pts: Vec<(Point<U>, u64)>) {
+        if pts.is_empty() {
+            return;
+        }
+
+        if self.depth == 0 {
+            self.kept_handles
+                .extend(pts.into_iter().map(|(_, handle)| handle));
+            return;
+        }
+
+        if self.subquadrants.is_none() {
+            self.expand_subquadrants_by_pt(self.region.center());
+        }
+
+        if let Some(sqs) = self.subquadrants.as_mut() {
+            let mut buckets: [Vec<(Point<U>, u64)>; 4] = Default::default();
+            for (pt, handle) in pts {
+                if let Some(i) = sqs.iter().position(|sq| sq.region.contains_pt(pt)) {
+                    buckets[i].push((pt, handle));
+                }
+            }
+            for (sq, bucket) in sqs.iter_mut().zip(buckets) {
+                sq.partition_pts(bucket);
+            }
+        }
+    }
+
     // a--+--+--+    +--+--+--+ // a <- self.region.anchor()
     // |        |    |     |  |
     // +     p  + => +--+--+--+ // p
@@ -200,30 +742,81 @@ where
 
         self.subquadrants = Some([
             // Northeast
-            Box::new(Self::new(
+            Box::new(Self::new_with_capacity(
                 Point {
                     x: p.x(),
                     y: self.region.anchor().y(),
                 },
                 self.depth - 1,
+                self.node_capacity,
             )),
             // Northwest
-            Box::new(Self::new(self.region.anchor(), self.depth - 1)),
+            Box::new(Self::new_with_capacity(
+                self.region.anchor(),
+                self.depth - 1,
+                self.node_capacity,
+            )),
             // Southeast
-            Box::new(Self::new(p, self.depth - 1)),
+            Box::new(Self::new_with_capacity(
+                p,
+                self.depth - 1,
+                self.node_capacity,
+            )),
             // Southwest
-            Box::new(Self::new(
+            Box::new(Self::new_with_capacity(
                 Point {
                     x: self.region.anchor().x(),
                     y: p.y(),
                 },
                 self.depth - 1,
+                self.node_capacity,
             )),
         ]);
     }
 
+    // Whether this node holds nothing, either itself or below it.
+    fn is_empty(&self) -> bool {
+        self.kept_handles.is_empty() && self.subquadrants.is_none()
+    }
+
+    // Drops this node's subquadrants if none of them hold anything. Only looks one level down, so
+    // relies on the subquadrants having been pruned first; a subtree which still has subquadrants
+    // is assumed to hold something.
+    fn prune(&mut self) {
+        if let Some(sqs) = self.subquadrants.as_ref() {
+            if sqs.iter().all(|sq| sq.is_empty()) {
+                self.subquadrants = None;
+            }
+        }
+    }
+
     // Strongly-typed alias for U::one() + U::One()
     fn two() -> U {
         U::one() + U::one()
     }
 }
+
+// A pre-order walk over a subtree, yielding each node alongside its level (zero for the node the
+// walk started from). Subquadrants are visited in the order they are stored: [ne, nw, se, sw].
+pub(crate) struct Nodes<'a, U>
+where
+    U: PrimInt + Default,
+{
+    stack: Vec<(&'a QTInner<U>, usize)>,
+}
+
+impl<'a, U> Iterator for Nodes<'a, U>
+where
+    U: PrimInt + Default,
+{
+    type Item = (&'a QTInner<U>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (qt, level) = self.stack.pop()?;
+        if let Some(sqs) = qt.subquadrants.as_ref() {
+            self.stack
+                .extend(sqs.iter().rev().map(|sq| (sq.as_ref(), level + 1)));
+        }
+        Some((qt, level))
+    }
+}
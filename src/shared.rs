@@ -0,0 +1,164 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Quadtree`] which many threads can read at once while one writes. See
+//! [`.into_shared()`].
+//!
+//! [`Quadtree`]: ../struct.Quadtree.html
+//! [`.into_shared()`]: ../struct.Quadtree.html#method.into_shared
+
+use {
+    crate::{area::Area, entry::Entry, Quadtree},
+    num::PrimInt,
+    std::{
+        default::Default,
+        fmt,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// A [`Quadtree`] behind a reader-writer lock, which can be cloned and handed to other threads.
+///
+/// Every clone refers to the same tree. Any number of threads may read it at once, through
+/// [`.read()`], while a write, through [`.write()`] or one of the mutating shortcuts, waits for
+/// them to finish and holds off new readers until it's done. Both take a closure, which is given
+/// the tree for as long as the lock is held, so that entries borrowed from the tree can be used
+/// inside it but never outlive it. The whole read-only API of [`Quadtree`] is available this way.
+///
+/// It can only be sent to, or shared with, another thread if `U` and `V` are both `Send` and
+/// `Sync`.
+///
+/// If a thread panics while writing, the tree may be left half-changed, so every later attempt to
+/// take the lock panics too.
+/// ```
+/// use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+/// use std::thread;
+///
+/// let shared = Quadtree::<u32, u32>::new(4).into_shared();
+/// shared.insert(AreaBuilder::default().anchor(Point {x: 1, y: 1}).build().unwrap(), 10);
+///
+/// let readers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let shared = shared.clone();
+///         thread::spawn(move || shared.read(|qt| qt.values().sum::<u32>()))
+///     })
+///     .collect();
+/// for reader in readers {
+///     assert_eq!(reader.join().unwrap(), 10);
+/// }
+///
+/// shared.write(|qt| qt.modify_all(|v| *v += 1));
+/// assert_eq!(shared.read(|qt| *qt.values().next().unwrap()), 11);
+/// ```
+///
+/// [`Quadtree`]: ../struct.Quadtree.html
+/// [`.read()`]: #method.read
+/// [`.write()`]: #method.write
+pub struct SharedQuadtree<U, V>
+where
+    U: PrimInt + Default,
+{
+    inner: Arc<RwLock<Quadtree<U, V>>>,
+}
+
+impl<U, V> SharedQuadtree<U, V>
+where
+    U: PrimInt + Default,
+{
+    pub(crate) fn new(qt: Quadtree<U, V>) -> SharedQuadtree<U, V> {
+        SharedQuadtree {
+            inner: Arc::new(RwLock::new(qt)),
+        }
+    }
+
+    /// Calls `f` with the tree, holding a read lock until it returns, and returns what it does.
+    pub fn read<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&Quadtree<U, V>) -> R,
+    {
+        f(&self
+            .inner
+            .read()
+            .expect("A thread panicked while writing to this SharedQuadtree."))
+    }
+
+    /// Calls `f` with the tree, holding the write lock until it returns, and returns what it does.
+    pub fn write<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Quadtree<U, V>) -> R,
+    {
+        f(&mut self
+            .inner
+            .write()
+            .expect("A thread panicked while writing to this SharedQuadtree."))
+    }
+
+    /// Takes the write lock to [`.insert()`] a value.
+    ///
+    /// [`.insert()`]: ../struct.Quadtree.html#method.insert
+    pub fn insert(&self, region: Area<U>, val: V) -> Option<u64> {
+        self.write(|qt| qt.insert(region, val))
+    }
+
+    /// Takes the write lock to [`.delete_by_handle()`] an entry.
+    ///
+    /// [`.delete_by_handle()`]: ../struct.Quadtree.html#method.delete_by_handle
+    pub fn delete_by_handle(&self, handle: u64) -> Option<Entry<U, V>> {
+        self.write(|qt| qt.delete_by_handle(handle))
+    }
+
+    /// The number of entries in the tree, under a read lock.
+    pub fn len(&self) -> usize {
+        self.read(|qt| qt.len())
+    }
+
+    /// Whether the tree is empty, under a read lock.
+    pub fn is_empty(&self) -> bool {
+        self.read(|qt| qt.is_empty())
+    }
+
+    /// Unwraps the tree again, or gives back `self` if another clone of it is still around.
+    pub fn into_inner(self) -> Result<Quadtree<U, V>, SharedQuadtree<U, V>> {
+        match Arc::try_unwrap(self.inner) {
+            Ok(lock) => Ok(lock
+                .into_inner()
+                .expect("A thread panicked while writing to this SharedQuadtree.")),
+            Err(inner) => Err(SharedQuadtree { inner }),
+        }
+    }
+}
+
+// Not derived, since cloning the handle shouldn't need U or V to be Clone.
+impl<U, V> Clone for SharedQuadtree<U, V>
+where
+    U: PrimInt + Default,
+{
+    fn clone(&self) -> Self {
+        SharedQuadtree {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<U, V> fmt::Debug for SharedQuadtree<U, V>
+where
+    U: PrimInt + Default + fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedQuadtree")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
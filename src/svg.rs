@@ -0,0 +1,100 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Draws a tree as SVG, behind the `debug-viz` feature.
+//
+// The viewBox is the tree's own region, so coordinates are written out unscaled and the browser
+// fits them to the image. Strokes don't scale with them, so node boundaries stay visible however
+// large the tree is. Nodes are drawn first, outlined in a color for their level, and entries are
+// drawn over them as translucent boxes.
+
+use {
+    crate::{area::Area, qtinner::QTInner, types::StoreType},
+    num::PrimInt,
+    std::{default::Default, fmt::Write},
+};
+
+// The size of the image, in pixels, along its longer side.
+const IMAGE_SIZE: f64 = 512.0;
+
+// One color per level, repeating for deeper trees.
+const LEVEL_COLORS: [&str; 8] = [
+    "#000000", "#1f77b4", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+pub(crate) fn to_svg<U, V>(qt: &QTInner<U>, store: &StoreType<U, V>) -> String
+where
+    U: PrimInt + Default,
+{
+    let root = qt.region();
+    let (x, y, width, height) = coordinates(root);
+    let scale = IMAGE_SIZE / width.max(height);
+
+    // Writing to a String never fails.
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        width * scale,
+        height * scale,
+        x,
+        y,
+        width,
+        height
+    );
+    let _ = writeln!(svg, r#"  <g fill="none">"#);
+    for (node, level) in qt.nodes() {
+        let (x, y, width, height) = coordinates(node.region());
+        let _ = writeln!(
+            svg,
+            r#"    <rect x="{}" y="{}" width="{}" height="{}" stroke="{}" stroke-width="1" vector-effect="non-scaling-stroke"><title>level {}</title></rect>"#,
+            x,
+            y,
+            width,
+            height,
+            LEVEL_COLORS[level % LEVEL_COLORS.len()],
+            level
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+
+    // In handle order, so that the same tree always draws the same way.
+    let mut handles: Vec<&u64> = store.keys().collect();
+    handles.sort_unstable();
+    let _ = writeln!(svg, r##"  <g fill="#ff7f0e" fill-opacity="0.5">"##);
+    for handle in handles {
+        let (x, y, width, height) = coordinates(store[handle].area());
+        let _ = writeln!(
+            svg,
+            r#"    <rect x="{}" y="{}" width="{}" height="{}"><title>handle {}</title></rect>"#,
+            x, y, width, height, handle
+        );
+    }
+    let _ = writeln!(svg, "  </g>");
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn coordinates<U>(area: Area<U>) -> (f64, f64, f64, f64)
+where
+    U: PrimInt + Default,
+{
+    let f = |u: U| u.to_f64().unwrap_or(0.0);
+    (
+        f(area.anchor().x()),
+        f(area.anchor().y()),
+        f(area.width()),
+        f(area.height()),
+    )
+}
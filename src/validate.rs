@@ -0,0 +1,200 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Checks the structure of a tree against its store, behind the `validate` feature.
+//
+// The tree holds each handle at every node its region was pushed down into. So rather than each
+// region being contained by one node, the nodes holding a handle must tile its region exactly:
+// none of them may lie beneath another, and their overlaps with the region must add up to the
+// whole of it. (Sibling subtrees never overlap, so that leaves no room for gaps or double cover.)
+
+use {
+    crate::{area::Area, qtinner::QTInner, types::StoreType},
+    num::PrimInt,
+    std::{
+        collections::{HashMap, HashSet},
+        default::Default,
+        fmt::Debug,
+    },
+};
+
+pub(crate) fn validate<U, V>(qt: &QTInner<U>, store: &StoreType<U, V>) -> Result<(), String>
+where
+    U: PrimInt + Default + Debug,
+{
+    let root = qt.region();
+    for (handle, entry) in store {
+        if entry.handle() != *handle {
+            return Err(format!(
+                "the entry stored under handle {} has handle {}",
+                handle,
+                entry.handle()
+            ));
+        }
+        if *handle >= qt.handle_counter() {
+            return Err(format!(
+                "handle {} hasn't been issued yet; the next is {}",
+                handle,
+                qt.handle_counter()
+            ));
+        }
+        if !root.contains(entry.area()) {
+            return Err(format!(
+                "handle {} has region {:?}, outside the tree's region {:?}",
+                handle,
+                entry.area(),
+                root
+            ));
+        }
+    }
+
+    // How much of each handle's region its nodes cover between them.
+    let mut covered: HashMap<u64, u128> = HashMap::new();
+    validate_node(qt, store, &mut HashSet::new(), &mut covered)?;
+
+    for (handle, entry) in store {
+        let covered = covered.get(handle).copied().unwrap_or(0);
+        if covered != size(entry.area()) {
+            return Err(format!(
+                "handle {} has region {:?} of area {}, but the nodes holding it cover {} of it",
+                handle,
+                entry.area(),
+                size(entry.area()),
+                covered
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Checks @qt and its subtree. @above holds the handles kept by the nodes above @qt.
+fn validate_node<U, V>(
+    qt: &QTInner<U>,
+    store: &StoreType<U, V>,
+    above: &mut HashSet<u64>,
+    covered: &mut HashMap<u64, u128>,
+) -> Result<(), String>
+where
+    U: PrimInt + Default + Debug,
+{
+    let region = qt.region();
+    let mut here = HashSet::new();
+    for handle in qt.handles() {
+        let entry = store.get(handle).ok_or_else(|| {
+            format!(
+                "the node at {:?} holds handle {}, which isn't in the store",
+                region, handle
+            )
+        })?;
+        if !here.insert(*handle) {
+            return Err(format!(
+                "the node at {:?} holds handle {} more than once",
+                region, handle
+            ));
+        }
+        if above.contains(handle) {
+            return Err(format!(
+                "the node at {:?} holds handle {}, which a node above it also holds",
+                region, handle
+            ));
+        }
+        let overlap = entry.area().intersection(region).ok_or_else(|| {
+            format!(
+                "the node at {:?} holds handle {}, whose region {:?} lies outside it",
+                region,
+                handle,
+                entry.area()
+            )
+        })?;
+        if qt.subquadrants().is_some() && !entry.area().contains(region) {
+            return Err(format!(
+                "the node at {:?} has subquadrants, but holds handle {}, whose region {:?} doesn't \
+                 cover it",
+                region,
+                handle,
+                entry.area()
+            ));
+        }
+        let sum = covered.entry(*handle).or_insert(0);
+        *sum = sum.saturating_add(size(overlap));
+    }
+
+    let sqs = match qt.subquadrants().as_ref() {
+        Some(sqs) => sqs,
+        None => return Ok(()),
+    };
+    if qt.depth() == 0 {
+        return Err(format!(
+            "the node at {:?} has subquadrants, but is at the bottom of the tree",
+            region
+        ));
+    }
+    // The subquadrants must tile their parent: each within it, none overlapping another, and
+    // adding up to the whole of it.
+    let mut total: u128 = 0;
+    for (i, sq) in sqs.iter().enumerate() {
+        if sq.depth() + 1 != qt.depth() {
+            return Err(format!(
+                "the node at {:?} has depth {}, but its subquadrant at {:?} has depth {}",
+                region,
+                qt.depth(),
+                sq.region(),
+                sq.depth()
+            ));
+        }
+        if !region.contains(sq.region()) {
+            return Err(format!(
+                "the node at {:?} has a subquadrant at {:?} which doesn't lie within it",
+                region,
+                sq.region()
+            ));
+        }
+        if let Some(other) = sqs[..i].iter().find(|o| o.region().intersects(sq.region())) {
+            return Err(format!(
+                "the node at {:?} has overlapping subquadrants at {:?} and {:?}",
+                region,
+                other.region(),
+                sq.region()
+            ));
+        }
+        total = total.saturating_add(size(sq.region()));
+    }
+    if total != size(region) {
+        return Err(format!(
+            "the subquadrants of the node at {:?} don't cover all of it",
+            region
+        ));
+    }
+
+    above.extend(here.iter().copied());
+    for sq in sqs.iter() {
+        validate_node(sq, store, above, covered)?;
+    }
+    for handle in &here {
+        above.remove(handle);
+    }
+    Ok(())
+}
+
+// The number of cells in @area. Widened, since it can be far larger than U, and saturating, since
+// even that may not be enough for 128-bit coordinates (where the checks on sizes are weaker).
+fn size<U>(area: Area<U>) -> u128
+where
+    U: PrimInt + Default,
+{
+    area.width()
+        .to_u128()
+        .unwrap()
+        .saturating_mul(area.height().to_u128().unwrap())
+}
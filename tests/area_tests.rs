@@ -40,6 +40,35 @@ mod area_tests {
         }
     }
 
+    #[test]
+    fn overflowing_dims() {
+        debug_assert!(AreaBuilder::default()
+            .anchor((i32::MAX - 1, 0).into())
+            .dimensions((10, 1))
+            .build()
+            .is_err());
+        debug_assert!(AreaBuilder::default()
+            .anchor((0, u8::MAX).into())
+            .build()
+            .is_err());
+        debug_assert!(AreaBuilder::default()
+            .anchor((i8::MIN, i8::MIN).into())
+            .dimensions((i8::MAX, i8::MAX))
+            .build()
+            .is_ok());
+
+        // Right up against the bound is fine.
+        let a: Area<i32> = AreaBuilder::default()
+            .anchor((i32::MAX - 10, i32::MAX - 1).into())
+            .dimensions((10, 1))
+            .build()
+            .unwrap();
+        debug_assert_eq!(a.right_edge(), i32::MAX);
+        debug_assert_eq!(a.bottom_edge(), i32::MAX);
+        debug_assert!(a.contains_pt((i32::MAX - 1, i32::MAX - 1).into()));
+        debug_assert!(!a.contains_pt((i32::MAX, i32::MAX).into()));
+    }
+
     #[test]
     fn point_in_all_quadrants() {
         for p in [(1, 1), (-1, 1), (1, -1), (-1, -1)].iter() {
@@ -65,6 +94,215 @@ mod area_tests {
         debug_assert_eq!(a.bottom_edge(), /*4+7*/ 11);
     }
 
+    #[test]
+    fn corners_and_center() {
+        let a = AreaBuilder::default()
+            .anchor((3, 4).into())
+            .dimensions((5, 7))
+            .build()
+            .unwrap();
+
+        debug_assert_eq!(a.top_left(), (3, 4).into());
+        debug_assert_eq!(a.bottom_right(), (8, 11).into());
+        debug_assert_eq!(a.center(), /*(3+5/2, 4+7/2)*/ (5, 7).into());
+    }
+
+    mod intersection_and_union {
+        use super::*;
+
+        fn area(anchor: (i8, i8), dimensions: (i8, i8)) -> Area<i8> {
+            AreaBuilder::default()
+                .anchor(anchor.into())
+                .dimensions(dimensions)
+                .build()
+                .unwrap()
+        }
+
+        #[test]
+        fn overlapping() {
+            debug_assert_eq!(
+                area((0, 0), (4, 3)).intersection(area((2, 1), (5, 5))),
+                Some(area((2, 1), (2, 2)))
+            );
+            debug_assert_eq!(
+                area((-3, -3), (4, 4)).intersection(area((-1, -5), (1, 9))),
+                Some(area((-1, -3), (1, 4)))
+            );
+        }
+
+        #[test]
+        fn contained() {
+            debug_assert_eq!(
+                area((0, 0), (8, 8)).intersection(area((2, 3), (1, 2))),
+                Some(area((2, 3), (1, 2)))
+            );
+            debug_assert_eq!(
+                area((2, 3), (1, 2)).intersection(area((0, 0), (8, 8))),
+                Some(area((2, 3), (1, 2)))
+            );
+            debug_assert_eq!(
+                area((1, 1), (2, 2)).intersection(area((1, 1), (2, 2))),
+                Some(area((1, 1), (2, 2)))
+            );
+        }
+
+        #[test]
+        fn disjoint() {
+            debug_assert_eq!(
+                area((0, 0), (2, 2)).intersection(area((5, 5), (2, 2))),
+                None
+            );
+            debug_assert_eq!(
+                area((0, 0), (2, 2)).intersection(area((0, 5), (2, 2))),
+                None
+            );
+        }
+
+        #[test]
+        fn touching() {
+            // Sharing an edge.
+            debug_assert_eq!(
+                area((0, 0), (2, 2)).intersection(area((2, 0), (2, 2))),
+                None
+            );
+            debug_assert_eq!(
+                area((0, 0), (2, 2)).intersection(area((0, -2), (2, 2))),
+                None
+            );
+            // Sharing a corner.
+            debug_assert_eq!(
+                area((0, 0), (2, 2)).intersection(area((2, 2), (2, 2))),
+                None
+            );
+        }
+
+        #[test]
+        fn touches() {
+            let a = area((0, 0), (2, 2));
+            // Along each edge, all or part of the way.
+            assert!(a.touches(area((2, 0), (2, 2))));
+            assert!(a.touches(area((-3, 1), (3, 5))));
+            assert!(a.touches(area((1, 2), (1, 1))));
+            assert!(a.touches(area((-1, -2), (4, 2))));
+            // At a corner only.
+            assert!(!a.touches(area((2, 2), (2, 2))));
+            assert!(!a.touches(area((-1, -1), (1, 1))));
+            // Overlapping, or apart.
+            assert!(!a.touches(area((1, 1), (2, 2))));
+            assert!(!a.touches(a));
+            assert!(!a.touches(area((3, 0), (2, 2))));
+        }
+
+        #[test]
+        fn touches_is_symmetric() {
+            let a = area((-2, -2), (4, 3));
+            for x in -6..4 {
+                for y in -6..4 {
+                    let b = area((x, y), (2, 3));
+                    debug_assert_eq!(a.touches(b), b.touches(a));
+                    assert!(!(a.touches(b) && a.intersects(b)));
+                }
+            }
+        }
+
+        #[test]
+        fn agrees_with_intersects() {
+            let a = area((-2, -2), (4, 4));
+            for x in -5..5 {
+                for y in -5..5 {
+                    let b = area((x, y), (3, 2));
+                    debug_assert_eq!(a.intersection(b).is_some(), a.intersects(b));
+                }
+            }
+        }
+
+        #[test]
+        fn union() {
+            debug_assert_eq!(
+                area((0, 0), (2, 2)).union(area((5, 1), (1, 4))),
+                area((0, 0), (6, 5))
+            );
+            debug_assert_eq!(
+                area((-3, 2), (1, 1)).union(area((1, -1), (1, 1))),
+                area((-3, -1), (5, 4))
+            );
+            debug_assert_eq!(
+                area((0, 0), (8, 8)).union(area((2, 3), (1, 2))),
+                area((0, 0), (8, 8))
+            );
+        }
+    }
+
+    mod scaled {
+        use super::*;
+
+        fn area<U>(anchor: (U, U), dimensions: (U, U)) -> Area<U>
+        where
+            U: num::PrimInt + Default,
+        {
+            AreaBuilder::default()
+                .anchor(anchor.into())
+                .dimensions(dimensions)
+                .build()
+                .unwrap()
+        }
+
+        #[test]
+        fn identity() {
+            let a = area((-3, 5), (7, 2));
+            debug_assert_eq!(a.scaled(1.0), a);
+        }
+
+        #[test]
+        fn even_changes_stay_centered() {
+            debug_assert_eq!(area((4, 4), (4, 6)).scaled(2.0), area((2, 1), (8, 12)));
+            debug_assert_eq!(area((4, 4), (4, 6)).scaled(0.5), area((5, 5), (2, 3)));
+            debug_assert_eq!(area((-2, -2), (4, 4)).scaled(1.5), area((-3, -3), (6, 6)));
+        }
+
+        #[test]
+        fn odd_changes_round_towards_the_anchor() {
+            debug_assert_eq!(area((0, 0), (3, 3)).scaled(2.0), area((-2, -2), (6, 6)));
+            debug_assert_eq!(area((0, 0), (5, 5)).scaled(0.4), area((1, 1), (2, 2)));
+            // 3 * 1.5 = 4.5 rounds to 5.
+            debug_assert_eq!(area((0, 0), (3, 4)).scaled(1.5), area((-1, -1), (5, 6)));
+        }
+
+        #[test]
+        fn never_smaller_than_a_cell() {
+            debug_assert_eq!(area((0, 0), (4, 5)).scaled(0.0), area((1, 2), (1, 1)));
+            debug_assert_eq!(area((0, 0), (1, 1)).scaled(0.1), area((0, 0), (1, 1)));
+        }
+
+        #[test]
+        fn clipped_to_the_coordinate_type() {
+            debug_assert_eq!(
+                area((2u8, 250), (4, 4)).scaled(4.0),
+                area((0, 244), (12, 11))
+            );
+            debug_assert_eq!(
+                area((0u8, 0), (200, 1)).scaled(10.0),
+                area((0, 0), (255, 5))
+            );
+            debug_assert_eq!(
+                area((-10i8, 0), (20, 1)).scaled(100.0),
+                area((i8::MIN, -50), (i8::MAX, 100))
+            );
+        }
+
+        #[test]
+        #[should_panic]
+        fn negative_factor() {
+            area((0, 0), (2, 2)).scaled(-1.0);
+        }
+
+        #[test]
+        #[should_panic]
+        fn nan_factor() {
+            area((0, 0), (2, 2)).scaled(f64::NAN);
+        }
+    }
+
     // Just positive values.
     mod contains_a {
         use super::*;
@@ -0,0 +1,230 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// For testing .to_bytes() and .from_bytes().
+mod bytes_tests {
+    use quadtree_rs::{area::AreaBuilder, bytes::DecodeError, eviction::EvictionPolicy, Quadtree};
+
+    fn mk_quadtree() -> Quadtree<i16, String> {
+        let mut qt = Quadtree::<i16, String>::with_capacity((-8, -8).into(), 4, 2);
+        qt.extend((0..10).map(|i| ((i - 5, 2 * i - 8), format!("pt{}", i))));
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-8, -8).into())
+                .dimensions((16, 16))
+                .build()
+                .unwrap(),
+            "everything".to_string(),
+        );
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-1, -2).into())
+                .dimensions((3, 5))
+                .build()
+                .unwrap(),
+            String::new(),
+        );
+        qt
+    }
+
+    #[test]
+    fn round_trip() {
+        let qt = mk_quadtree();
+        let copy = Quadtree::<i16, String>::from_bytes(&qt.to_bytes()).unwrap();
+
+        debug_assert!(copy == qt);
+        debug_assert_eq!(copy.anchor(), qt.anchor());
+        debug_assert_eq!(copy.depth(), qt.depth());
+        // The same entries build the same nodes.
+        debug_assert_eq!(copy.node_count(), qt.node_count());
+    }
+
+    #[test]
+    fn round_trip_empty() {
+        let qt = Quadtree::<u64, ()>::new(63);
+        let copy = Quadtree::<u64, ()>::from_bytes(&qt.to_bytes()).unwrap();
+        debug_assert!(copy.is_empty());
+        debug_assert_eq!(copy.depth(), 63);
+    }
+
+    #[test]
+    fn encoding_is_deterministic() {
+        let mut qt = mk_quadtree();
+        debug_assert_eq!(qt.to_bytes(), mk_quadtree().to_bytes());
+
+        // Deleting and reinserting moves an entry to the end.
+        let handle = qt.iter().map(|e| e.handle()).min().unwrap();
+        let entry = qt.delete_by_handle(handle).unwrap();
+        qt.insert(entry.area(), entry.value_ref().clone());
+        debug_assert_ne!(qt.to_bytes(), mk_quadtree().to_bytes());
+    }
+
+    #[test]
+    fn header() {
+        let bytes = Quadtree::<u32, u8>::new(2).to_bytes();
+        debug_assert_eq!(&bytes[..6], b"QTRS\x02\x04");
+        // The anchor, depth, capacity, growable flag, maximum, policy and count, all
+        // little-endian.
+        debug_assert_eq!(bytes[6..14], [0; 8]);
+        debug_assert_eq!(bytes[14..22], [2, 0, 0, 0, 0, 0, 0, 0]);
+        debug_assert_eq!(bytes[30..40], [0; 10]);
+        debug_assert_eq!(bytes.len(), 48);
+    }
+
+    #[test]
+    fn round_trip_max_entries() {
+        let mut qt = Quadtree::<i16, String>::with_max_entries(
+            (-8, -8).into(),
+            4,
+            3,
+            EvictionPolicy::FarthestFromCentroid,
+        );
+        qt.extend((0..3).map(|i| ((i, i), format!("pt{}", i))));
+        let mut copy = Quadtree::<i16, String>::from_bytes(&qt.to_bytes()).unwrap();
+        debug_assert!(copy == qt);
+
+        // Still full, and still evicting the most isolated entry.
+        copy.insert_pt((-8, -8).into(), "far".to_string());
+        qt.insert_pt((-8, -8).into(), "far".to_string());
+        debug_assert_eq!(copy.len(), 3);
+        debug_assert!(copy == qt);
+    }
+
+    #[test]
+    fn round_trip_growable() {
+        let mut qt = Quadtree::<i16, String>::new_growable((0, 0).into(), 2);
+        qt.insert_pt((1, 1).into(), "a".to_string());
+        qt.insert_pt((-3, 6).into(), "b".to_string());
+        let mut copy = Quadtree::<i16, String>::from_bytes(&qt.to_bytes()).unwrap();
+        debug_assert!(copy == qt);
+        debug_assert_eq!((copy.anchor(), copy.depth()), (qt.anchor(), qt.depth()));
+
+        // Still grows.
+        debug_assert!(copy.insert_pt((40, 40).into(), "c".to_string()).is_some());
+        debug_assert!(copy.depth() > qt.depth());
+    }
+
+    #[test]
+    fn reads_version_1() {
+        let mut qt = Quadtree::<u32, u8>::new(2);
+        qt.insert_pt((1, 1).into(), 7);
+        // The same layout, less the growable flag, maximum and policy.
+        let mut bytes = qt.to_bytes();
+        bytes[4] = 1;
+        bytes.drain(30..40);
+
+        let mut copy = Quadtree::<u32, u8>::from_bytes(&bytes).unwrap();
+        debug_assert!(copy == qt);
+        debug_assert_eq!(copy.insert_pt((5, 5).into(), 8), None);
+        copy.extend((0..4).map(|i| ((i, 0), i as u8)));
+        debug_assert_eq!(copy.len(), 5);
+    }
+
+    #[test]
+    fn bad_policy() {
+        let qt = Quadtree::<u32, u8>::with_max_entries((0, 0).into(), 2, 1, EvictionPolicy::Oldest);
+        let mut bytes = qt.to_bytes();
+        bytes[39] = 9;
+        debug_assert!(matches!(
+            Quadtree::<u32, u8>::from_bytes(&bytes),
+            Err(DecodeError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn more_entries_than_the_maximum() {
+        let mut qt =
+            Quadtree::<u32, u8>::with_max_entries((0, 0).into(), 2, 2, EvictionPolicy::Oldest);
+        qt.extend(vec![((0, 0), 0), ((1, 1), 1)]);
+        let mut bytes = qt.to_bytes();
+        // Lower the maximum to one.
+        bytes[31] = 1;
+        debug_assert!(matches!(
+            Quadtree::<u32, u8>::from_bytes(&bytes),
+            Err(DecodeError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn every_truncation_is_an_error() {
+        let bytes = mk_quadtree().to_bytes();
+        for len in 0..bytes.len() {
+            debug_assert!(Quadtree::<i16, String>::from_bytes(&bytes[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn every_corruption_is_handled() {
+        let bytes = mk_quadtree().to_bytes();
+        for i in 0..bytes.len() {
+            for &flip in &[0x01, 0x80, 0xff] {
+                let mut corrupt = bytes.clone();
+                corrupt[i] ^= flip;
+                // Either an error or some other well-formed tree; never a panic.
+                let _ = Quadtree::<i16, String>::from_bytes(&corrupt);
+            }
+        }
+    }
+
+    #[test]
+    fn bad_header() {
+        let mut bytes = Quadtree::<u32, u8>::new(2).to_bytes();
+
+        bytes[0] = b'X';
+        debug_assert_eq!(
+            Quadtree::<u32, u8>::from_bytes(&bytes),
+            Err(DecodeError::BadMagic)
+        );
+
+        bytes[0] = b'Q';
+        bytes[4] = 9;
+        debug_assert_eq!(
+            Quadtree::<u32, u8>::from_bytes(&bytes),
+            Err(DecodeError::UnsupportedVersion(9))
+        );
+    }
+
+    #[test]
+    fn wrong_coordinate_type() {
+        let bytes = Quadtree::<u32, u8>::new(2).to_bytes();
+        debug_assert_eq!(
+            Quadtree::<u16, u8>::from_bytes(&bytes),
+            Err(DecodeError::CoordinateWidth {
+                expected: 2,
+                found: 4
+            })
+        );
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let mut bytes = mk_quadtree().to_bytes();
+        bytes.push(0);
+        debug_assert!(matches!(
+            Quadtree::<i16, String>::from_bytes(&bytes),
+            Err(DecodeError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn depth_too_large() {
+        // A depth-8 tree of u8 would be 256 wide.
+        let mut bytes = Quadtree::<u8, u8>::new(7).to_bytes();
+        bytes[8] = 8;
+        debug_assert!(matches!(
+            Quadtree::<u8, u8>::from_bytes(&bytes),
+            Err(DecodeError::Invalid(_))
+        ));
+    }
+}
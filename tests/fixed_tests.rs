@@ -0,0 +1,101 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// For testing the Fixed fixed-point coordinate helper.
+mod fixed_tests {
+    use quadtree_rs::{fixed::Fixed, point::Point, Quadtree};
+
+    type Q4 = Fixed<4>;
+
+    #[test]
+    fn round_trip() {
+        for &x in &[0.0, 1.0, -1.0, 0.0625, -3.125, 1234.5] {
+            debug_assert_eq!(Q4::from_f64(x).unwrap().to_f64(), x);
+        }
+        debug_assert_eq!(Q4::ONE.raw(), 16);
+        debug_assert_eq!(i64::from(Q4::ONE), 16);
+        debug_assert_eq!(Q4::from_raw(-8).to_f64(), -0.5);
+    }
+
+    #[test]
+    fn from_f64_rounds_to_nearest() {
+        debug_assert_eq!(Q4::from_f64(0.03).unwrap().raw(), 0);
+        debug_assert_eq!(Q4::from_f64(0.04).unwrap().raw(), 1);
+        debug_assert_eq!(Q4::from_f64(-0.04).unwrap().raw(), -1);
+        // Halves round away from zero.
+        debug_assert_eq!(Q4::from_f64(0.03125).unwrap().raw(), 1);
+        debug_assert_eq!(Q4::from_f64(-0.03125).unwrap().raw(), -1);
+    }
+
+    #[test]
+    fn out_of_range_and_non_finite() {
+        debug_assert_eq!(Q4::from_f64(f64::NAN), None);
+        debug_assert_eq!(Q4::from_f64(f64::INFINITY), None);
+        debug_assert_eq!(Q4::from_f64(f64::NEG_INFINITY), None);
+        debug_assert_eq!(Q4::from_f64(2f64.powi(59)), None);
+        debug_assert!(Q4::from_f64(-(2f64.powi(59))).is_some());
+        debug_assert_eq!(Q4::point(f64::NAN, 0.0), None);
+        debug_assert_eq!(Q4::area(0.0, 0.0, f64::NAN, 1.0), None);
+        debug_assert_eq!(Q4::area(0.0, 0.0, 1.0, -1.0), None);
+        debug_assert_eq!(Q4::area(2f64.powi(59) - 1.0, 0.0, 2.0, 1.0), None);
+    }
+
+    #[test]
+    fn point_floors() {
+        debug_assert_eq!(Q4::point(0.99, 1.0), Some(Point { x: 15, y: 16 }));
+        debug_assert_eq!(Q4::point(-0.01, -1.0), Some(Point { x: -1, y: -16 }));
+    }
+
+    #[test]
+    fn area_covers_the_rectangle() {
+        let area = Q4::area(0.5, -0.5, 1.0, 0.25).unwrap();
+        debug_assert_eq!(area.anchor(), Point { x: 8, y: -8 });
+        debug_assert_eq!((area.width(), area.height()), (16, 4));
+
+        // Edges between steps widen the region outwards.
+        let area = Q4::area(0.01, 0.01, 0.1, 0.1).unwrap();
+        debug_assert_eq!(area.anchor(), Point { x: 0, y: 0 });
+        debug_assert_eq!((area.width(), area.height()), (2, 2));
+
+        // Anything thinner than a step still gets a cell.
+        let area = Q4::area(-0.02, 3.0, 0.0, 0.001).unwrap();
+        debug_assert_eq!(area.anchor(), Point { x: -1, y: 48 });
+        debug_assert_eq!((area.width(), area.height()), (1, 1));
+    }
+
+    #[test]
+    fn in_a_quadtree() {
+        // Sixteenths of a unit, over 0.0..8.0.
+        let mut qt = Quadtree::<i64, u8>::new(3 + 4);
+        qt.insert(Q4::area(1.0, 1.0, 0.5, 0.5).unwrap(), 1);
+        qt.insert(Q4::area(1.5, 1.0, 0.5, 0.5).unwrap(), 2);
+        qt.insert_pt(Q4::point(7.97, 7.97).unwrap(), 3);
+
+        let hits = |x, y| -> Vec<u8> {
+            let mut hits: Vec<u8> = qt
+                .get_at_point(Q4::point(x, y).unwrap())
+                .map(|e| *e.value_ref())
+                .collect();
+            hits.sort_unstable();
+            hits
+        };
+        debug_assert_eq!(hits(1.49, 1.2), vec![1]);
+        debug_assert_eq!(hits(1.5, 1.2), vec![2]);
+        debug_assert_eq!(hits(1.2, 1.5), Vec::<u8>::new());
+        debug_assert_eq!(hits(7.99, 7.95), vec![3]);
+
+        let query = Q4::area(1.4, 1.4, 0.2, 0.2).unwrap();
+        debug_assert_eq!(qt.query(query).count(), 2);
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Only built with `--features geo`.
+#![cfg(feature = "geo")]
+
+mod geo_tests {
+    use {
+        geo_types::{coord, Coord, Rect},
+        quadtree_rs::{
+            area::{Area, AreaBuilder},
+            point::Point,
+            Quadtree,
+        },
+        std::convert::{TryFrom, TryInto},
+    };
+
+    #[test]
+    fn coord_round_trip() {
+        let p: Point<i32> = coord! { x: -3, y: 7 }.into();
+        debug_assert_eq!(p, Point { x: -3, y: 7 });
+
+        let c: Coord<i32> = p.into();
+        debug_assert_eq!(c, coord! { x: -3, y: 7 });
+    }
+
+    #[test]
+    fn point_round_trip() {
+        let p: Point<u8> = geo_types::Point::new(1, 2).into();
+        debug_assert_eq!(p, Point { x: 1, y: 2 });
+
+        let gp: geo_types::Point<u8> = p.into();
+        debug_assert_eq!(gp, geo_types::Point::new(1, 2));
+    }
+
+    #[test]
+    fn rect_round_trip() {
+        let rect = Rect::new(coord! { x: 5, y: -1 }, coord! { x: 2, y: 3 });
+        let area = Area::try_from(rect).unwrap();
+        debug_assert_eq!(area.anchor(), (2, -1).into());
+        debug_assert_eq!((area.width(), area.height()), (3, 4));
+        debug_assert_eq!(area.bottom_right(), (5, 3).into());
+
+        debug_assert_eq!(Rect::from(area), rect);
+    }
+
+    #[test]
+    fn rect_at_the_coordinate_bounds() {
+        let rect = Rect::new(coord! { x: 250_u8, y: 0 }, coord! { x: 255, y: 255 });
+        let area = Area::try_from(rect).unwrap();
+        debug_assert_eq!(Rect::from(area), rect);
+    }
+
+    #[test]
+    fn degenerate_rects_are_rejected() {
+        let flat = Rect::new(coord! { x: 0, y: 2 }, coord! { x: 4, y: 2 });
+        debug_assert!(Area::<i32>::try_from(flat).is_err());
+
+        let empty = Rect::new(coord! { x: 1, y: 1 }, coord! { x: 1, y: 1 });
+        debug_assert!(Area::<i32>::try_from(empty).is_err());
+    }
+
+    #[test]
+    fn insert_and_query_geo_types() {
+        let mut qt = Quadtree::<u32, &str>::new(4);
+        let rect = Rect::new(coord! { x: 1, y: 1 }, coord! { x: 3, y: 4 });
+        qt.insert(rect.try_into().unwrap(), "rect").unwrap();
+        qt.insert_pt(geo_types::Point::new(8, 8).into(), "point")
+            .unwrap();
+
+        let region = AreaBuilder::default()
+            .anchor(coord! { x: 0, y: 0 }.into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+        let found: Vec<Rect<u32>> = qt.query(region).map(|e| e.area().into()).collect();
+        debug_assert_eq!(found, [rect]);
+    }
+}
@@ -12,12 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-mod util; // For unordered_elements_are.
+mod util; // For unordered_elements_are and scatter.
 
 use quadtree_rs::{area::AreaBuilder, Quadtree};
 
 mod new {
-    use super::*;
+    use {super::*, quadtree_rs::builder::QuadtreeBuilder};
 
     #[test]
     fn new_with_depth() {
@@ -34,6 +34,100 @@ mod new {
         let _q1 = Quadtree::<u32, u32>::new_with_anchor((0, 510123).into(), 1);
         let _q2 = Quadtree::<u32, f64>::new_with_anchor((4009, 4009).into(), 2);
     }
+
+    #[test]
+    fn new_with_anchor_and_capacity() {
+        let mut qt = Quadtree::<u32, u32>::new_with_anchor_and_capacity((2, 4).into(), 3, 100);
+        debug_assert_eq!(qt.anchor(), (2, 4).into());
+        debug_assert_eq!(qt.depth(), 3);
+        debug_assert!(qt.is_empty());
+
+        qt.reserve(1000);
+        qt.extend((0..64).map(|i| ((2 + i % 8, 4 + i / 8), i)));
+        debug_assert_eq!(qt.len(), 64);
+        // Every cell is filled, as with any other quadtree.
+        debug_assert_eq!(qt.node_count(), 1 + 4 + 16 + 64);
+    }
+
+    #[test]
+    fn default() {
+        let qt = Quadtree::<u32, u8>::default();
+        debug_assert_eq!(qt.anchor(), (0, 0).into());
+        debug_assert_eq!(qt.depth(), quadtree_rs::DEFAULT_DEPTH);
+        debug_assert!(qt.is_empty());
+        debug_assert_eq!(
+            qt,
+            Quadtree::new_with_anchor((0, 0).into(), quadtree_rs::DEFAULT_DEPTH)
+        );
+
+        // None of these should crash, even the narrowest coordinate types.
+        let _q0 = Quadtree::<i8, u8>::default();
+        let _q1 = Quadtree::<u8, u8>::default();
+        let _q2 = Quadtree::<i64, u8>::default();
+    }
+
+    #[test]
+    fn builder_defaults() {
+        let qt: Quadtree<u32, u8> = QuadtreeBuilder::default().depth(3).build().unwrap();
+        debug_assert_eq!(qt, Quadtree::new_with_anchor((0, 0).into(), 3));
+        debug_assert_eq!(qt.root_region(), Quadtree::<u32, u8>::new(3).root_region());
+    }
+
+    #[test]
+    fn builder_options() {
+        let mut qt: Quadtree<i32, u8> = QuadtreeBuilder::default()
+            .anchor((-4, -4).into())
+            .depth(3)
+            .node_capacity(4)
+            .growable(true)
+            .build()
+            .unwrap();
+        debug_assert_eq!(qt.anchor(), (-4, -4).into());
+        debug_assert_eq!(qt.depth(), 3);
+
+        // Four points fit in the root before it splits.
+        qt.extend(vec![((-4, -4), 0), ((3, -4), 1), ((-4, 3), 2), ((3, 3), 3)]);
+        debug_assert_eq!(qt.node_count(), 1);
+
+        // And the tree grows to hold a point outside it.
+        debug_assert!(qt.insert_pt((10, 10).into(), 4).is_some());
+        debug_assert_eq!(qt.depth(), 4);
+    }
+
+    #[test]
+    fn builder_is_reusable() {
+        let mut builder = QuadtreeBuilder::<u32, u8>::default();
+        builder.depth(2);
+        let small = builder.build().unwrap();
+        let large = builder.depth(5).build().unwrap();
+        debug_assert_eq!((small.depth(), large.depth()), (2, 5));
+    }
+
+    #[test]
+    fn builder_errors() {
+        // No depth.
+        debug_assert!(QuadtreeBuilder::<u32, u8>::default().build().is_err());
+        // Too deep for the type.
+        debug_assert!(QuadtreeBuilder::<u8, u8>::default()
+            .depth(8)
+            .build()
+            .is_err());
+        debug_assert!(QuadtreeBuilder::<u8, u8>::default()
+            .depth(7)
+            .build()
+            .is_ok());
+        // Past the largest coordinate.
+        debug_assert!(QuadtreeBuilder::<u8, u8>::default()
+            .depth(7)
+            .anchor((129, 0).into())
+            .build()
+            .is_err());
+        debug_assert!(QuadtreeBuilder::<i8, u8>::default()
+            .depth(2)
+            .anchor((-128, 124).into())
+            .build()
+            .is_err());
+    }
 }
 
 #[test]
@@ -66,9 +160,222 @@ fn width_and_height() {
     debug_assert_eq!(Quadtree::<u32, f32>::new(3).height(), 8);
 }
 
+#[test]
+fn root_region_and_in_bounds() {
+    let qt = Quadtree::<i32, u8>::new_with_anchor((-4, 2).into(), 3);
+    let root = qt.root_region();
+    debug_assert_eq!(root.anchor(), qt.anchor());
+    debug_assert_eq!((root.width() as usize, root.height() as usize), (8, 8));
+
+    let area = |x, y, w, h| {
+        AreaBuilder::default()
+            .anchor((x, y).into())
+            .dimensions((w, h))
+            .build()
+            .unwrap()
+    };
+    debug_assert!(qt.in_bounds(root));
+    debug_assert!(qt.in_bounds(area(-4, 2, 1, 1)));
+    debug_assert!(qt.in_bounds(area(3, 9, 1, 1)));
+    // Just past the far edges.
+    debug_assert!(!qt.in_bounds(area(4, 2, 1, 1)));
+    debug_assert!(!qt.in_bounds(area(-4, 10, 1, 1)));
+    // Overlapping, but not wholly within.
+    debug_assert!(!qt.in_bounds(area(-5, 2, 2, 2)));
+    debug_assert!(!qt.in_bounds(area(-4, 2, 9, 8)));
+}
+
+mod growable {
+    use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+
+    #[test]
+    fn fits_without_growing() {
+        let mut qt = Quadtree::<i32, u8>::new_growable((0, 0).into(), 2);
+        qt.insert_pt((3, 3).into(), 0);
+        debug_assert_eq!((qt.anchor(), qt.depth()), ((0, 0).into(), 2));
+    }
+
+    #[test]
+    fn grows_towards_each_side() {
+        // (x, y) to insert, and the anchor of the grown tree.
+        let cases = [
+            ((5, 1), (0, 0)),
+            ((1, 5), (0, 0)),
+            ((-1, 1), (-4, 0)),
+            ((1, -1), (0, -4)),
+            ((-1, -1), (-4, -4)),
+            ((6, -3), (0, -4)),
+        ];
+        for &(pt, anchor) in cases.iter() {
+            let mut qt = Quadtree::<i32, u8>::new_growable((0, 0).into(), 2);
+            let old = qt.insert_pt((2, 2).into(), 0).unwrap();
+            let new = qt.insert_pt(pt.into(), 1).unwrap();
+
+            debug_assert_eq!(qt.anchor(), anchor.into());
+            debug_assert_eq!((qt.depth(), qt.width(), qt.height()), (3, 8, 8));
+            debug_assert_eq!(qt.get(old).unwrap().anchor(), (2, 2).into());
+            debug_assert_eq!(qt.get(new).unwrap().anchor(), pt.into());
+            debug_assert_eq!(
+                qt.get_at_point((2, 2).into())
+                    .map(|e| e.handle())
+                    .collect::<Vec<_>>(),
+                vec![old]
+            );
+            debug_assert_eq!(
+                qt.get_at_point(pt.into())
+                    .map(|e| e.handle())
+                    .collect::<Vec<_>>(),
+                vec![new]
+            );
+        }
+    }
+
+    #[test]
+    fn grows_as_often_as_needed() {
+        let mut qt = Quadtree::<i32, u8>::new_growable((0, 0).into(), 1);
+        qt.insert_pt((0, 0).into(), 0);
+        // A region much larger than the tree, reaching out on all sides.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-20, -3).into())
+                .dimensions((50, 9))
+                .build()
+                .unwrap(),
+            1,
+        );
+        debug_assert!(qt.depth() > 1);
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert!(qt.root_region().contains_pt((-20, -3).into()));
+        debug_assert!(qt.root_region().contains_pt((29, 5).into()));
+    }
+
+    #[test]
+    fn same_as_a_tree_built_at_the_final_size() {
+        let pts: Vec<((i32, i32), usize)> = (0..40)
+            .map(|i| (((i * 7) % 23 - 11, (i * 5) % 19 - 9), i as usize))
+            .collect();
+
+        let mut grown = Quadtree::<i32, usize>::new_growable((0, 0).into(), 0);
+        grown.extend(pts.iter().copied());
+        debug_assert_eq!(grown.len(), pts.len());
+
+        let mut fixed = Quadtree::<i32, usize>::new_with_anchor(grown.anchor(), grown.depth());
+        fixed.extend(pts.iter().copied());
+
+        debug_assert!(grown == fixed);
+        debug_assert_eq!(grown.node_count(), fixed.node_count());
+        let area = AreaBuilder::default()
+            .anchor((-5, -5).into())
+            .dimensions((8, 6))
+            .build()
+            .unwrap();
+        let mut a: Vec<usize> = grown.query(area).map(|e| *e.value_ref()).collect();
+        let mut b: Vec<usize> = fixed.query(area).map(|e| *e.value_ref()).collect();
+        a.sort_unstable();
+        b.sort_unstable();
+        debug_assert_eq!(a, b);
+    }
+
+    #[test]
+    fn cannot_grow_past_the_coordinate_bounds() {
+        let mut qt = Quadtree::<u8, u8>::new_growable(Point { x: 0, y: 0 }, 7);
+        qt.insert_pt((1, 1).into(), 0);
+
+        // A 256-wide tree doesn't fit in u8.
+        debug_assert_eq!(qt.insert_pt((200, 0).into(), 1), None);
+        // Nor can the anchor move below zero.
+        let mut small = Quadtree::<u8, u8>::new_growable(Point { x: 4, y: 4 }, 1);
+        debug_assert_eq!(small.insert_pt((1, 5).into(), 1), None);
+
+        // Neither tree was grown.
+        debug_assert_eq!((qt.anchor(), qt.depth(), qt.len()), ((0, 0).into(), 7, 1));
+        debug_assert_eq!((small.anchor(), small.depth()), ((4, 4).into(), 1));
+    }
+
+    #[test]
+    fn ordinary_trees_do_not_grow() {
+        let mut qt = Quadtree::<i32, u8>::new_with_anchor((0, 0).into(), 2);
+        debug_assert_eq!(qt.insert_pt((5, 5).into(), 0), None);
+        debug_assert_eq!(qt.depth(), 2);
+    }
+
+    #[test]
+    fn grows_to_merge() {
+        let mut qt = Quadtree::<i32, u8>::new_growable((0, 0).into(), 2);
+        let old = qt.insert_pt((1, 1).into(), 0).unwrap();
+        let mut other = Quadtree::<i32, u8>::new_with_anchor((-8, 0).into(), 4);
+        other.insert_pt((-3, 2).into(), 1);
+        other.insert_pt((6, 6).into(), 2);
+
+        assert!(qt.merge(other).is_ok());
+        debug_assert_eq!((qt.anchor(), qt.depth()), ((-4, 0).into(), 4));
+        debug_assert_eq!(qt.len(), 3);
+        debug_assert_eq!(qt.get(old).unwrap().anchor(), (1, 1).into());
+        debug_assert_eq!(qt.get_at_point((-3, 2).into()).count(), 1);
+        debug_assert_eq!(qt.get_at_point((6, 6).into()).count(), 1);
+    }
+
+    #[test]
+    fn merge_cannot_grow_past_the_coordinate_bounds() {
+        let mut qt = Quadtree::<u8, u8>::new_growable(Point { x: 4, y: 4 }, 1);
+        let mut other = Quadtree::<u8, u8>::new(3);
+        other.insert_pt((1, 5).into(), 1);
+
+        let other = qt.merge(other).unwrap_err();
+        debug_assert_eq!(other.len(), 1);
+        debug_assert_eq!((qt.anchor(), qt.depth(), qt.len()), ((4, 4).into(), 1, 0));
+    }
+
+    #[test]
+    fn grows_to_relocate() {
+        let mut qt = Quadtree::<i32, u8>::new_growable((0, 0).into(), 2);
+        let handle = qt.insert_pt((1, 1).into(), 0).unwrap();
+        let region = AreaBuilder::default()
+            .anchor((5, -2).into())
+            .build()
+            .unwrap();
+
+        assert!(qt.relocate(handle, region));
+        debug_assert_eq!((qt.anchor(), qt.depth()), ((0, -4).into(), 3));
+        debug_assert_eq!(qt.get(handle).unwrap().area(), region);
+        debug_assert_eq!(qt.get_at_point((5, -2).into()).count(), 1);
+        debug_assert_eq!(qt.get_at_point((1, 1).into()).count(), 0);
+    }
+
+    #[test]
+    fn relocating_a_missing_handle_does_not_grow() {
+        let mut qt = Quadtree::<i32, u8>::new_growable((0, 0).into(), 2);
+        let handle = qt.insert_pt((1, 1).into(), 0).unwrap();
+        let region = AreaBuilder::default()
+            .anchor((5, -2).into())
+            .build()
+            .unwrap();
+
+        assert!(!qt.relocate(handle + 1, region));
+        debug_assert_eq!((qt.anchor(), qt.depth()), ((0, 0).into(), 2));
+    }
+}
+
 mod insert {
     use super::*;
 
+    #[test]
+    fn handles_are_deterministic() {
+        let run = || {
+            let mut qt = Quadtree::<u32, u8>::new(3);
+            let mut handles = vec![];
+            handles.push(qt.insert_pt((1, 1).into(), 1));
+            handles.push(qt.insert_pt((9, 9).into(), 2));
+            handles.push(qt.insert_pt((2, 5).into(), 3));
+            qt.delete_by_handle(0);
+            handles.push(qt.insert_pt((1, 1).into(), 4));
+            handles
+        };
+        // Sequential, skipping the failed insertion, and not reusing the deleted handle.
+        debug_assert_eq!(run(), vec![Some(0), None, Some(1), Some(2)]);
+        debug_assert_eq!(run(), run());
+    }
+
     #[test]
     fn insert_successful() {
         let mut qt = Quadtree::<u32, u8>::new(2);
@@ -155,6 +462,419 @@ mod insert {
     }
 }
 
+mod upsert {
+    use {super::*, crate::util::unordered_elements_are, quadtree_rs::area::Area};
+
+    fn region(anchor: (u32, u32), dimensions: (u32, u32)) -> Area<u32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dimensions)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn inserts_then_replaces() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        debug_assert_eq!(qt.upsert(region((1, 1), (2, 2)), 1), None);
+        let handle = qt.query(region((1, 1), (1, 1))).next().unwrap().handle();
+
+        debug_assert_eq!(qt.upsert(region((1, 1), (2, 2)), 2), Some(1));
+        debug_assert_eq!(qt.len(), 1);
+        // The entry kept its handle.
+        debug_assert_eq!(qt.get(handle).unwrap().value_ref(), &2);
+    }
+
+    #[test]
+    fn only_exact_matches_are_replaced() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.insert(region((0, 0), (4, 4)), 1);
+        qt.insert(region((1, 1), (1, 1)), 2);
+
+        // Inside the first, and containing the second, but equal to neither.
+        debug_assert_eq!(qt.upsert(region((1, 1), (2, 2)), 3), None);
+        debug_assert_eq!(qt.len(), 3);
+        debug_assert!(unordered_elements_are(qt.values().copied(), vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn replaces_the_oldest_of_several() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        let first = qt.insert(region((2, 0), (3, 1)), 1).unwrap();
+        let second = qt.insert(region((2, 0), (3, 1)), 2).unwrap();
+
+        debug_assert_eq!(qt.upsert(region((2, 0), (3, 1)), 3), Some(1));
+        debug_assert_eq!(qt.get(first).unwrap().value_ref(), &3);
+        debug_assert_eq!(qt.get(second).unwrap().value_ref(), &2);
+    }
+
+    #[test]
+    fn tile_map() {
+        // A 4x4 grid of 2x2 tiles.
+        let mut qt = Quadtree::<u32, char>::new(3);
+        for i in 0..16 {
+            let tile = region((i % 4 * 2, i / 4 * 2), (2, 2));
+            debug_assert_eq!(qt.upsert(tile, '.'), None);
+        }
+        debug_assert_eq!(qt.upsert(region((2, 4), (2, 2)), '#'), Some('.'));
+        debug_assert_eq!(qt.upsert(region((2, 4), (2, 2)), '~'), Some('#'));
+
+        debug_assert_eq!(qt.len(), 16);
+        let at = |x, y| -> Vec<char> {
+            qt.get_at_point((x, y).into())
+                .map(|e| *e.value_ref())
+                .collect()
+        };
+        debug_assert_eq!(at(3, 5), vec!['~']);
+        debug_assert_eq!(at(4, 5), vec!['.']);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut qt = Quadtree::<u32, u8>::new(2);
+        debug_assert_eq!(qt.upsert(region((3, 3), (2, 2)), 1), None);
+        debug_assert!(qt.is_empty());
+    }
+}
+
+mod max_entries {
+    use {
+        super::*,
+        quadtree_rs::{builder::QuadtreeBuilder, eviction::EvictionPolicy},
+    };
+
+    #[test]
+    fn oldest() {
+        let mut qt =
+            Quadtree::<u32, u32>::with_max_entries((0, 0).into(), 4, 3, EvictionPolicy::Oldest);
+        for i in 0..10 {
+            qt.insert_pt((i, i).into(), i);
+            assert!(qt.len() <= 3);
+        }
+        let mut values: Vec<u32> = qt.values().copied().collect();
+        values.sort_unstable();
+        debug_assert_eq!(values, vec![7, 8, 9]);
+        // Evicted entries are gone from the tree, not just the store.
+        debug_assert_eq!(qt.get_at_point((1, 1).into()).count(), 0);
+    }
+
+    #[test]
+    fn insert_evicting() {
+        let mut qt =
+            Quadtree::<u32, char>::with_max_entries((0, 0).into(), 4, 2, EvictionPolicy::Oldest);
+        let pt = |x, y| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .build()
+                .unwrap()
+        };
+        let (a, evicted) = qt.insert_evicting(pt(1, 1), 'a').unwrap();
+        assert!(evicted.is_none());
+        qt.insert_evicting(pt(2, 2), 'b').unwrap();
+        let (_, evicted) = qt.insert_evicting(pt(3, 3), 'c').unwrap();
+        let evicted = evicted.unwrap();
+        debug_assert_eq!((evicted.handle(), *evicted.value_ref()), (a, 'a'));
+
+        // Out of bounds, so nothing is evicted either.
+        assert!(qt.insert_evicting(pt(20, 20), 'd').is_none());
+        debug_assert_eq!(qt.len(), 2);
+
+        // Deleting makes room again.
+        qt.delete(pt(2, 2));
+        let (_, evicted) = qt.insert_evicting(pt(4, 4), 'e').unwrap();
+        assert!(evicted.is_none());
+    }
+
+    #[test]
+    fn farthest_from_centroid() {
+        let mut qt = Quadtree::<i32, char>::with_max_entries(
+            (0, 0).into(),
+            5,
+            4,
+            EvictionPolicy::FarthestFromCentroid,
+        );
+        qt.extend(vec![((10, 10), 'a'), ((11, 10), 'b'), ((0, 31), 'c')]);
+        // A large region counts from its center, at (12, 12).
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((10, 10).into())
+                .dimensions((5, 5))
+                .build()
+                .unwrap(),
+            'd',
+        );
+        qt.insert_pt((30, 30).into(), 'e');
+        let mut values: Vec<char> = qt.values().copied().collect();
+        values.sort_unstable();
+        debug_assert_eq!(values, vec!['a', 'b', 'd', 'e']);
+
+        // The new entry is never the one evicted, even when it's the farthest.
+        qt.insert_pt((31, 0).into(), 'f');
+        let mut values: Vec<char> = qt.values().copied().collect();
+        values.sort_unstable();
+        debug_assert_eq!(values, vec!['a', 'b', 'd', 'f']);
+    }
+
+    #[test]
+    fn farthest_from_centroid_ties_go_to_the_oldest() {
+        let mut qt = Quadtree::<i32, char>::with_max_entries(
+            (0, 0).into(),
+            4,
+            2,
+            EvictionPolicy::FarthestFromCentroid,
+        );
+        let a = qt.insert_pt((2, 2).into(), 'a').unwrap();
+        qt.insert_pt((6, 6).into(), 'b');
+        qt.insert_pt((4, 4).into(), 'c');
+        assert!(qt.get(a).is_none());
+        debug_assert_eq!(qt.len(), 2);
+    }
+
+    #[test]
+    fn merge_evicts() {
+        let mut qt =
+            Quadtree::<u32, u8>::with_max_entries((0, 0).into(), 4, 3, EvictionPolicy::Oldest);
+        qt.extend(vec![((0, 0), 0), ((1, 1), 1)]);
+        let mut other = Quadtree::<u32, u8>::new(4);
+        other.extend(vec![((2, 2), 2), ((3, 3), 3)]);
+        assert!(qt.merge(other).is_ok());
+        debug_assert_eq!(qt.len(), 3);
+        debug_assert_eq!(qt.get_at_point((0, 0).into()).count(), 0);
+    }
+
+    #[test]
+    fn carried_over_by_copies() {
+        let mut qt =
+            Quadtree::<u32, u8>::with_max_entries((0, 0).into(), 4, 2, EvictionPolicy::Oldest);
+        qt.extend(vec![((0, 0), 0), ((1, 1), 1)]);
+        let mut copy = qt.clone_with_new_handles();
+        copy.insert_pt((2, 2).into(), 2);
+        debug_assert_eq!(copy.len(), 2);
+        let mut labels = qt.map(|v| v.to_string());
+        labels.insert_pt((2, 2).into(), "2".to_string());
+        debug_assert_eq!(labels.len(), 2);
+    }
+
+    #[test]
+    fn builder() {
+        let mut qt: Quadtree<u32, u8> = QuadtreeBuilder::default()
+            .depth(4)
+            .max_entries(1, EvictionPolicy::Oldest)
+            .build()
+            .unwrap();
+        qt.insert_pt((0, 0).into(), 0);
+        qt.insert_pt((1, 1).into(), 1);
+        debug_assert_eq!(qt.values().collect::<Vec<_>>(), vec![&1]);
+
+        assert!(QuadtreeBuilder::<u32, u8>::default()
+            .depth(4)
+            .max_entries(0, EvictionPolicy::Oldest)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero() {
+        let _qt =
+            Quadtree::<u32, u8>::with_max_entries((0, 0).into(), 4, 0, EvictionPolicy::Oldest);
+    }
+}
+
+mod swap_values {
+    use super::*;
+
+    #[test]
+    fn swap_values() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        let a = qt.insert_pt((1, 2).into(), 'a').unwrap();
+        let b = qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((6, 6).into())
+                    .dimensions((3, 2))
+                    .build()
+                    .unwrap(),
+                'b',
+            )
+            .unwrap();
+        assert!(qt.swap_values(a, b));
+
+        let at = |qt: &Quadtree<u32, char>, x, y| -> Vec<char> {
+            qt.get_at_point((x, y).into())
+                .map(|e| *e.value_ref())
+                .collect()
+        };
+        debug_assert_eq!(at(&qt, 1, 2), vec!['b']);
+        debug_assert_eq!(at(&qt, 8, 7), vec!['a']);
+        // The handles stay with the regions.
+        debug_assert_eq!(qt.get(a).unwrap().anchor(), (1, 2).into());
+        debug_assert_eq!(qt.get(b).unwrap().anchor(), (6, 6).into());
+
+        // And back again.
+        assert!(qt.swap_values(b, a));
+        debug_assert_eq!(at(&qt, 1, 2), vec!['a']);
+        debug_assert_eq!(at(&qt, 8, 7), vec!['b']);
+    }
+
+    #[test]
+    fn swap_values_with_self() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        let a = qt.insert_pt((1, 2).into(), 'a').unwrap();
+        assert!(qt.swap_values(a, a));
+        debug_assert_eq!(qt.get(a).unwrap().value_ref(), &'a');
+    }
+
+    #[test]
+    fn swap_values_unknown_handle() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        let a = qt.insert_pt((1, 2).into(), 'a').unwrap();
+        let b = qt.insert_pt((3, 4).into(), 'b').unwrap();
+        qt.delete_by_handle(b);
+        assert!(!qt.swap_values(a, b));
+        assert!(!qt.swap_values(b, a));
+        assert!(!qt.swap_values(b, b));
+        assert!(!qt.swap_values(a, 99));
+        debug_assert_eq!(qt.get(a).unwrap().value_ref(), &'a');
+        debug_assert_eq!(qt.len(), 1);
+    }
+}
+
+mod try_insert {
+    use {super::*, quadtree_rs::error::QuadtreeError};
+
+    #[test]
+    fn inserted() {
+        let mut qt = Quadtree::<i8, u8>::new_with_anchor((-4, -4).into(), 3);
+        let handle = qt.try_insert((-4, -4).into(), (8, 8), 1).unwrap();
+        debug_assert_eq!(qt.get(handle).map(|e| *e.value_ref()), Some(1));
+    }
+
+    #[test]
+    fn each_error() {
+        let mut qt = Quadtree::<i8, u8>::new_with_anchor((-4, -4).into(), 3);
+        debug_assert_eq!(
+            qt.try_insert((0, 0).into(), (0, 1), 1),
+            Err(QuadtreeError::ZeroArea)
+        );
+        debug_assert_eq!(
+            qt.try_insert((0, 0).into(), (1, -1), 1),
+            Err(QuadtreeError::ZeroArea)
+        );
+        debug_assert_eq!(
+            qt.try_insert((120, 0).into(), (8, 1), 1),
+            Err(QuadtreeError::Overflow)
+        );
+        debug_assert_eq!(qt.try_insert((0, 0).into(), (1, 1), 1).map(|_| ()), Ok(()));
+        debug_assert_eq!(
+            qt.try_insert((3, 3).into(), (2, 1), 1),
+            Err(QuadtreeError::OutOfBounds)
+        );
+        debug_assert_eq!(
+            qt.try_insert((-5, 0).into(), (1, 1), 1),
+            Err(QuadtreeError::OutOfBounds)
+        );
+        debug_assert_eq!(qt.len(), 1);
+    }
+
+    #[test]
+    fn grows_if_growable() {
+        let mut qt = Quadtree::<i8, u8>::new_growable((0, 0).into(), 2);
+        debug_assert!(qt.try_insert((5, 5).into(), (1, 1), 1).is_ok());
+        debug_assert_eq!(
+            qt.try_insert((100, 0).into(), (20, 1), 1),
+            Err(QuadtreeError::OutOfBounds)
+        );
+    }
+}
+
+mod insert_batch {
+    use super::*;
+
+    #[test]
+    fn handles_in_input_order() {
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        let handles = qt.insert_batch((0..8).map(|i| ((i, 15 - i), (1, 1), i)));
+        debug_assert_eq!(handles.len(), 8);
+        debug_assert_eq!(qt.len(), 8);
+        for (i, handle) in handles.into_iter().enumerate() {
+            let entry = qt.get(handle).unwrap();
+            debug_assert_eq!(*entry.value_ref(), i as u32);
+            debug_assert_eq!(entry.anchor(), (i as u32, 15 - i as u32).into());
+        }
+    }
+
+    #[test]
+    fn skips_what_doesnt_fit() {
+        let mut qt = Quadtree::<i8, char>::new_with_anchor((-4, -4).into(), 3);
+        let handles = qt.insert_batch(vec![
+            ((-4, -4), (2, 2), 'a'),
+            ((0, 0), (0, 1), 'b'),
+            ((120, 0), (8, 1), 'c'),
+            ((3, 3), (2, 1), 'd'),
+            ((1, 1), (3, 3), 'e'),
+        ]);
+        debug_assert_eq!(handles.len(), qt.len());
+        debug_assert_eq!(
+            handles
+                .iter()
+                .map(|&h| *qt.get(h).unwrap().value_ref())
+                .collect::<String>(),
+            "ae"
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        assert!(qt.insert_batch(vec![]).is_empty());
+        assert!(qt.is_empty());
+    }
+}
+
+mod insert_dedup {
+    use {super::*, quadtree_rs::area::Area};
+
+    fn region(anchor: (u32, u32), dimensions: (u32, u32)) -> Area<u32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dimensions)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn coalesces_equal_entries() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        debug_assert!(qt.insert_dedup(region((1, 1), (3, 2)), 'a'));
+        debug_assert!(!qt.insert_dedup(region((1, 1), (3, 2)), 'a'));
+        debug_assert_eq!(qt.len(), 1);
+
+        // Plain inserts still always add.
+        debug_assert!(qt.insert(region((1, 1), (3, 2)), 'a').is_some());
+        debug_assert_eq!(qt.len(), 2);
+    }
+
+    #[test]
+    fn different_regions_or_values_are_kept() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        debug_assert!(qt.insert_dedup(region((1, 1), (3, 2)), 'a'));
+        debug_assert!(qt.insert_dedup(region((1, 1), (3, 2)), 'b'));
+        // Inside, containing, and offset from the first.
+        debug_assert!(qt.insert_dedup(region((1, 1), (1, 1)), 'a'));
+        debug_assert!(qt.insert_dedup(region((0, 0), (8, 8)), 'a'));
+        debug_assert!(qt.insert_dedup(region((2, 1), (3, 2)), 'a'));
+        debug_assert_eq!(qt.len(), 5);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut qt = Quadtree::<u32, char>::new(2);
+        debug_assert!(!qt.insert_dedup(region((3, 3), (2, 2)), 'a'));
+        debug_assert!(qt.is_empty());
+    }
+}
+
 #[test]
 fn len() {
     let mut qt = Quadtree::<u32, u32>::new(4);
@@ -193,26 +913,564 @@ fn len() {
     debug_assert_eq!(qt.len(), 3);
 }
 
-#[test]
-fn fill_quadrant() {
-    let mut qt = Quadtree::<u8, f64>::new(2);
-    debug_assert!(qt.is_empty());
+mod insert_grouped {
+    use {super::*, quadtree_rs::area::Area};
 
-    assert!(qt
-        .insert(
-            AreaBuilder::default()
-                .anchor((0, 0).into())
-                .dimensions((2, 2))
-                .build()
-                .unwrap(),
-            49.17,
-        )
-        .is_some());
-    // This should 100% fill one quadrant.
-    debug_assert_eq!(qt.len(), 1);
-    debug_assert!(!qt.is_empty());
+    fn region(anchor: (u32, u32), dimensions: (u32, u32)) -> Area<u32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dimensions)
+            .build()
+            .unwrap()
+    }
 
-    assert!(qt
+    #[test]
+    fn groups_by_exact_region() {
+        let mut qt = Quadtree::<u32, Vec<u8>>::new(4);
+        let a = qt.insert_grouped(region((1, 1), (3, 2)), 1).unwrap();
+        debug_assert_eq!(qt.insert_grouped(region((1, 1), (3, 2)), 2), Some(a));
+        // Overlapping, and sharing an anchor, but not the same region.
+        let b = qt.insert_grouped(region((1, 1), (3, 3)), 3).unwrap();
+        let c = qt.insert_grouped(region((2, 1), (1, 1)), 4).unwrap();
+        debug_assert_eq!(qt.insert_grouped(region((1, 1), (3, 2)), 5), Some(a));
+
+        debug_assert_eq!(qt.len(), 3);
+        debug_assert_ne!(a, b);
+        debug_assert_ne!(b, c);
+        debug_assert_eq!(qt.get(a).unwrap().value_ref(), &vec![1, 2, 5]);
+        debug_assert_eq!(qt.get(b).unwrap().value_ref(), &vec![3]);
+        debug_assert_eq!(qt.get(c).unwrap().value_ref(), &vec![4]);
+    }
+
+    #[test]
+    fn joins_plainly_inserted_entries() {
+        let mut qt = Quadtree::<u32, Vec<u8>>::new(4);
+        let handle = qt.insert(region((4, 4), (2, 2)), vec![1, 2]).unwrap();
+        debug_assert_eq!(qt.insert_grouped(region((4, 4), (2, 2)), 3), Some(handle));
+        debug_assert_eq!(qt.get(handle).unwrap().value_ref(), &vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut qt = Quadtree::<u32, Vec<u8>>::new(2);
+        debug_assert_eq!(qt.insert_grouped(region((3, 3), (2, 2)), 1), None);
+        debug_assert!(qt.is_empty());
+    }
+
+    #[test]
+    fn query_grouped() {
+        let mut qt = Quadtree::<u32, Vec<u8>>::new(4);
+        for i in 0..5 {
+            qt.insert_grouped(region((0, 0), (2, 2)), i);
+        }
+        qt.insert_grouped(region((8, 8), (4, 4)), 10);
+        qt.insert_grouped(region((8, 8), (4, 4)), 11);
+
+        let mut found: Vec<(Area<u32>, Vec<u8>)> = qt
+            .query_grouped(region((1, 1), (8, 8)))
+            .map(|(area, values)| (area, values.to_vec()))
+            .collect();
+        found.sort_by_key(|(area, _)| area.anchor().x());
+        debug_assert_eq!(
+            found,
+            vec![
+                (region((0, 0), (2, 2)), vec![0, 1, 2, 3, 4]),
+                (region((8, 8), (4, 4)), vec![10, 11]),
+            ]
+        );
+
+        // Only the first bucket lies wholly within.
+        let strict: Vec<Area<u32>> = qt
+            .query_grouped_strict(region((0, 0), (8, 8)))
+            .map(|(area, _)| area)
+            .collect();
+        debug_assert_eq!(strict, vec![region((0, 0), (2, 2))]);
+    }
+}
+
+mod statistics {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let qt = Quadtree::<u32, u8>::new(4);
+        debug_assert_eq!(qt.max_depth(), 0);
+        debug_assert_eq!(qt.node_count(), 1);
+        debug_assert_eq!(qt.leaf_count(), 1);
+    }
+
+    #[test]
+    fn region_held_high_up() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        // Exactly one subquadrant of the root.
+        assert!(qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((8, 0).into())
+                    .dimensions((8, 8))
+                    .build()
+                    .unwrap(),
+                1,
+            )
+            .is_some());
+        debug_assert_eq!(qt.max_depth(), 1);
+        debug_assert_eq!(qt.node_count(), 5);
+        debug_assert_eq!(qt.leaf_count(), 4);
+    }
+
+    #[test]
+    fn points_sink_to_the_bottom() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        assert!(qt.insert_pt((0, 0).into(), 1).is_some());
+        debug_assert_eq!(qt.max_depth(), 4);
+        debug_assert_eq!(qt.node_count(), 1 + 4 * 4);
+        debug_assert_eq!(qt.leaf_count(), 1 + 3 * 4);
+
+        // A second point with the same parent as the first adds no nodes.
+        assert!(qt.insert_pt((1, 1).into(), 2).is_some());
+        debug_assert_eq!(qt.node_count(), 1 + 4 * 4);
+
+        // One in the opposite corner adds a whole new path.
+        assert!(qt.insert_pt((15, 15).into(), 3).is_some());
+        debug_assert_eq!(qt.node_count(), 1 + 4 * 4 + 3 * 4);
+        debug_assert_eq!(qt.leaf_count(), 1 + 3 * 4 + 3 * 3);
+    }
+
+    #[test]
+    fn visit_nodes() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        let big = qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((8, 0).into())
+                    .dimensions((8, 8))
+                    .build()
+                    .unwrap(),
+                1,
+            )
+            .unwrap();
+        let small = qt.insert_pt((0, 0).into(), 2).unwrap();
+
+        let mut visited = Vec::new();
+        qt.visit_nodes(|region, handles, level| {
+            visited.push((*region, handles.to_vec(), level));
+        });
+
+        // Once per node, starting from the root.
+        debug_assert_eq!(visited.len(), qt.node_count());
+        debug_assert_eq!(visited[0].0.anchor(), qt.anchor());
+        debug_assert_eq!(visited[0].2, 0);
+        debug_assert_eq!(
+            visited.iter().map(|(_, _, level)| *level).max().unwrap(),
+            qt.max_depth()
+        );
+
+        // Each region is kept exactly once, at the level it sank to.
+        let keepers = |handle| {
+            visited
+                .iter()
+                .filter(|(_, handles, _)| handles.contains(&handle))
+                .map(|(region, _, level)| (region.anchor(), region.width(), *level))
+                .collect::<Vec<_>>()
+        };
+        debug_assert_eq!(keepers(big), vec![((8, 0).into(), 8, 1)]);
+        debug_assert_eq!(keepers(small), vec![((0, 0).into(), 1, 4)]);
+
+        // Walking the tree again visits the nodes in the same order.
+        let mut again = Vec::new();
+        qt.visit_nodes(|region, _, _| again.push(*region));
+        debug_assert_eq!(
+            again,
+            visited
+                .iter()
+                .map(|(region, _, _)| *region)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn entries_per_depth() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        debug_assert_eq!(qt.entries_per_depth(), vec![0; 5]);
+
+        // The whole tree, then one subquadrant of the root, then a point.
+        for (anchor, size) in [((0, 0), 16), ((8, 0), 8), ((3, 3), 1)] {
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor(anchor.into())
+                    .dimensions((size, size))
+                    .build()
+                    .unwrap(),
+                1,
+            );
+        }
+        debug_assert_eq!(qt.entries_per_depth(), vec![1, 1, 0, 0, 1]);
+
+        // Agrees with visit_nodes().
+        let mut per_level = vec![0; qt.depth() + 1];
+        qt.visit_nodes(|_, handles, level| per_level[level] += handles.len());
+        debug_assert_eq!(qt.entries_per_depth(), per_level);
+        debug_assert_eq!(qt.entries_per_depth().iter().sum::<usize>(), qt.len());
+    }
+
+    #[test]
+    fn coverage_area() {
+        let regions = [
+            (-8, -8, 16, 16),
+            (-7, -3, 5, 9),
+            (-4, -6, 9, 2),
+            (1, 1, 6, 6),
+            (3, -5, 1, 1),
+            (0, 0, 1, 1),
+        ];
+        // Counts the covered cells one by one, for regions[skip..].
+        let brute_force = |skip: usize| {
+            let mut covered = 0;
+            for x in -8..8 {
+                for y in -8..8 {
+                    if regions[skip..]
+                        .iter()
+                        .any(|&(rx, ry, w, h)| rx <= x && x < rx + w && ry <= y && y < ry + h)
+                    {
+                        covered += 1;
+                    }
+                }
+            }
+            covered
+        };
+        for capacity in [0, 1, 4] {
+            let mut qt =
+                Quadtree::<i32, u8>::new_with_anchor_and_capacity((-8, -8).into(), 4, capacity);
+            debug_assert_eq!(qt.coverage_area(), 0);
+            debug_assert_eq!(qt.total_entry_area(), 0);
+
+            let handles: Vec<u64> = regions
+                .iter()
+                .map(|&(x, y, w, h)| {
+                    qt.insert(
+                        AreaBuilder::default()
+                            .anchor((x, y).into())
+                            .dimensions((w, h))
+                            .build()
+                            .unwrap(),
+                        0,
+                    )
+                    .unwrap()
+                })
+                .collect();
+            debug_assert_eq!(qt.coverage_area(), 256);
+            debug_assert_eq!(
+                qt.total_entry_area(),
+                regions.iter().map(|&(_, _, w, h)| (w * h) as u64).sum()
+            );
+
+            // Without the region covering the whole tree, the others overlap in places.
+            qt.delete_by_handle(handles[0]);
+            debug_assert_eq!(qt.coverage_area(), brute_force(1));
+            debug_assert!(qt.coverage_area() < qt.total_entry_area());
+        }
+    }
+
+    #[test]
+    fn coverage_area_saturates() {
+        let mut qt = Quadtree::<u64, u8>::new(40);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((1 << 40, 1 << 40))
+                .build()
+                .unwrap(),
+            1,
+        );
+        debug_assert_eq!(qt.coverage_area(), u64::MAX);
+        debug_assert_eq!(qt.total_entry_area(), u64::MAX);
+    }
+
+    #[test]
+    fn extent() {
+        let mut qt = Quadtree::<i8, u8>::new_with_anchor((-8, -8).into(), 4);
+        debug_assert!(qt.extent().is_none());
+
+        let handle = qt.insert_pt((-8, -8).into(), 1).unwrap();
+        let extent = qt.extent().unwrap();
+        debug_assert_eq!(extent.anchor(), (-8, -8).into());
+        debug_assert_eq!((extent.width(), extent.height()), (1, 1));
+
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, -3).into())
+                .dimensions((5, 2))
+                .build()
+                .unwrap(),
+            2,
+        );
+        qt.insert_pt((0, 4).into(), 3);
+        let extent = qt.extent().unwrap();
+        debug_assert_eq!(extent.anchor(), (-8, -8).into());
+        debug_assert_eq!(extent.bottom_right(), (7, 5).into());
+
+        // Shrinks back down after deletions.
+        qt.delete_by_handle(handle);
+        let extent = qt.extent().unwrap();
+        debug_assert_eq!(extent.anchor(), (0, -3).into());
+        debug_assert_eq!(extent.bottom_right(), (7, 5).into());
+
+        qt.reset();
+        debug_assert!(qt.extent().is_none());
+    }
+
+    #[test]
+    fn density_grid() {
+        let mut qt = Quadtree::<i8, u8>::new_with_anchor((-4, -4).into(), 3);
+        debug_assert_eq!(qt.density_grid(2, 3), vec![vec![0, 0]; 3]);
+
+        // One entry in every cell of the tree.
+        for x in -4..4 {
+            for y in -4..4 {
+                qt.insert_pt((x, y).into(), 0);
+            }
+        }
+        // Every cell of a grid which divides the tree evenly gets the same share.
+        debug_assert_eq!(qt.density_grid(4, 2), vec![vec![8; 4]; 2]);
+        // Otherwise, cells split at the first coordinate past their share: here at x = -4, -1, 2.
+        debug_assert_eq!(qt.density_grid(3, 1), vec![vec![24, 24, 16]]);
+        // More cells than coordinates leaves some empty.
+        let fine = qt.density_grid(16, 1);
+        debug_assert_eq!(fine[0].iter().filter(|&&n| n == 8).count(), 8);
+        debug_assert_eq!(fine[0].iter().sum::<usize>(), 64);
+
+        debug_assert!(qt.density_grid(3, 0).is_empty());
+        debug_assert_eq!(qt.density_grid(0, 2), vec![Vec::<usize>::new(); 2]);
+    }
+
+    #[test]
+    fn density_grid_counts_regions_at_their_centers() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        // Covers most of the tree, but is centered at (7, 7).
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((15, 15))
+                .build()
+                .unwrap(),
+            1,
+        );
+        // Centered exactly on the middle of the tree.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((6, 6).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+            2,
+        );
+        debug_assert_eq!(qt.density_grid(2, 2), vec![vec![1, 0], vec![0, 1]]);
+    }
+
+    #[test]
+    fn rasterize() {
+        let mut qt = Quadtree::<i8, u8>::new_with_anchor((-4, -4).into(), 3);
+        debug_assert_eq!(qt.rasterize(2, 3, 9), vec![9; 6]);
+        debug_assert!(qt.rasterize(0, 3, 9).is_empty());
+        debug_assert!(qt.rasterize(3, 0, 9).is_empty());
+
+        // Every cell of the tree holds its column.
+        for x in -4..4 {
+            for y in -4..4 {
+                qt.insert_pt((x, y).into(), (x + 4) as u8);
+            }
+        }
+        // One sample per coordinate reproduces the tree.
+        let full = qt.rasterize(8, 8, 9);
+        debug_assert_eq!(full.len(), 64);
+        for (i, value) in full.iter().enumerate() {
+            debug_assert_eq!(*value as usize, i % 8);
+        }
+        // Cells 2 wide are sampled at x = -3, -1, 1, 3; cells 8/3 wide at x = -3, 0, 2.
+        debug_assert_eq!(qt.rasterize(4, 1, 9), vec![1, 3, 5, 7]);
+        debug_assert_eq!(qt.rasterize(3, 1, 9), vec![1, 4, 6]);
+    }
+
+    #[test]
+    fn rasterize_first_inserted_wins() {
+        let mut qt = Quadtree::<u32, char>::new(2);
+        let region = |x, y, w, h| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .dimensions((w, h))
+                .build()
+                .unwrap()
+        };
+        qt.insert(region(1, 1, 3, 3), 'b');
+        qt.insert(region(0, 0, 4, 4), 'a');
+        // Covered by 'a' too, which came first.
+        qt.insert_pt((0, 0).into(), 'c');
+        debug_assert_eq!(
+            qt.rasterize(4, 4, '.').into_iter().collect::<String>(),
+            "aaaaabbbabbbabbb"
+        );
+    }
+
+    #[test]
+    fn centroid() {
+        let mut qt = Quadtree::<i32, u8>::new_with_anchor((-8, -8).into(), 4);
+        debug_assert_eq!(qt.centroid(), None);
+        debug_assert_eq!(qt.weighted_centroid(|_| 1.0), None);
+
+        qt.insert_pt((-8, -8).into(), 1);
+        debug_assert_eq!(qt.centroid(), Some((-7.5, -7.5)));
+
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-3, 0).into())
+                .dimensions((10, 5))
+                .build()
+                .unwrap(),
+            3,
+        );
+        // Centers at (-7.5, -7.5) and (2, 2.5).
+        debug_assert_eq!(qt.centroid(), Some((-2.75, -2.5)));
+        debug_assert_eq!(qt.weighted_centroid(|&v| f64::from(v)), Some((-0.375, 0.0)));
+        // Only the second counts.
+        debug_assert_eq!(
+            qt.weighted_centroid(|&v| if v == 3 { 1.0 } else { 0.0 }),
+            Some((2.0, 2.5))
+        );
+        debug_assert_eq!(qt.weighted_centroid(|_| 0.0), None);
+
+        qt.reset();
+        debug_assert_eq!(qt.centroid(), None);
+    }
+
+    #[test]
+    fn reset() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        assert!(qt.insert_pt((3, 9).into(), 1).is_some());
+        qt.reset();
+        debug_assert_eq!(qt.max_depth(), 0);
+        debug_assert_eq!(qt.node_count(), 1);
+        debug_assert_eq!(qt.leaf_count(), 1);
+    }
+}
+
+mod root_node {
+    use {super::*, quadtree_rs::node::NodeRef, std::collections::HashSet};
+
+    fn mk_quadtree() -> Quadtree<u32, u8> {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend(vec![((0, 0), 1), ((3, 9), 2), ((15, 15), 3)]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, 2).into())
+                .dimensions((9, 5))
+                .build()
+                .unwrap(),
+            4,
+        );
+        qt
+    }
+
+    fn walk<'a>(
+        node: NodeRef<'a, u32, u8>,
+        level: usize,
+        out: &mut Vec<(NodeRef<'a, u32, u8>, usize)>,
+    ) {
+        out.push((node, level));
+        if let Some(children) = node.children() {
+            for child in children.iter() {
+                walk(*child, level + 1, out);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_visit_nodes() {
+        let qt = mk_quadtree();
+        let mut walked = Vec::new();
+        walk(qt.root_node(), 0, &mut walked);
+
+        let mut visited = Vec::new();
+        qt.visit_nodes(|region, handles, level| visited.push((*region, handles.to_vec(), level)));
+
+        debug_assert_eq!(walked.len(), visited.len());
+        for ((node, level), (region, handles, visited_level)) in walked.iter().zip(visited) {
+            debug_assert_eq!(node.region(), region);
+            debug_assert_eq!(*level, visited_level);
+            debug_assert_eq!(
+                node.entries().map(|e| e.handle()).collect::<Vec<_>>(),
+                handles
+            );
+            debug_assert_eq!(node.depth(), qt.depth() - level);
+        }
+    }
+
+    #[test]
+    fn children_quarter_their_parent() {
+        let qt = mk_quadtree();
+        let root = qt.root_node();
+        debug_assert_eq!(root.region(), qt.root_region());
+        debug_assert!(!root.is_leaf());
+
+        let children = root.children().unwrap();
+        let anchors: Vec<(u32, u32)> = children
+            .iter()
+            .map(|c| c.region().anchor().into())
+            .collect();
+        // Northeast, northwest, southeast, southwest.
+        debug_assert_eq!(anchors, vec![(8, 0), (0, 0), (8, 8), (0, 8)]);
+        for child in children.iter() {
+            debug_assert_eq!((child.region().width(), child.region().height()), (8, 8));
+        }
+    }
+
+    #[test]
+    fn every_entry_is_reachable() {
+        let qt = mk_quadtree();
+        let mut walked = Vec::new();
+        walk(qt.root_node(), 0, &mut walked);
+
+        let handles: HashSet<u64> = walked
+            .iter()
+            .flat_map(|(node, _)| node.entries().map(|e| e.handle()))
+            .collect();
+        debug_assert_eq!(handles.len(), qt.len());
+        // Only leaves are without children, and single cells are always leaves.
+        for (node, _) in walked.iter() {
+            debug_assert_eq!(node.is_leaf(), node.children().is_none());
+            if node.depth() == 0 {
+                debug_assert!(node.is_leaf());
+            }
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let qt = Quadtree::<u32, u8>::new(4);
+        debug_assert!(qt.root_node().is_leaf());
+        debug_assert_eq!(qt.root_node().entries().count(), 0);
+    }
+}
+
+#[test]
+fn fill_quadrant() {
+    let mut qt = Quadtree::<u8, f64>::new(2);
+    debug_assert!(qt.is_empty());
+
+    assert!(qt
+        .insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+            49.17,
+        )
+        .is_some());
+    // This should 100% fill one quadrant.
+    debug_assert_eq!(qt.len(), 1);
+    debug_assert!(!qt.is_empty());
+
+    assert!(qt
         .insert(
             AreaBuilder::default()
                 .anchor((2, 2).into())
@@ -226,6 +1484,141 @@ fn fill_quadrant() {
     debug_assert!(!qt.is_empty());
 }
 
+// Subquadrants are split off at the center of their parent, which is found without ever adding
+// two coordinates together, so trees at the very top of the coordinate range split correctly.
+mod subdivision {
+    use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+
+    // Inserts a point in each corner of the tree, then returns the anchor and width of each
+    // subquadrant of the root.
+    fn quadrants(mut qt: Quadtree<u32, u8>) -> Vec<((u32, u32), usize)> {
+        let (lo, hi) = (qt.anchor().x(), qt.anchor().x() + qt.width() as u32 - 1);
+        qt.extend(vec![
+            ((lo, lo), 0),
+            ((hi, lo), 1),
+            ((lo, hi), 2),
+            ((hi, hi), 3),
+        ]);
+        debug_assert_eq!(qt.len(), 4);
+
+        let mut found = Vec::new();
+        qt.visit_nodes(|region, _, level| {
+            if level == 1 {
+                found.push((region.anchor().into(), region.width() as usize));
+            }
+        });
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn near_the_unsigned_max() {
+        // The far edges of this tree are at u32::MAX.
+        let lo = u32::MAX - (1 << 31);
+        let mid = lo + (1 << 30);
+        let qt = Quadtree::<u32, u8>::new_with_anchor(Point { x: lo, y: lo }, 31);
+        debug_assert_eq!(
+            quadrants(qt),
+            vec![
+                ((lo, lo), 1 << 30),
+                ((lo, mid), 1 << 30),
+                ((mid, lo), 1 << 30),
+                ((mid, mid), 1 << 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn small_tree_near_the_unsigned_max() {
+        let lo = u32::MAX - 4;
+        let qt = Quadtree::<u32, u8>::new_with_anchor(Point { x: lo, y: lo }, 2);
+        debug_assert_eq!(
+            quadrants(qt),
+            vec![
+                ((lo, lo), 2),
+                ((lo, lo + 2), 2),
+                ((lo + 2, lo), 2),
+                ((lo + 2, lo + 2), 2),
+            ]
+        );
+    }
+
+    // The leaves holding @handle.
+    fn keepers(qt: &Quadtree<u32, u8>, handle: u64) -> Vec<(Point<u32>, u32)> {
+        let mut found = Vec::new();
+        qt.visit_nodes(|region, handles, _| {
+            if handles.contains(&handle) {
+                found.push((region.anchor(), region.width()));
+            }
+        });
+        found
+    }
+
+    #[test]
+    fn points_on_the_midlines_land_in_one_subquadrant() {
+        // The root of this tree splits at (8, 8).
+        let mut qt = Quadtree::<u32, u8>::with_capacity(Point { x: 0, y: 0 }, 4, 1);
+        for pt in [(8, 8), (8, 3), (3, 8), (7, 7), (8, 15), (15, 8)] {
+            let handle = qt.insert_pt(pt.into(), 0).unwrap();
+            let keepers = keepers(&qt, handle);
+            debug_assert_eq!(keepers.len(), 1, "{:?} is held by {:?}", pt, keepers);
+            debug_assert!(AreaBuilder::default()
+                .anchor(keepers[0].0)
+                .dimensions((keepers[0].1, keepers[0].1))
+                .build()
+                .unwrap()
+                .contains_pt(pt.into()));
+        }
+        // Every point on the midlines is below or to the right of them.
+        let east = AreaBuilder::default()
+            .anchor((8, 0).into())
+            .dimensions((8, 16))
+            .build()
+            .unwrap();
+        debug_assert_eq!(qt.query(east).count(), 4);
+    }
+
+    #[test]
+    fn adjacent_queries_count_boundary_points_once() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        for x in 0..16 {
+            qt.insert_pt((x, 8).into(), 0);
+            qt.insert_pt((8, x).into(), 0);
+        }
+        let total: usize = [(0, 0), (8, 0), (0, 8), (8, 8)]
+            .iter()
+            .map(|&anchor| {
+                qt.query(
+                    AreaBuilder::default()
+                        .anchor(anchor.into())
+                        .dimensions((8, 8))
+                        .build()
+                        .unwrap(),
+                )
+                .count()
+            })
+            .sum();
+        debug_assert_eq!(total, qt.len());
+    }
+
+    #[test]
+    fn near_the_signed_max() {
+        let lo = i32::MAX - (1 << 30);
+        let mut qt = Quadtree::<i32, u8>::new_with_anchor(Point { x: lo, y: lo }, 30);
+        qt.insert_pt((i32::MAX - 1, i32::MAX - 1).into(), 0);
+        qt.insert_pt((lo, lo).into(), 1);
+
+        // Each point sinks into its own corner of the root.
+        let far = AreaBuilder::default()
+            .anchor((lo + (1 << 29), lo + (1 << 29)).into())
+            .dimensions((1 << 29, 1 << 29))
+            .build()
+            .unwrap();
+        let values: Vec<u8> = qt.query_strict(far).map(|e| *e.value_ref()).collect();
+        debug_assert_eq!(values, vec![0]);
+    }
+}
+
 #[test]
 fn is_empty() {
     let mut qt = Quadtree::<u32, u64>::new(2);
@@ -483,31 +1876,1355 @@ mod extend {
     }
 }
 
-mod delete {
-    use super::*;
+mod from_iterator {
+    use {super::*, crate::util::unordered_elements_are};
 
     #[test]
-    fn delete_by_handle() {
-        let mut qt = Quadtree::<u32, i8>::new(4);
-        // We don't know the indices for any of these.
-        qt.extend(vec![((0, 0), 0), ((2, 3), 5), ((2, 2), 7), ((1, 2), 9)]);
-        debug_assert_eq!(qt.len(), 4);
+    fn empty() {
+        let qt: Quadtree<u32, u8> = vec![].into_iter().collect();
+        debug_assert!(qt.is_empty());
+        debug_assert_eq!(qt.anchor(), (0, 0).into());
+        debug_assert_eq!(qt.depth(), 0);
+    }
 
-        // But we will be sure to retain this one.
-        let handle = qt
-            .insert(
-                AreaBuilder::default()
-                    .anchor((0, 0).into())
-                    .build()
-                    .unwrap(),
-                11,
-            )
-            .unwrap();
-        debug_assert_eq!(qt.len(), 5); // Insertion succeeded.
+    #[test]
+    fn single_point() {
+        let qt: Quadtree<u32, u8> = vec![((7, 3), 1)].into_iter().collect();
+        debug_assert_eq!(qt.len(), 1);
+        debug_assert_eq!(qt.anchor(), (7, 3).into());
+        debug_assert_eq!(qt.depth(), 0);
+    }
 
-        // Check the returned entry.
-        let entry = qt.delete_by_handle(handle).unwrap();
-        let entry_area = entry.area();
+    #[test]
+    fn keeps_every_point() {
+        let pts = vec![
+            ((-5, 17), 'a'),
+            ((12, -20), 'b'),
+            ((0, 0), 'c'),
+            ((0, 0), 'd'),
+            ((-5, 17), 'e'),
+        ];
+        let qt: Quadtree<i32, char> = pts.clone().into_iter().collect();
+        debug_assert_eq!(qt.len(), pts.len());
+        debug_assert_eq!(qt.anchor(), (-5, -20).into());
+        // The points span 38 cells vertically.
+        debug_assert_eq!(qt.width(), 64);
+        debug_assert!(unordered_elements_are(
+            qt.iter().map(|e| (e.anchor().into(), *e.value_ref())),
+            pts,
+        ));
+    }
+
+    #[test]
+    fn exact_power_of_two() {
+        let qt: Quadtree<u32, u8> = vec![((0, 0), 1), ((7, 7), 2)].into_iter().collect();
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert_eq!(qt.width(), 8);
+
+        let qt: Quadtree<u32, u8> = vec![((0, 0), 1), ((8, 0), 2)].into_iter().collect();
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert_eq!(qt.width(), 16);
+    }
+
+    #[test]
+    fn near_the_coordinate_bounds() {
+        // Holding (254, 254) would take a width of 256, which doesn't fit in a u8.
+        let qt: Quadtree<u8, u8> = vec![((0, 0), 1), ((100, 100), 2), ((254, 254), 3)]
+            .into_iter()
+            .collect();
+        debug_assert_eq!(qt.width(), 128);
+        debug_assert!(unordered_elements_are(qt.values(), vec![&1, &2]));
+
+        let qt: Quadtree<i8, u8> = vec![((-128, -128), 1), ((126, 126), 2)]
+            .into_iter()
+            .collect();
+        debug_assert_eq!(qt.width(), 64);
+        debug_assert!(unordered_elements_are(qt.values(), vec![&1]));
+    }
+
+    #[test]
+    fn extend_by_reference() {
+        let pts = [((0_u32, 0_u32), 1_u8), ((2, 3), 5)];
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend(pts.iter().map(|(pt, val)| (pt, val)));
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert!(unordered_elements_are(qt.values(), vec![&1, &5]));
+    }
+}
+
+mod from_entries {
+    use {
+        super::*,
+        crate::util::{lcg, unordered_elements_are},
+        quadtree_rs::point::Point,
+    };
+
+    #[test]
+    fn empty() {
+        let qt = Quadtree::<u32, u8>::from_entries((0, 0).into(), 4, vec![]);
+        debug_assert!(qt.is_empty());
+        debug_assert_eq!(qt, Quadtree::<u32, u8>::new(4));
+    }
+
+    #[test]
+    fn drops_out_of_bounds_points() {
+        let qt = Quadtree::<i32, u8>::from_entries(
+            (-4, -4).into(),
+            3,
+            vec![((-4, -4), 1), ((3, 3), 2), ((4, 0), 3), ((0, -5), 4)],
+        );
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert!(unordered_elements_are(qt.values(), vec![&1, &2]));
+    }
+
+    #[test]
+    fn matches_incremental_insertion() {
+        let mut seed = 17_u64;
+        let pts: Vec<((u32, u32), u32)> = (0..10_000)
+            .map(|i| ((lcg(&mut seed) % 1024, lcg(&mut seed) % 1024), i))
+            .collect();
+
+        let bulk = Quadtree::<u32, u32>::from_entries((0, 0).into(), 10, pts.clone());
+        let mut incremental = Quadtree::<u32, u32>::new(10);
+        incremental.extend(pts);
+
+        debug_assert_eq!(bulk.len(), 10_000);
+        debug_assert_eq!(bulk, incremental);
+
+        for _ in 0..50 {
+            let anchor: Point<u32> = (lcg(&mut seed) % 1000, lcg(&mut seed) % 1000).into();
+            let region = AreaBuilder::default()
+                .anchor(anchor)
+                .dimensions((1 + lcg(&mut seed) % 24, 1 + lcg(&mut seed) % 24))
+                .build()
+                .unwrap();
+            debug_assert!(unordered_elements_are(
+                bulk.query(region).map(|e| (e.area(), e.value_ref())),
+                incremental.query(region).map(|e| (e.area(), e.value_ref())),
+            ));
+        }
+    }
+}
+
+mod with_capacity {
+    use {
+        super::*,
+        crate::util::{lcg, unordered_elements_are},
+        quadtree_rs::{area::Area, point::Point},
+    };
+
+    fn random_area(seed: &mut u64, max_dim: u32) -> Area<u32> {
+        let (x, y) = (lcg(seed) % 64, lcg(seed) % 64);
+        let (w, h) = (1 + lcg(seed) % max_dim, 1 + lcg(seed) % max_dim);
+        AreaBuilder::default()
+            .anchor((x, y).into())
+            .dimensions((w.min(64 - x), h.min(64 - y)))
+            .build()
+            .unwrap()
+    }
+
+    fn assert_same_answers(a: &Quadtree<u32, u32>, b: &Quadtree<u32, u32>, seed: &mut u64) {
+        debug_assert_eq!(a.len(), b.len());
+        debug_assert!(unordered_elements_are(a.values(), b.values()));
+        for _ in 0..50 {
+            let area = random_area(seed, 20);
+            debug_assert!(unordered_elements_are(
+                a.query(area).map(|e| e.value_ref()),
+                b.query(area).map(|e| e.value_ref())
+            ));
+            debug_assert!(unordered_elements_are(
+                a.query_strict(area).map(|e| e.value_ref()),
+                b.query_strict(area).map(|e| e.value_ref())
+            ));
+            debug_assert_eq!(a.count_in_region(area), b.count_in_region(area));
+
+            let pt = area.anchor();
+            debug_assert!(unordered_elements_are(
+                a.get_at_point(pt).map(|e| e.value_ref()),
+                b.get_at_point(pt).map(|e| e.value_ref())
+            ));
+            debug_assert_eq!(a.contains_point(pt), b.contains_point(pt));
+        }
+    }
+
+    #[test]
+    fn zero_capacity_is_the_default() {
+        let mut qt_a = Quadtree::<u32, u8>::with_capacity((0, 0).into(), 4, 0);
+        let mut qt_b = Quadtree::<u32, u8>::new(4);
+        let entries = vec![((0, 0), 1), ((3, 9), 2), ((3, 10), 3), ((15, 15), 4)];
+        qt_a.extend(entries.clone());
+        qt_b.extend(entries);
+
+        debug_assert_eq!(qt_a.node_count(), qt_b.node_count());
+        debug_assert_eq!(qt_a.leaf_count(), qt_b.leaf_count());
+        debug_assert_eq!(qt_a.max_depth(), qt_b.max_depth());
+    }
+
+    #[test]
+    fn splits_when_full() {
+        let mut qt = Quadtree::<u32, u8>::with_capacity((0, 0).into(), 4, 2);
+        qt.extend(vec![((0, 0), 1), ((15, 15), 2)]);
+        debug_assert_eq!(qt.node_count(), 1);
+
+        // A third point overflows the root, which splits; each point then fits in a quadrant.
+        qt.extend(vec![((0, 15), 3)]);
+        debug_assert_eq!(qt.node_count(), 5);
+        debug_assert_eq!(qt.max_depth(), 1);
+
+        // Regions covering a whole node stay put when it splits.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((8, 8))
+                .build()
+                .unwrap(),
+            4,
+        );
+        qt.extend(vec![((1, 1), 5), ((6, 6), 6)]);
+        debug_assert_eq!(qt.node_count(), 9);
+        debug_assert_eq!(qt.len(), 6);
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((1, 1).into()).map(|e| *e.value_ref()),
+            vec![4, 5]
+        ));
+    }
+
+    #[test]
+    fn never_deeper_than_max_depth() {
+        let mut qt = Quadtree::<u32, u8>::with_capacity((0, 0).into(), 3, 1);
+        // Many points in the same spot can't all be separated.
+        qt.extend((0..10).map(|i| ((5, 5), i)));
+        debug_assert_eq!(qt.len(), 10);
+        debug_assert_eq!(qt.max_depth(), 3);
+        debug_assert_eq!(qt.get_at_point((5, 5).into()).count(), 10);
+    }
+
+    #[test]
+    fn clustered_points_need_fewer_nodes() {
+        let mut seed = 42;
+        // Two tight clusters in an otherwise empty tree.
+        let entries: Vec<((u32, u32), u32)> = (0..200)
+            .map(|i| {
+                let (cx, cy) = if i % 2 == 0 { (3, 5) } else { (50, 40) };
+                ((cx + lcg(&mut seed) % 8, cy + lcg(&mut seed) % 8), i)
+            })
+            .collect();
+
+        let mut fixed = Quadtree::<u32, u32>::new(6);
+        fixed.extend(entries.clone());
+        let mut by_capacity = Quadtree::<u32, u32>::with_capacity((0, 0).into(), 6, 8);
+        by_capacity.extend(entries);
+
+        debug_assert!(by_capacity.node_count() < fixed.node_count());
+        assert_same_answers(&fixed, &by_capacity, &mut seed);
+    }
+
+    #[test]
+    fn same_answers_as_fixed_depth() {
+        let mut seed = 7;
+        for capacity in [1, 3, 16] {
+            let mut fixed = Quadtree::<u32, u32>::new(6);
+            let mut by_capacity = Quadtree::<u32, u32>::with_capacity((0, 0).into(), 6, capacity);
+            let mut handles = vec![];
+            for i in 0..300 {
+                let area = random_area(&mut seed, if i % 3 == 0 { 24 } else { 1 });
+                handles.push((
+                    fixed.insert(area, i).unwrap(),
+                    by_capacity.insert(area, i).unwrap(),
+                ));
+            }
+            assert_same_answers(&fixed, &by_capacity, &mut seed);
+
+            // Deleting, moving, and compacting don't change that.
+            for (i, (a, b)) in handles.iter().enumerate() {
+                match i % 4 {
+                    0 => {
+                        fixed.delete_by_handle(*a);
+                        by_capacity.delete_by_handle(*b);
+                    }
+                    1 => {
+                        let area = random_area(&mut seed, 8);
+                        fixed.relocate(*a, area);
+                        by_capacity.relocate(*b, area);
+                    }
+                    _ => (),
+                }
+            }
+            fixed.compact();
+            by_capacity.compact();
+            assert_same_answers(&fixed, &by_capacity, &mut seed);
+
+            // Ties may come out in either order, but the distances must agree.
+            let pt = Point { x: 31, y: 17 };
+            let distances = |qt: &Quadtree<u32, u32>| -> Vec<u32> {
+                qt.nearest_neighbors(pt, 10)
+                    .iter()
+                    .map(|e| {
+                        let cx = pt.x.clamp(e.area().left_edge(), e.area().right_edge() - 1);
+                        let cy = pt.y.clamp(e.area().top_edge(), e.area().bottom_edge() - 1);
+                        cx.abs_diff(pt.x).pow(2) + cy.abs_diff(pt.y).pow(2)
+                    })
+                    .collect()
+            };
+            debug_assert_eq!(distances(&fixed), distances(&by_capacity));
+        }
+    }
+}
+
+mod partial_eq {
+    use {super::*, quadtree_rs::area::Area};
+
+    fn region(anchor: (u32, u32), dimensions: (u32, u32)) -> Area<u32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dimensions)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn insertion_order_does_not_matter() {
+        let entries = [
+            (region((0, 0), (1, 1)), 'a'),
+            (region((3, 3), (4, 2)), 'b'),
+            (region((0, 0), (8, 8)), 'c'),
+            (region((5, 1), (1, 1)), 'd'),
+        ];
+        let mut qt_a = Quadtree::<u32, char>::new(4);
+        for (area, val) in entries.iter() {
+            qt_a.insert(*area, *val);
+        }
+        let mut qt_b = Quadtree::<u32, char>::new(4);
+        for (area, val) in entries.iter().rev() {
+            qt_b.insert(*area, *val);
+        }
+        debug_assert_eq!(qt_a, qt_b);
+    }
+
+    #[test]
+    fn tree_shape_and_handles_do_not_matter() {
+        let mut qt_a = Quadtree::<u32, char>::new(4);
+        qt_a.insert(region((1, 1), (2, 2)), 'a');
+
+        // A different depth and capacity, and a handle which has moved on.
+        let mut qt_b = Quadtree::<u32, char>::with_capacity((0, 0).into(), 6, 4);
+        let handle = qt_b.insert(region((9, 9), (1, 1)), 'z').unwrap();
+        qt_b.delete_by_handle(handle);
+        qt_b.insert(region((1, 1), (2, 2)), 'a');
+
+        debug_assert_eq!(qt_a, qt_b);
+        debug_assert_eq!(Quadtree::<u32, char>::new(2), Quadtree::new(5));
+    }
+
+    #[test]
+    fn counts_duplicates() {
+        let mut qt_a = Quadtree::<u32, char>::new(4);
+        qt_a.insert(region((1, 1), (2, 2)), 'a');
+        qt_a.insert(region((1, 1), (2, 2)), 'a');
+        qt_a.insert(region((1, 1), (2, 2)), 'b');
+
+        let mut qt_b = Quadtree::<u32, char>::new(4);
+        qt_b.insert(region((1, 1), (2, 2)), 'a');
+        qt_b.insert(region((1, 1), (2, 2)), 'b');
+        qt_b.insert(region((1, 1), (2, 2)), 'b');
+
+        debug_assert_ne!(qt_a, qt_b);
+    }
+
+    #[test]
+    fn regions_and_values_must_match() {
+        let mut qt_a = Quadtree::<u32, f64>::new(4);
+        qt_a.insert(region((1, 1), (2, 2)), 1.0);
+
+        let mut qt_b = Quadtree::<u32, f64>::new(4);
+        qt_b.insert(region((1, 1), (2, 3)), 1.0);
+        debug_assert_ne!(qt_a, qt_b);
+
+        let mut qt_c = Quadtree::<u32, f64>::new(4);
+        qt_c.insert(region((1, 1), (2, 2)), 2.0);
+        debug_assert_ne!(qt_a, qt_c);
+
+        // Values are compared with their own PartialEq.
+        let mut qt_nan = Quadtree::<u32, f64>::new(4);
+        qt_nan.insert(region((1, 1), (2, 2)), f64::NAN);
+        debug_assert_ne!(qt_nan, qt_nan);
+    }
+}
+
+mod map {
+    use super::*;
+
+    #[test]
+    fn same_regions_and_shape() {
+        let mut qt = Quadtree::<u32, u8>::with_capacity((0, 0).into(), 4, 2);
+        qt.extend(vec![((0, 0), 1), ((2, 3), 2), ((9, 9), 3), ((9, 10), 4)]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((1, 1).into())
+                .dimensions((6, 6))
+                .build()
+                .unwrap(),
+            5,
+        );
+
+        let mapped: Quadtree<u32, String> = qt.map(|v| v.to_string());
+        debug_assert_eq!(mapped.len(), qt.len());
+        debug_assert_eq!(mapped.node_count(), qt.node_count());
+        debug_assert_eq!(mapped.max_depth(), qt.max_depth());
+        for entry in qt.iter() {
+            let other = mapped.get(entry.handle()).unwrap();
+            debug_assert_eq!(other.area(), entry.area());
+            debug_assert_eq!(other.value_ref(), &entry.value_ref().to_string());
+        }
+        // The original is untouched.
+        debug_assert_eq!(qt.values().map(|&v| u32::from(v)).sum::<u32>(), 15);
+    }
+
+    #[test]
+    fn mapped_tree_stays_usable() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        let handle = qt.insert_pt((3, 3).into(), 1).unwrap();
+
+        let mut mapped = qt.map(|&v| i32::from(v) * -10);
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+        debug_assert_eq!(mapped.query(region).next().unwrap().value_ref(), &-10);
+
+        // New handles don't collide with the ones carried over.
+        let other = mapped.insert_pt((3, 3).into(), 7).unwrap();
+        debug_assert_ne!(other, handle);
+        debug_assert_eq!(mapped.delete_by_handle(handle).unwrap().value_ref(), &-10);
+        debug_assert_eq!(mapped.query(region).count(), 1);
+    }
+}
+
+mod clone_with_new_handles {
+    use {super::*, std::collections::HashSet};
+
+    fn mk_quadtree() -> Quadtree<u32, String> {
+        let mut qt = Quadtree::<u32, String>::with_capacity((0, 0).into(), 4, 2);
+        qt.extend(vec![
+            ((0, 0), "a".to_string()),
+            ((2, 3), "b".to_string()),
+            ((9, 9), "c".to_string()),
+        ]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((1, 1).into())
+                .dimensions((6, 6))
+                .build()
+                .unwrap(),
+            "d".to_string(),
+        );
+        qt
+    }
+
+    #[test]
+    fn equal_contents_disjoint_handles() {
+        let mut qt = mk_quadtree();
+        // A gap in the original's handles.
+        let gone = qt.insert_pt((5, 5).into(), "e".to_string()).unwrap();
+        qt.delete_by_handle(gone);
+
+        let copy = qt.clone_with_new_handles();
+        debug_assert_eq!(copy, qt);
+        debug_assert_eq!(copy.node_count(), qt.node_count());
+
+        let original: HashSet<u64> = qt.iter().map(|e| e.handle()).collect();
+        let copied: HashSet<u64> = copy.iter().map(|e| e.handle()).collect();
+        debug_assert_eq!(copied.len(), original.len());
+        debug_assert!(original.is_disjoint(&copied));
+        for &handle in original.iter().chain(std::iter::once(&gone)) {
+            debug_assert!(copy.get(handle).is_none());
+        }
+    }
+
+    #[test]
+    fn keeps_relative_order() {
+        let qt = mk_quadtree();
+        let copy = qt.clone_with_new_handles();
+        let order = |qt: &Quadtree<u32, String>| {
+            let mut entries: Vec<(u64, String)> = qt
+                .iter()
+                .map(|e| (e.handle(), e.value_ref().clone()))
+                .collect();
+            entries.sort();
+            entries.into_iter().map(|(_, v)| v).collect::<String>()
+        };
+        debug_assert_eq!(order(&copy), order(&qt));
+    }
+
+    #[test]
+    fn copy_is_independent() {
+        let mut qt = Quadtree::<i32, u8>::new_growable((0, 0).into(), 2);
+        qt.insert_pt((1, 1).into(), 1);
+        let mut copy = qt.clone_with_new_handles();
+
+        // Still growable, and changes to one don't show up in the other.
+        debug_assert!(copy.insert_pt((10, 10).into(), 2).is_some());
+        copy.modify_all(|v| *v += 10);
+        debug_assert_eq!(qt.len(), 1);
+        debug_assert_eq!(qt.values().copied().collect::<Vec<u8>>(), vec![1]);
+        debug_assert_eq!(copy.len(), 2);
+    }
+}
+
+mod split_off_region {
+    use {super::*, crate::util::unordered_elements_are};
+
+    // 'e' straddles the western half's edge.
+    fn mk_quadtree() -> Quadtree<i32, char> {
+        let mut qt = Quadtree::<i32, char>::with_capacity((-8, -8).into(), 4, 2);
+        qt.extend(vec![
+            ((-5, -5), 'a'),
+            ((-1, 3), 'b'),
+            ((2, -6), 'c'),
+            ((6, 6), 'd'),
+        ]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-2, -2).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+            'e',
+        );
+        qt
+    }
+
+    fn west() -> quadtree_rs::area::Area<i32> {
+        AreaBuilder::default()
+            .anchor((-8, -8).into())
+            .dimensions((8, 16))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn partitions_the_entries() {
+        let mut qt = mk_quadtree();
+        let len = qt.len();
+        let d = qt.iter().find(|e| *e.value_ref() == 'd').unwrap().handle();
+        let west_qt = qt.split_off_region(west());
+
+        debug_assert_eq!(qt.len() + west_qt.len(), len);
+        debug_assert!(unordered_elements_are(
+            west_qt.values().copied(),
+            "abe".chars()
+        ));
+        debug_assert!(unordered_elements_are(qt.values().copied(), "cd".chars()));
+        // What stays behind keeps its handle.
+        debug_assert_eq!(qt.get(d).map(|e| *e.value_ref()), Some('d'));
+    }
+
+    #[test]
+    fn same_shape() {
+        let mut qt = mk_quadtree();
+        let west_qt = qt.split_off_region(west());
+        debug_assert_eq!(west_qt.anchor(), qt.anchor());
+        debug_assert_eq!(west_qt.depth(), qt.depth());
+
+        // The new tree is a working quadtree in its own right.
+        let found: Vec<char> = west_qt
+            .query(
+                AreaBuilder::default()
+                    .anchor((0, 0).into())
+                    .dimensions((1, 1))
+                    .build()
+                    .unwrap(),
+            )
+            .map(|e| *e.value_ref())
+            .collect();
+        debug_assert_eq!(found, vec!['e']);
+    }
+
+    #[test]
+    fn nothing_overlaps() {
+        let mut qt = mk_quadtree();
+        let empty = qt.split_off_region(
+            AreaBuilder::default()
+                .anchor((4, -8).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+        );
+        debug_assert!(empty.is_empty());
+        debug_assert_eq!(qt.len(), 5);
+    }
+}
+
+mod merge {
+    use {super::*, crate::util::unordered_elements_are};
+
+    #[test]
+    fn into_empty() {
+        let mut qt_a = Quadtree::<u32, i8>::new(3);
+        let mut qt_b = Quadtree::<u32, i8>::new(3);
+        qt_b.extend(vec![((0, 0), 1), ((7, 7), 2)]);
+
+        assert!(qt_a.merge(qt_b).is_ok());
+        debug_assert_eq!(qt_a.len(), 2);
+        debug_assert!(unordered_elements_are(qt_a.values().copied(), vec![1, 2]));
+    }
+
+    #[test]
+    fn from_empty() {
+        let mut qt_a = Quadtree::<u32, i8>::new(3);
+        qt_a.extend(vec![((0, 0), 1)]);
+
+        assert!(qt_a.merge(Quadtree::new(6)).is_ok());
+        debug_assert_eq!(qt_a.len(), 1);
+    }
+
+    #[test]
+    fn different_anchors_and_depths() {
+        let mut qt_a = Quadtree::<i32, i8>::new_with_anchor((-8, -8).into(), 4);
+        qt_a.extend(vec![((-8, -8), 1), ((0, 0), 2)]);
+        let region = AreaBuilder::default()
+            .anchor((-2, -2).into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+        qt_a.insert(region, 3);
+
+        let mut qt_b = Quadtree::<i32, i8>::new_with_anchor((2, 2).into(), 2);
+        qt_b.extend(vec![((2, 2), 4), ((5, 5), 5)]);
+        qt_b.insert(
+            AreaBuilder::default()
+                .anchor((2, 2).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+            6,
+        );
+
+        assert!(qt_a.merge(qt_b).is_ok());
+        debug_assert_eq!(qt_a.len(), 6);
+        debug_assert!(unordered_elements_are(
+            qt_a.values().copied(),
+            vec![1, 2, 3, 4, 5, 6]
+        ));
+        debug_assert!(unordered_elements_are(
+            qt_a.query(
+                AreaBuilder::default()
+                    .anchor((1, 1).into())
+                    .dimensions((2, 2))
+                    .build()
+                    .unwrap()
+            )
+            .map(|e| *e.value_ref()),
+            vec![3, 4, 6]
+        ));
+    }
+
+    #[test]
+    fn handles_are_reissued() {
+        let mut qt_a = Quadtree::<u32, i8>::new(3);
+        let handle_a = qt_a.insert_pt((1, 1).into(), 1).unwrap();
+        let mut qt_b = Quadtree::<u32, i8>::new(3);
+        // The first handle issued by each tree is the same.
+        debug_assert_eq!(qt_b.insert_pt((2, 2).into(), 2).unwrap(), handle_a);
+
+        assert!(qt_a.merge(qt_b).is_ok());
+        debug_assert_eq!(qt_a.len(), 2);
+        debug_assert_eq!(qt_a.get(handle_a).unwrap().value_ref(), &1);
+
+        let handles: Vec<u64> = qt_a.iter().map(|e| e.handle()).collect();
+        debug_assert_eq!(handles.len(), 2);
+        debug_assert_ne!(handles[0], handles[1]);
+    }
+
+    #[test]
+    fn handles_are_deterministic() {
+        // Each run's HashMaps hash differently, so merge twice within one run too.
+        let merged = || {
+            let mut qt_a = Quadtree::<u32, u32>::new(5);
+            qt_a.extend((0..5).map(|i| ((i, 0), i)));
+            let mut qt_b = Quadtree::<u32, u32>::new(5);
+            qt_b.extend((0..50).map(|i| ((i % 32, 1 + i / 32), 100 + i)));
+            assert!(qt_a.merge(qt_b).is_ok());
+            let mut handles: Vec<(u64, u32)> =
+                qt_a.iter().map(|e| (e.handle(), *e.value_ref())).collect();
+            handles.sort_unstable();
+            handles
+        };
+        let handles = merged();
+        debug_assert_eq!(handles, merged());
+        // Issued in the order qt_b issued its own.
+        debug_assert!(handles.windows(2).all(|w| w[0].1 < w[1].1));
+    }
+
+    #[test]
+    fn incompatible_regions() {
+        let mut qt_a = Quadtree::<u32, i8>::new(3);
+        qt_a.extend(vec![((1, 1), 1)]);
+        let mut qt_b = Quadtree::<u32, i8>::new(4);
+        // One entry which would fit, and one which wouldn't.
+        qt_b.extend(vec![((2, 2), 2), ((12, 12), 3)]);
+
+        let qt_b = qt_a.merge(qt_b).unwrap_err();
+        debug_assert_eq!(qt_a.len(), 1);
+        debug_assert_eq!(qt_b.len(), 2);
+        debug_assert!(unordered_elements_are(qt_b.values().copied(), vec![2, 3]));
+    }
+}
+
+mod diff {
+    use super::*;
+
+    fn region(x: u32, y: u32, w: u32, h: u32) -> quadtree_rs::area::Area<u32> {
+        AreaBuilder::default()
+            .anchor((x, y).into())
+            .dimensions((w, h))
+            .build()
+            .unwrap()
+    }
+
+    fn values(entries: &[quadtree_rs::entry::Entry<u32, char>]) -> String {
+        entries.iter().map(|e| *e.value_ref()).collect()
+    }
+
+    #[test]
+    fn identical_trees() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        qt.insert(region(1, 1, 3, 3), 'a');
+        qt.insert_pt((9, 9).into(), 'b');
+        let (added, removed) = qt.diff(&qt);
+        debug_assert!(added.is_empty() && removed.is_empty());
+
+        // Built differently, with other handles and another shape, but holding the same.
+        let mut other = Quadtree::<u32, char>::with_capacity((0, 0).into(), 5, 1);
+        other.insert_pt((0, 0).into(), 'x');
+        other.insert_pt((9, 9).into(), 'b');
+        other.insert(region(1, 1, 3, 3), 'a');
+        other.delete(region(0, 0, 1, 1));
+        let (added, removed) = qt.diff(&other);
+        debug_assert!(added.is_empty() && removed.is_empty());
+    }
+
+    #[test]
+    fn matched_by_region_and_value() {
+        let mut old = Quadtree::<u32, char>::new(4);
+        old.insert(region(1, 1, 3, 3), 'a');
+        old.insert(region(5, 5, 2, 2), 'b');
+        old.insert_pt((8, 8).into(), 'c');
+        let mut new = Quadtree::<u32, char>::new(4);
+        // Same value, moved.
+        new.insert(region(1, 1, 3, 4), 'a');
+        // Same region, new value.
+        new.insert(region(5, 5, 2, 2), 'z');
+        new.insert_pt((8, 8).into(), 'c');
+
+        let (added, removed) = old.diff(&new);
+        debug_assert_eq!(values(&added), "az");
+        debug_assert_eq!(values(&removed), "ab");
+        debug_assert_eq!(added[0].area(), region(1, 1, 3, 4));
+
+        // And the other way round.
+        let (added, removed) = new.diff(&old);
+        debug_assert_eq!(values(&added), "ab");
+        debug_assert_eq!(values(&removed), "az");
+    }
+
+    #[test]
+    fn duplicates_are_counted() {
+        let mut old = Quadtree::<u32, char>::new(4);
+        let handles: Vec<u64> = (0..3)
+            .map(|_| old.insert(region(2, 2, 2, 2), 'a').unwrap())
+            .collect();
+        let mut new = Quadtree::<u32, char>::new(4);
+        new.insert(region(2, 2, 2, 2), 'a');
+
+        let (added, removed) = old.diff(&new);
+        debug_assert!(added.is_empty());
+        // The surplus copies with the highest handles.
+        let removed: Vec<u64> = removed.iter().map(|e| e.handle()).collect();
+        debug_assert_eq!(removed, handles[1..].to_vec());
+
+        let (added, removed) = new.diff(&old);
+        debug_assert_eq!(added.len(), 2);
+        debug_assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn against_an_empty_tree() {
+        let empty = Quadtree::<u32, char>::new(2);
+        let mut qt = Quadtree::<u32, char>::new(4);
+        qt.extend(vec![((3, 3), 'b'), ((1, 1), 'a')]);
+
+        let (added, removed) = empty.diff(&qt);
+        debug_assert_eq!(values(&added), "ba");
+        debug_assert!(removed.is_empty());
+        let (added, removed) = qt.diff(&empty);
+        debug_assert!(added.is_empty());
+        debug_assert_eq!(values(&removed), "ba");
+    }
+
+    #[test]
+    fn changes_apply_to_the_old_tree() {
+        let mut old = Quadtree::<u32, char>::new(4);
+        old.insert(region(1, 1, 3, 3), 'a');
+        old.insert(region(5, 5, 2, 2), 'b');
+        old.insert(region(5, 5, 2, 2), 'b');
+        old.insert_pt((8, 8).into(), 'c');
+        let mut new = Quadtree::<u32, char>::new(5);
+        new.insert_pt((8, 8).into(), 'c');
+        new.insert(region(5, 5, 2, 2), 'b');
+        new.insert(region(10, 2, 4, 1), 'd');
+
+        // Held while the old tree is changed.
+        let (added, removed) = old.diff(&new);
+        for entry in &removed {
+            debug_assert_eq!(old.delete_by_handle(entry.handle()).as_ref(), Some(entry));
+        }
+        for entry in added {
+            debug_assert!(old.insert(entry.area(), *entry.value_ref()).is_some());
+        }
+        debug_assert_eq!(values(&removed), "ab");
+
+        let (added, removed) = old.diff(&new);
+        debug_assert!(added.is_empty() && removed.is_empty());
+    }
+}
+
+mod compact {
+    use {super::*, crate::util::unordered_elements_are};
+
+    #[test]
+    fn empty() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.compact();
+        debug_assert_eq!(qt.node_count(), 1);
+    }
+
+    #[test]
+    fn nothing_to_prune() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend(vec![((0, 0), 1), ((15, 15), 2), ((7, 8), 3)]);
+        let node_count = qt.node_count();
+
+        qt.compact();
+        debug_assert_eq!(qt.node_count(), node_count);
+        debug_assert_eq!(qt.len(), 3);
+    }
+
+    #[test]
+    fn after_deleting_most_entries() {
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        qt.extend((0..16).flat_map(|x| (0..16).map(move |y| ((x, y), x * 16 + y))));
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((3, 3).into())
+                .dimensions((6, 2))
+                .build()
+                .unwrap(),
+            1000,
+        );
+        let full_node_count = qt.node_count();
+
+        // Keep a handful of points in one corner, and the region.
+        qt.retain(|area, &v| v == 1000 || (area.anchor().x() < 2 && area.anchor().y() < 3));
+        debug_assert_eq!(qt.len(), 7);
+        // Deleting already pruned the emptied subtrees, so there's nothing left for compact().
+        let node_count = qt.node_count();
+        debug_assert!(node_count < full_node_count);
+
+        qt.compact();
+        debug_assert_eq!(qt.node_count(), node_count);
+        debug_assert_eq!(qt.len(), 7);
+
+        // The tree is now just what the remaining entries need.
+        let mut fresh = Quadtree::<u32, u32>::new(4);
+        qt.iter().for_each(|e| {
+            fresh.insert(e.area(), *e.value_ref());
+        });
+        debug_assert_eq!(qt.node_count(), fresh.node_count());
+        debug_assert_eq!(qt.leaf_count(), fresh.leaf_count());
+
+        debug_assert!(unordered_elements_are(
+            qt.values().copied(),
+            vec![0, 1, 2, 16, 17, 18, 1000]
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.query(
+                AreaBuilder::default()
+                    .anchor((1, 2).into())
+                    .dimensions((4, 2))
+                    .build()
+                    .unwrap()
+            )
+            .map(|e| *e.value_ref()),
+            vec![18, 1000]
+        ));
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut qt = Quadtree::<u32, u32>::new(7);
+        qt.extend((0..10_000).map(|i| ((i % 100, i / 100), i)));
+        let handle = qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((10, 10).into())
+                    .dimensions((50, 50))
+                    .build()
+                    .unwrap(),
+                20_000,
+            )
+            .unwrap();
+        let full_node_count = qt.node_count();
+        qt.retain(|_, &v| v == 20_000 || v % 100 == 0);
+        debug_assert_eq!(qt.len(), 101);
+
+        let node_count = qt.node_count();
+        debug_assert!(node_count < full_node_count);
+        qt.shrink_to_fit();
+        debug_assert_eq!(qt.node_count(), node_count);
+        debug_assert_eq!(qt.len(), 101);
+        debug_assert_eq!(qt.get(handle).unwrap().value_ref(), &20_000);
+
+        // The column of points along x = 0 survived, and the region over it still answers.
+        let column = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((1, 100))
+            .build()
+            .unwrap();
+        debug_assert_eq!(qt.query(column).count(), 100);
+        debug_assert_eq!(qt.count_in_region(column), 100);
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((0, 20).into()).map(|e| *e.value_ref()),
+            vec![2000]
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((20, 20).into()).map(|e| *e.value_ref()),
+            vec![20_000]
+        ));
+
+        // And the tree still grows again.
+        qt.insert_pt((5, 5).into(), 1);
+        debug_assert_eq!(qt.count_in_region(column), 100);
+        debug_assert_eq!(qt.len(), 102);
+    }
+
+    #[test]
+    fn after_deleting_everything() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend(vec![((0, 0), 1), ((15, 15), 2), ((7, 8), 3)]);
+        qt.retain(|_, _| false);
+
+        qt.compact();
+        debug_assert_eq!(qt.node_count(), 1);
+        debug_assert_eq!(qt.leaf_count(), 1);
+    }
+}
+
+mod relocate {
+    use {super::*, crate::util::unordered_elements_are};
+
+    // Deliberately neither Clone nor Copy.
+    #[derive(Debug, PartialEq)]
+    struct Particle(u8);
+
+    fn area(anchor: (u32, u32), dimensions: (u32, u32)) -> quadtree_rs::area::Area<u32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dimensions)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn between_subquadrants() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        let handle = qt.insert(area((1, 1), (1, 1)), Particle(1)).unwrap();
+        let other = qt.insert(area((2, 2), (1, 1)), Particle(2)).unwrap();
+
+        // From the top-left quadrant, to a region straddling the bottom-right ones.
+        assert!(qt.relocate(handle, area((10, 7), (3, 3))));
+
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert_eq!(qt.get(handle).unwrap().area(), area((10, 7), (3, 3)));
+        debug_assert_eq!(qt.get(handle).unwrap().value_ref(), &Particle(1));
+        debug_assert_eq!(qt.query(area((0, 0), (2, 2))).count(), 0);
+        debug_assert_eq!(
+            qt.query(area((12, 9), (1, 1))).next().unwrap().handle(),
+            handle
+        );
+        debug_assert_eq!(qt.iter().count(), 2);
+        debug_assert_eq!(qt.get(other).unwrap().value_ref(), &Particle(2));
+    }
+
+    #[test]
+    fn every_frame() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        let handle = qt.insert(area((0, 0), (1, 1)), Particle(7)).unwrap();
+
+        for i in 1..16 {
+            assert!(qt.relocate(handle, area((i, i), (1, 1))));
+            debug_assert!(unordered_elements_are(
+                qt.query(area((0, 0), (16, 16))).map(|e| e.anchor()),
+                vec![(i, i).into()]
+            ));
+        }
+        debug_assert_eq!(qt.len(), 1);
+    }
+
+    #[test]
+    fn onto_itself() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        let handle = qt.insert(area((3, 3), (4, 4)), Particle(1)).unwrap();
+
+        assert!(qt.relocate(handle, area((3, 3), (4, 4))));
+        debug_assert_eq!(qt.query(area((3, 3), (4, 4))).count(), 1);
+        debug_assert_eq!(qt.iter().count(), 1);
+    }
+
+    #[test]
+    fn missing_handle() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        let handle = qt.insert(area((1, 1), (1, 1)), Particle(1)).unwrap();
+        qt.delete_by_handle(handle);
+
+        assert!(!qt.relocate(handle, area((2, 2), (1, 1))));
+        debug_assert!(qt.is_empty());
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        let handle = qt.insert(area((1, 1), (1, 1)), Particle(1)).unwrap();
+
+        assert!(!qt.relocate(handle, area((15, 15), (2, 2))));
+        debug_assert_eq!(qt.get(handle).unwrap().area(), area((1, 1), (1, 1)));
+        debug_assert_eq!(qt.query(area((1, 1), (1, 1))).count(), 1);
+    }
+}
+
+mod translate {
+    use {
+        super::*,
+        quadtree_rs::{error::QuadtreeError, point::Point},
+    };
+
+    fn mk_quadtree() -> Quadtree<i16, char> {
+        let mut qt = Quadtree::<i16, char>::new_with_anchor((-8, -8).into(), 4);
+        qt.insert_pt((-8, -8).into(), 'a');
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-2, -3).into())
+                .dimensions((5, 4))
+                .build()
+                .unwrap(),
+            'b',
+        );
+        qt.insert_pt((7, 7).into(), 'c');
+        qt
+    }
+
+    #[test]
+    fn shifts_the_tree_and_its_entries() {
+        let mut qt = mk_quadtree();
+        let before: Vec<(u64, Point<i16>)> = qt.iter().map(|e| (e.handle(), e.anchor())).collect();
+        let (nodes, leaves) = (qt.node_count(), qt.leaf_count());
+
+        debug_assert_eq!(qt.translate(100, -20), Ok(()));
+        debug_assert_eq!(qt.anchor(), (92, -28).into());
+        debug_assert_eq!(qt.depth(), 4);
+        for (handle, anchor) in before {
+            debug_assert_eq!(
+                qt.get(handle).unwrap().anchor(),
+                anchor + Point { x: 100, y: -20 }
+            );
+        }
+        // The same shape, with every node moved along.
+        debug_assert_eq!((qt.node_count(), qt.leaf_count()), (nodes, leaves));
+        let mut inside = true;
+        let root = qt.root_region();
+        qt.visit_nodes(|region, _, _| inside &= root.contains(*region));
+        debug_assert!(inside);
+    }
+
+    #[test]
+    fn shifted_queries_agree() {
+        let original = mk_quadtree();
+        let mut moved = mk_quadtree();
+        moved.translate(-1000, 3000).unwrap();
+
+        for x in -8..8 {
+            for y in -8..8 {
+                let query = AreaBuilder::default()
+                    .anchor((x, y).into())
+                    .dimensions((3, 2))
+                    .build()
+                    .unwrap();
+                let shifted = AreaBuilder::default()
+                    .anchor((x - 1000, y + 3000).into())
+                    .dimensions((3, 2))
+                    .build()
+                    .unwrap();
+                let mut a: Vec<char> = original.query(query).map(|e| *e.value_ref()).collect();
+                let mut b: Vec<char> = moved.query(shifted).map(|e| *e.value_ref()).collect();
+                a.sort_unstable();
+                b.sort_unstable();
+                debug_assert_eq!(a, b);
+            }
+        }
+        // Inserting afterwards works in the new coordinates.
+        debug_assert!(moved.insert_pt((-1008, 2992).into(), 'd').is_some());
+        debug_assert!(moved.insert_pt((-8, -8).into(), 'e').is_none());
+    }
+
+    #[test]
+    fn overflow_changes_nothing() {
+        let mut qt = mk_quadtree();
+        // The far edges are at 8, so the largest shift right is i16::MAX - 8.
+        debug_assert_eq!(qt.translate(i16::MAX - 7, 0), Err(QuadtreeError::Overflow));
+        debug_assert_eq!(qt.translate(0, i16::MIN + 7), Err(QuadtreeError::Overflow));
+        debug_assert_eq!(qt.anchor(), (-8, -8).into());
+        debug_assert_eq!(qt.get_at_point((7, 7).into()).count(), 1);
+
+        debug_assert_eq!(qt.translate(i16::MAX - 8, i16::MIN + 8), Ok(()));
+        debug_assert_eq!(qt.root_region().right_edge(), i16::MAX);
+        debug_assert_eq!(qt.anchor().y(), i16::MIN);
+    }
+
+    #[test]
+    fn unsigned() {
+        let mut qt = Quadtree::<u8, u8>::new(4);
+        qt.insert_pt((15, 15).into(), 1);
+        // The right edge can go as far as 255, and no further.
+        debug_assert_eq!(qt.translate(240, 0), Err(QuadtreeError::Overflow));
+        debug_assert_eq!(qt.translate(239, 0), Ok(()));
+        debug_assert_eq!(qt.translate(1, 0), Err(QuadtreeError::Overflow));
+        debug_assert!(qt.contains_point((254, 15).into()));
+    }
+}
+
+mod remap_regions {
+    use {
+        super::*,
+        quadtree_rs::{area::Area, error::QuadtreeError, point::Point},
+    };
+
+    // Deliberately neither Clone nor Copy.
+    #[derive(Debug, PartialEq)]
+    struct Particle(u8);
+
+    fn area(anchor: (u32, u32), dimensions: (u32, u32)) -> Area<u32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dimensions)
+            .build()
+            .unwrap()
+    }
+
+    fn shifted(region: Area<u32>, dx: u32) -> Area<u32> {
+        area(
+            (region.left_edge() + dx, region.top_edge()),
+            (region.width(), region.height()),
+        )
+    }
+
+    #[test]
+    fn keeps_handles_and_values() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        let handles: Vec<u64> = (0..4)
+            .map(|i| {
+                qt.insert(area((i, i * 3), (2, 2)), Particle(i as u8))
+                    .unwrap()
+            })
+            .collect();
+
+        debug_assert_eq!(qt.remap_regions(|region| shifted(region, 8)), Ok(()));
+        debug_assert_eq!(qt.len(), 4);
+        for (i, &handle) in handles.iter().enumerate() {
+            let entry = qt.get(handle).unwrap();
+            debug_assert_eq!(entry.area(), area((i as u32 + 8, i as u32 * 3), (2, 2)));
+            debug_assert_eq!(entry.value_ref(), &Particle(i as u8));
+        }
+        debug_assert_eq!(qt.query(area((0, 0), (8, 16))).count(), 0);
+        debug_assert_eq!(qt.query(area((8, 0), (8, 16))).count(), 4);
+        // Nothing is left behind at the old regions.
+        debug_assert!(!qt.contains_point((0, 0).into()));
+    }
+
+    #[test]
+    fn calls_f_in_handle_order() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        for i in 0..6 {
+            qt.insert_pt((15 - i, i).into(), Particle(i as u8));
+        }
+        let mut seen = Vec::new();
+        qt.remap_regions(|region| {
+            seen.push(region.anchor());
+            region
+        })
+        .unwrap();
+        let expected: Vec<Point<u32>> = (0..6).map(|i| (15 - i, i).into()).collect();
+        debug_assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn matches_a_tree_built_afresh() {
+        let mut qt = Quadtree::<u32, u8>::with_capacity((0, 0).into(), 4, 1);
+        for i in 0..8 {
+            qt.insert_pt((i, i).into(), i as u8);
+        }
+        let mut fresh = Quadtree::<u32, u8>::with_capacity((0, 0).into(), 4, 1);
+        for i in 0..8 {
+            fresh.insert_pt((i + 8, 15 - i).into(), i as u8);
+        }
+
+        // Mirrors every point vertically, and moves it right.
+        qt.remap_regions(|region| area((region.left_edge() + 8, 15 - region.top_edge()), (1, 1)))
+            .unwrap();
+        debug_assert_eq!(qt.node_count(), fresh.node_count());
+        debug_assert_eq!(qt.leaf_count(), fresh.leaf_count());
+        debug_assert_eq!(qt.entries_per_depth(), fresh.entries_per_depth());
+        debug_assert!(qt == fresh);
+    }
+
+    #[test]
+    fn out_of_bounds_changes_nothing() {
+        let mut qt = Quadtree::<u32, Particle>::new(4);
+        let near = qt.insert(area((1, 1), (1, 1)), Particle(1)).unwrap();
+        let far = qt.insert(area((12, 1), (2, 2)), Particle(2)).unwrap();
+
+        // Fine for `near`, but pushes `far` past the right edge.
+        debug_assert_eq!(
+            qt.remap_regions(|region| shifted(region, 3)),
+            Err(QuadtreeError::OutOfBounds)
+        );
+        debug_assert_eq!(qt.get(near).unwrap().area(), area((1, 1), (1, 1)));
+        debug_assert_eq!(qt.get(far).unwrap().area(), area((12, 1), (2, 2)));
+        debug_assert_eq!(qt.query(area((1, 1), (1, 1))).count(), 1);
+        debug_assert_eq!(qt.root_region(), area((0, 0), (16, 16)));
+    }
+
+    #[test]
+    fn growable_grows() {
+        let mut qt = Quadtree::<u32, Particle>::new_growable((0, 0).into(), 2);
+        let handle = qt.insert(area((1, 1), (1, 1)), Particle(1)).unwrap();
+
+        debug_assert_eq!(qt.remap_regions(|region| shifted(region, 20)), Ok(()));
+        assert!(qt.root_region().contains(area((21, 1), (1, 1))));
+        debug_assert_eq!(
+            qt.get_at_point((21, 1).into()).next().unwrap().handle(),
+            handle
+        );
+
+        // The root can only double until it spans 2^31, so it can never reach u32::MAX.
+        debug_assert_eq!(
+            qt.remap_regions(|region| shifted(region, u32::MAX - 23)),
+            Err(QuadtreeError::OutOfBounds)
+        );
+        debug_assert_eq!(qt.get(handle).unwrap().anchor(), (21, 1).into());
+    }
+
+    #[test]
+    fn empty() {
+        let mut qt = Quadtree::<u32, Particle>::new(2);
+        debug_assert_eq!(qt.remap_regions(|_| unreachable!()), Ok(()));
+        debug_assert!(qt.is_empty());
+    }
+}
+
+mod delete {
+    use {
+        super::*,
+        crate::util::{scatter, unordered_elements_are},
+    };
+
+    #[test]
+    fn take_region() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        let region = |x, y, w, h| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .dimensions((w, h))
+                .build()
+                .unwrap()
+        };
+        qt.insert(region(6, 6, 4, 4), 'a');
+        qt.extend(vec![((3, 3), 'b'), ((12, 1), 'c'), ((1, 14), 'd')]);
+        qt.insert(region(0, 0, 16, 2), 'e');
+
+        let taken = qt.take_region(region(0, 0, 8, 8));
+        debug_assert_eq!(
+            taken.iter().map(|e| *e.value_ref()).collect::<String>(),
+            "abe"
+        );
+        assert!(taken.windows(2).all(|w| w[0].handle() < w[1].handle()));
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert_eq!(qt.query(region(0, 0, 8, 8)).count(), 0);
+
+        // Nothing left to take.
+        assert!(qt.take_region(region(0, 0, 8, 8)).is_empty());
+    }
+
+    #[test]
+    fn take_region_strict() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        let region = |x, y, w, h| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .dimensions((w, h))
+                .build()
+                .unwrap()
+        };
+        qt.insert(region(6, 6, 4, 4), 'a');
+        qt.extend(vec![((3, 3), 'b'), ((5, 0), 'c')]);
+
+        let taken = qt.take_region_strict(region(0, 0, 8, 8));
+        debug_assert_eq!(
+            taken.iter().map(|e| *e.value_ref()).collect::<String>(),
+            "bc"
+        );
+        // The straddling region stays behind.
+        debug_assert_eq!(qt.len(), 1);
+    }
+
+    #[test]
+    fn delete_knows_its_length() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend(vec![((0, 0), 1), ((1, 1), 2), ((9, 9), 3)]);
+        let mut deleted = qt.delete(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+        );
+        debug_assert_eq!(deleted.size_hint(), (2, Some(2)));
+        deleted.next();
+        debug_assert_eq!(deleted.size_hint(), (1, Some(1)));
+    }
+
+    #[test]
+    fn delete_by_handle() {
+        let mut qt = Quadtree::<u32, i8>::new(4);
+        // We don't know the indices for any of these.
+        qt.extend(vec![((0, 0), 0), ((2, 3), 5), ((2, 2), 7), ((1, 2), 9)]);
+        debug_assert_eq!(qt.len(), 4);
+
+        // But we will be sure to retain this one.
+        let handle = qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((0, 0).into())
+                    .build()
+                    .unwrap(),
+                11,
+            )
+            .unwrap();
+        debug_assert_eq!(qt.len(), 5); // Insertion succeeded.
+
+        // Check the returned entry.
+        let entry = qt.delete_by_handle(handle).unwrap();
+        let entry_area = entry.area();
         debug_assert_eq!(entry_area.anchor(), (0, 0).into());
         debug_assert_eq!(entry_area.width(), 1);
         debug_assert_eq!(entry_area.height(), 1);
@@ -529,6 +3246,387 @@ mod delete {
             1
         );
     }
+
+    #[test]
+    fn handles_outlive_other_changes() {
+        let mut qt = Quadtree::<u32, i8>::new(4);
+        let region = AreaBuilder::default()
+            .anchor((2, 2).into())
+            .dimensions((3, 3))
+            .build()
+            .unwrap();
+        let handle_a = qt.insert(region, 1).unwrap();
+        let handle_b = qt.insert(region, 2).unwrap();
+
+        // Churn the rest of the tree.
+        qt.extend((0..16).map(|i| ((i, 15 - i), 3)));
+        qt.delete(
+            AreaBuilder::default()
+                .anchor((8, 0).into())
+                .dimensions((8, 8))
+                .build()
+                .unwrap(),
+        );
+        qt.retain(|_, &v| v != 3);
+        qt.compact();
+        let handle_c = qt.insert(region, 4).unwrap();
+
+        debug_assert_eq!(qt.get(handle_a).unwrap().value_ref(), &1);
+        debug_assert_eq!(qt.get(handle_b).unwrap().value_ref(), &2);
+        debug_assert_eq!(qt.get(handle_c).unwrap().value_ref(), &4);
+        debug_assert_eq!(qt.len(), 3);
+
+        // Removing one of several entries in the same region leaves the others alone.
+        debug_assert_eq!(qt.delete_by_handle(handle_b).unwrap().value_ref(), &2);
+        debug_assert!(qt.get(handle_b).is_none());
+        debug_assert!(unordered_elements_are(
+            qt.query(region).map(|e| *e.value_ref()),
+            vec![1, 4]
+        ));
+    }
+
+    #[test]
+    fn delete_then_iterate() {
+        let mut qt = Quadtree::<u32, i8>::new(4);
+        qt.extend(vec![((0, 0), 0), ((2, 3), 5), ((9, 9), 7)]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((1, 1).into())
+                .dimensions((6, 6))
+                .build()
+                .unwrap(),
+            10,
+        );
+
+        let region = AreaBuilder::default()
+            .anchor((2, 2).into())
+            .dimensions((2, 2))
+            .build()
+            .unwrap();
+        debug_assert!(unordered_elements_are(
+            qt.delete(region).map(|e| *e.value_ref()),
+            vec![5, 10]
+        ));
+
+        // Nothing left behind in the tree refers to the deleted entries.
+        debug_assert!(unordered_elements_are(qt.values().copied(), vec![0, 7]));
+        debug_assert_eq!(qt.query(region).count(), 0);
+    }
+
+    #[test]
+    fn delete_strict_keeps_straddling_entries() {
+        let fixture = || {
+            let mut qt = Quadtree::<u32, i8>::new(4);
+            qt.extend(vec![((1, 1), 1), ((3, 3), 2), ((9, 9), 3)]);
+            // Straddles the edge of the region below.
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor((2, 2).into())
+                    .dimensions((4, 4))
+                    .build()
+                    .unwrap(),
+                4,
+            );
+            qt
+        };
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+
+        let mut qt = fixture();
+        debug_assert!(unordered_elements_are(
+            qt.delete_strict(region).map(|e| *e.value_ref()),
+            vec![1, 2]
+        ));
+        debug_assert!(unordered_elements_are(qt.values().copied(), vec![3, 4]));
+
+        let mut qt = fixture();
+        debug_assert!(unordered_elements_are(
+            qt.delete(region).map(|e| *e.value_ref()),
+            vec![1, 2, 4]
+        ));
+        debug_assert!(unordered_elements_are(qt.values().copied(), vec![3]));
+    }
+
+    #[test]
+    fn remove_many() {
+        let mut qt = Quadtree::<u32, u32>::new(5);
+        let handles: Vec<u64> = (0..32)
+            .map(|i| qt.insert_pt((i, 31 - i).into(), i).unwrap())
+            .collect();
+        let big = qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((4, 4).into())
+                    .dimensions((20, 20))
+                    .build()
+                    .unwrap(),
+                100,
+            )
+            .unwrap();
+
+        // Every third point and the big region, with some duplicates and strangers mixed in.
+        let doomed: Vec<u64> = handles
+            .iter()
+            .step_by(3)
+            .copied()
+            .chain(vec![big, big, 9999, handles[0]])
+            .collect();
+        debug_assert_eq!(qt.remove_many(doomed), 12);
+        debug_assert_eq!(qt.len(), 33 - 12);
+        debug_assert!(qt.get(big).is_none());
+
+        // Nothing left in the tree refers to the deleted entries.
+        let everything = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((32, 32))
+            .build()
+            .unwrap();
+        debug_assert!(unordered_elements_are(
+            qt.query(everything).map(|e| *e.value_ref()),
+            (0..32).filter(|i| i % 3 != 0)
+        ));
+        debug_assert_eq!(qt.get_at_point((10, 10).into()).count(), 0);
+
+        debug_assert_eq!(qt.remove_many(vec![]), 0);
+        debug_assert_eq!(qt.remove_many(vec![handles[0]]), 0);
+        debug_assert_eq!(qt.len(), 21);
+    }
+
+    #[test]
+    fn clear_region_matches_delete() {
+        let fixture = || {
+            let mut qt = Quadtree::<u32, i8>::new(4);
+            qt.extend(vec![((0, 0), 0), ((2, 3), 5), ((9, 9), 7)]);
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor((1, 1).into())
+                    .dimensions((6, 6))
+                    .build()
+                    .unwrap(),
+                10,
+            );
+            qt
+        };
+        let mut qt = fixture();
+        let mut other = fixture();
+
+        let region = AreaBuilder::default()
+            .anchor((2, 2).into())
+            .dimensions((2, 2))
+            .build()
+            .unwrap();
+        let overlapping = qt.count_in_region(region);
+        let len = qt.len();
+
+        qt.clear_region(region);
+        other.delete(region);
+
+        debug_assert_eq!(qt.len(), len - overlapping);
+        debug_assert_eq!(qt.query(region).count(), 0);
+        debug_assert!(unordered_elements_are(
+            qt.values().copied(),
+            other.values().copied()
+        ));
+    }
+
+    #[test]
+    fn clear_region_strict() {
+        let mut qt = Quadtree::<u32, i8>::new(4);
+        qt.extend(vec![((2, 3), 5), ((9, 9), 7)]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((1, 1).into())
+                .dimensions((6, 6))
+                .build()
+                .unwrap(),
+            10,
+        );
+
+        let region = AreaBuilder::default()
+            .anchor((2, 2).into())
+            .dimensions((2, 2))
+            .build()
+            .unwrap();
+        qt.clear_region_strict(region);
+
+        // The straddling region is left behind.
+        debug_assert!(unordered_elements_are(qt.values().copied(), vec![7, 10]));
+    }
+
+    #[test]
+    fn clear_region_drops_values() {
+        use std::rc::Rc;
+
+        let value = Rc::new(());
+        let mut qt = Quadtree::<u32, Rc<()>>::new(4);
+        qt.insert_pt((1, 1).into(), Rc::clone(&value));
+        qt.insert_pt((2, 2).into(), Rc::clone(&value));
+        debug_assert_eq!(Rc::strong_count(&value), 3);
+
+        qt.clear_region(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+        );
+        debug_assert!(qt.is_empty());
+        debug_assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn emptying_a_quadrant_prunes_it() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        let nw = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((8, 8))
+            .build()
+            .unwrap();
+        qt.extend(vec![((1, 1), 1), ((6, 2), 2), ((3, 7), 3), ((12, 12), 4)]);
+        let nw_nodes = |qt: &Quadtree<u32, u8>| {
+            let mut nodes = 0;
+            qt.visit_nodes(|region, _, _| nodes += nw.contains(*region) as usize);
+            nodes
+        };
+        debug_assert!(nw_nodes(&qt) > 1);
+
+        qt.delete(nw);
+        // All that's left of the quadrant is the one empty leaf.
+        debug_assert_eq!(nw_nodes(&qt), 1);
+        debug_assert_eq!(qt.node_count(), 1 + 4 + 3 * 4);
+
+        qt.delete_by_handle(qt.iter().next().unwrap().handle());
+        debug_assert_eq!(qt.node_count(), 1);
+        debug_assert_eq!(qt.leaf_count(), 1);
+    }
+
+    #[test]
+    fn deletions_leave_nothing_to_compact() {
+        let mut qt = Quadtree::<u32, u32>::with_capacity((0, 0).into(), 6, 2);
+        for (i, region) in scatter(400, 64, |_| (1, 1)) {
+            qt.insert(region, i);
+        }
+        for i in 0..20 {
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor((i * 3, i * 2).into())
+                    .dimensions((1 + i % 5, 1 + i % 7))
+                    .build()
+                    .unwrap(),
+                1000 + i,
+            );
+        }
+        // The node count before and after compacting.
+        let node_count = |qt: &mut Quadtree<u32, u32>| {
+            let nodes = qt.node_count();
+            qt.compact();
+            (nodes, qt.node_count())
+        };
+
+        let handles: Vec<u64> = qt.iter().map(|e| e.handle()).step_by(3).collect();
+        qt.remove_many(handles);
+        let (nodes, compacted) = node_count(&mut qt);
+        debug_assert_eq!(nodes, compacted);
+
+        qt.retain(|_, &v| v % 5 != 0);
+        let (nodes, compacted) = node_count(&mut qt);
+        debug_assert_eq!(nodes, compacted);
+
+        qt.delete(
+            AreaBuilder::default()
+                .anchor((10, 10).into())
+                .dimensions((30, 20))
+                .build()
+                .unwrap(),
+        );
+        let (nodes, compacted) = node_count(&mut qt);
+        debug_assert_eq!(nodes, compacted);
+
+        let handles: Vec<u64> = qt.iter().map(|e| e.handle()).collect();
+        for handle in handles {
+            qt.delete_by_handle(handle);
+        }
+        debug_assert_eq!(node_count(&mut qt), (1, 1));
+    }
+}
+
+mod retain {
+    use {super::*, crate::util::unordered_elements_are};
+
+    fn fixture() -> Quadtree<u32, i8> {
+        let mut qt = Quadtree::<u32, i8>::new(4);
+        qt.extend(vec![((0, 0), 0), ((2, 3), 5), ((2, 2), 7), ((1, 2), 8)]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((1, 1).into())
+                .dimensions((6, 6))
+                .build()
+                .unwrap(),
+            10,
+        );
+        qt
+    }
+
+    #[test]
+    fn keep_everything() {
+        let mut qt = fixture();
+        let node_count = qt.node_count();
+
+        debug_assert_eq!(qt.retain(|_, _| true).count(), 0);
+        debug_assert_eq!(qt.len(), 5);
+        debug_assert_eq!(qt.iter().count(), 5);
+        debug_assert_eq!(qt.node_count(), node_count);
+    }
+
+    #[test]
+    fn keep_nothing() {
+        let mut qt = fixture();
+
+        debug_assert_eq!(qt.retain(|_, _| false).count(), 5);
+        debug_assert!(qt.is_empty());
+        debug_assert_eq!(qt.iter().count(), 0);
+    }
+
+    #[test]
+    fn by_value() {
+        let mut qt = fixture();
+
+        let removed = qt.retain(|_, &v| v % 2 == 0);
+        debug_assert!(unordered_elements_are(
+            removed.map(|e| *e.value_ref()),
+            vec![5, 7]
+        ));
+        debug_assert_eq!(qt.len(), 3);
+        debug_assert!(unordered_elements_are(qt.values().copied(), vec![0, 8, 10]));
+        debug_assert!(unordered_elements_are(
+            qt.query(
+                AreaBuilder::default()
+                    .anchor((2, 2).into())
+                    .dimensions((1, 2))
+                    .build()
+                    .unwrap()
+            )
+            .map(|e| *e.value_ref()),
+            vec![10]
+        ));
+    }
+
+    #[test]
+    fn by_region() {
+        let mut qt = fixture();
+
+        // Drop the one entry which isn't a point.
+        qt.retain(|area, _| area.width() == 1 && area.height() == 1);
+        debug_assert_eq!(qt.len(), 4);
+        debug_assert!(unordered_elements_are(
+            qt.values().copied(),
+            vec![0, 5, 7, 8]
+        ));
+        debug_assert_eq!(qt.regions().filter(|a| a.width() > 1).count(), 0);
+    }
 }
 
 #[test]
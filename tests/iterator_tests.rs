@@ -14,11 +14,17 @@
 
 mod util; // For unordered_elements_are.
 
-// For testing .iter(), .iter_mut(), .regions(), .values(), .values_mut().
+// For testing .iter(), .iter_mut(), .iter_z_order(), .nth_z(), .iter_hilbert(), .leaves(),
+// .straddling_entries(), .drain_region(), .regions(), .values(), .values_mut().
 mod iterator_tests {
     use {
         crate::util::unordered_elements_are,
-        quadtree_rs::{area::AreaBuilder, entry::Entry, Quadtree},
+        quadtree_rs::{
+            area::{Area, AreaBuilder},
+            entry::Entry,
+            Quadtree,
+        },
+        std::thread,
     };
 
     fn mk_quadtree_for_iter_tests() -> Quadtree<i32, i8> {
@@ -37,6 +43,29 @@ mod iterator_tests {
         ));
     }
 
+    #[test]
+    fn iter_yields_each_entry_once() {
+        let mut qt = mk_quadtree_for_iter_tests();
+        // Held by several nodes, and overlapping the points.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-30, -30).into())
+                .dimensions((60, 60))
+                .build()
+                .unwrap(),
+            100,
+        );
+        debug_assert_eq!(qt.iter().size_hint(), (4, Some(4)));
+        debug_assert!(unordered_elements_are(
+            qt.iter().map(|e| e.value_ref()),
+            vec![&-25, &10, &40, &100]
+        ));
+        debug_assert!(unordered_elements_are(
+            (&qt).into_iter().map(|e| e.handle()),
+            qt.iter().map(|e| e.handle())
+        ));
+    }
+
     // The same as iter_all(), except we mutate each value by +1.
     #[test]
     fn iter_mut_all() {
@@ -66,6 +95,285 @@ mod iterator_tests {
         debug_assert!(unordered_elements_are(qt.values(), vec![&10, &-25, &40]));
     }
 
+    #[test]
+    fn values_mut() {
+        let mut qt = mk_quadtree_for_iter_tests();
+        // One region large enough to be held by several nodes.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-30, -30).into())
+                .dimensions((60, 60))
+                .build()
+                .unwrap(),
+            100,
+        );
+
+        assert_eq!(qt.values_mut().count(), qt.len());
+        for v in qt.values_mut() {
+            *v += 1;
+        }
+
+        debug_assert!(unordered_elements_are(
+            qt.values(),
+            vec![&11, &-24, &41, &101]
+        ));
+    }
+
+    // A depth-3 tree holding:
+    //
+    //     0 1 2 3 4 5 6 7
+    //   0 R R R R . . . .
+    //   1 R p R R . q . .
+    //   2 R R R R . . . .
+    //   3 R R R R w . . .
+    //   4 . . . w w . . .
+    //   5 . . . . . . . .
+    //   6 . . s . . . . .
+    //   7 . . . . . . . e
+    //
+    // where R is the whole northwest quadrant, and w is a 2x2 region straddling all four.
+    fn mk_quadtree_for_z_order_tests(order: &[char]) -> Quadtree<u32, char> {
+        let mut qt = Quadtree::<u32, char>::new(3);
+        for &c in order {
+            let (anchor, dimensions) = match c {
+                'R' => ((0, 0), (4, 4)),
+                'p' => ((1, 1), (1, 1)),
+                'q' => ((5, 1), (1, 1)),
+                'w' => ((3, 3), (2, 2)),
+                's' => ((2, 6), (1, 1)),
+                'e' => ((7, 7), (1, 1)),
+                _ => unreachable!(),
+            };
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor(anchor.into())
+                    .dimensions(dimensions)
+                    .build()
+                    .unwrap(),
+                c,
+            );
+        }
+        qt
+    }
+
+    #[test]
+    fn iter_z_order() {
+        let qt = mk_quadtree_for_z_order_tests(&['e', 'w', 's', 'q', 'R', 'p']);
+
+        // R is held by the northwest quadrant itself, so comes out ahead of the points beneath it.
+        // w is first reached in the northwest quadrant's southeast corner.
+        let order: String = qt.iter_z_order().map(|e| e.value_ref()).collect();
+        debug_assert_eq!(order, "Rpwqse");
+    }
+
+    #[test]
+    fn iter_z_order_is_independent_of_insertion_order() {
+        let qt_a = mk_quadtree_for_z_order_tests(&['R', 'p', 'q', 'w', 's', 'e']);
+        let qt_b = mk_quadtree_for_z_order_tests(&['s', 'q', 'e', 'p', 'w', 'R']);
+
+        debug_assert!(qt_a
+            .iter_z_order()
+            .map(|e| e.value_ref())
+            .eq(qt_b.iter_z_order().map(|e| e.value_ref())));
+    }
+
+    #[test]
+    fn iter_z_order_after_delete() {
+        let mut qt = mk_quadtree_for_z_order_tests(&['e', 'w', 's', 'q', 'R', 'p']);
+        qt.retain(|_, &c| c != 'w' && c != 'q');
+
+        let order: String = qt.iter_z_order().map(|e| e.value_ref()).collect();
+        debug_assert_eq!(order, "Rpse");
+    }
+
+    #[test]
+    fn nth_z() {
+        let qt = mk_quadtree_for_z_order_tests(&['e', 'w', 's', 'q', 'R', 'p']);
+        let order: String = (0..qt.len())
+            .map(|n| *qt.nth_z(n).unwrap().value_ref())
+            .collect();
+        debug_assert_eq!(order, "Rpwqse");
+        debug_assert!(qt.nth_z(qt.len()).is_none());
+        debug_assert!(qt.nth_z(usize::MAX).is_none());
+        debug_assert!(Quadtree::<u32, u8>::new(2).nth_z(0).is_none());
+
+        // The same positions, whatever order the tree was built in.
+        let other = mk_quadtree_for_z_order_tests(&['s', 'q', 'e', 'p', 'w', 'R']);
+        for n in 0..qt.len() {
+            debug_assert_eq!(
+                qt.nth_z(n).map(|e| e.value_ref()),
+                other.nth_z(n).map(|e| e.value_ref())
+            );
+        }
+    }
+
+    #[test]
+    fn iter_hilbert_on_a_4x4_grid() {
+        let mut qt = Quadtree::<u32, (u32, u32)>::new(2);
+        for x in 0..4 {
+            for y in 0..4 {
+                qt.insert_pt((x, y).into(), (x, y));
+            }
+        }
+        //   0 1 2 3
+        // 0 0-1 e-f
+        //     | |
+        // 1 3-2 d-c
+        //   |     |
+        // 2 4 7-8 b
+        //   | | | |
+        // 3 5-6 9-a
+        let order: Vec<(u32, u32)> = qt.iter_hilbert().map(|e| *e.value_ref()).collect();
+        debug_assert_eq!(
+            order,
+            vec![
+                (0, 0),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 3),
+                (1, 2),
+                (2, 2),
+                (2, 3),
+                (3, 3),
+                (3, 2),
+                (3, 1),
+                (2, 1),
+                (2, 0),
+                (3, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn iter_hilbert_matches_the_curve() {
+        // The distance of (x, y) along the Hilbert curve through an n by n grid.
+        fn distance(n: u32, mut x: u32, mut y: u32) -> u32 {
+            let mut d = 0;
+            let mut s = n / 2;
+            while s > 0 {
+                let rx = (x & s > 0) as u32;
+                let ry = (y & s > 0) as u32;
+                d += s * s * ((3 * rx) ^ ry);
+                if ry == 0 {
+                    if rx == 1 {
+                        x = s - 1 - (x & (s - 1));
+                        y = s - 1 - (y & (s - 1));
+                    }
+                    std::mem::swap(&mut x, &mut y);
+                }
+                s /= 2;
+            }
+            d
+        }
+
+        let mut qt = Quadtree::<u32, u32>::new(5);
+        for x in 0..32 {
+            for y in 0..32 {
+                qt.insert_pt((x, y).into(), distance(32, x, y));
+            }
+        }
+        debug_assert!(qt.iter_hilbert().map(|e| *e.value_ref()).eq(0..32 * 32));
+
+        // Consecutive points along the curve are neighbors.
+        let points: Vec<(u32, u32)> = qt
+            .iter_hilbert()
+            .map(|e| (e.anchor().x(), e.anchor().y()))
+            .collect();
+        debug_assert!(points.windows(2).all(|w| {
+            let (a, b) = (w[0], w[1]);
+            (a.0 as i64 - b.0 as i64).abs() + (a.1 as i64 - b.1 as i64).abs() == 1
+        }));
+    }
+
+    #[test]
+    fn iter_hilbert_with_regions() {
+        let qt = mk_quadtree_for_z_order_tests(&['e', 'w', 's', 'q', 'R', 'p']);
+
+        // The curve runs down the west side and back up the east. Within the northwest quadrant
+        // it's turned, and runs across to the northeast corner first.
+        let order: String = qt.iter_hilbert().map(|e| e.value_ref()).collect();
+        debug_assert_eq!(order, "Rpwseq");
+
+        let qt_b = mk_quadtree_for_z_order_tests(&['s', 'q', 'e', 'p', 'w', 'R']);
+        debug_assert!(qt
+            .iter_hilbert()
+            .map(|e| e.value_ref())
+            .eq(qt_b.iter_hilbert().map(|e| e.value_ref())));
+    }
+
+    #[test]
+    fn leaves() {
+        let qt = mk_quadtree_for_z_order_tests(&['e', 'w', 's', 'q', 'R', 'p']);
+        let leaves: Vec<(Area<u32>, String)> = qt
+            .leaves()
+            .map(|(area, entries)| (area, entries.iter().map(|e| e.value_ref()).collect()))
+            .collect();
+
+        // Only leaves holding something, and only the regions they hold themselves: R is held by
+        // the northwest quadrant, and w by one leaf in each of the four quadrants.
+        debug_assert_eq!(leaves.len(), 8);
+        debug_assert!(leaves.iter().all(|(area, _)| area.width() == 1));
+        debug_assert!(!leaves.iter().any(|(_, values)| values.contains('R')));
+        debug_assert_eq!(leaves.iter().filter(|(_, values)| values == "w").count(), 4);
+        for (area, values) in leaves.iter().filter(|(_, values)| values != "w") {
+            let entry = qt.iter().find(|e| values.contains(*e.value_ref())).unwrap();
+            debug_assert_eq!(entry.area(), *area);
+        }
+
+        debug_assert_eq!(Quadtree::<u32, u8>::new(3).leaves().count(), 0);
+    }
+
+    #[test]
+    fn straddling_entries() {
+        let qt = mk_quadtree_for_z_order_tests(&['e', 'w', 's', 'q', 'R', 'p']);
+        // R is held by the northwest quadrant itself, and w is pushed down into all four.
+        debug_assert!(unordered_elements_are(
+            qt.straddling_entries().map(|e| *e.value_ref()),
+            vec!['R', 'w']
+        ));
+        debug_assert_eq!(Quadtree::<u32, u8>::new(3).straddling_entries().count(), 0);
+    }
+
+    #[test]
+    fn straddling_entries_are_those_not_in_exactly_one_leaf() {
+        for &capacity in &[0, 1, 2] {
+            let mut qt = Quadtree::<u32, u32>::with_capacity((0, 0).into(), 3, capacity);
+            for i in 0..10 {
+                qt.insert(
+                    AreaBuilder::default()
+                        .anchor((i % 7, (i * 3) % 8).into())
+                        .dimensions((1 + (i * 5) % 3, 1 + i % 2))
+                        .build()
+                        .unwrap(),
+                    i,
+                );
+            }
+            let mut in_one_leaf: Vec<u64> = Vec::new();
+            let in_leaves: Vec<u64> = qt
+                .leaves()
+                .flat_map(|(_, entries)| entries.into_iter().map(|e| e.handle()))
+                .collect();
+            for handle in qt.iter().map(|e| e.handle()) {
+                if in_leaves.iter().filter(|&&h| h == handle).count() == 1 {
+                    in_one_leaf.push(handle);
+                }
+            }
+
+            let mut straddling: Vec<u64> = qt.straddling_entries().map(|e| e.handle()).collect();
+            straddling.sort_unstable();
+            let mut expected: Vec<u64> = qt
+                .iter()
+                .map(|e| e.handle())
+                .filter(|handle| !in_one_leaf.contains(handle))
+                .collect();
+            expected.sort_unstable();
+            debug_assert_eq!(straddling, expected);
+        }
+    }
+
     #[test]
     fn into_iterator_reference() {
         let mut qt = mk_quadtree_for_iter_tests();
@@ -79,6 +387,59 @@ mod iterator_tests {
         debug_assert!(qt.is_empty());
     }
 
+    #[test]
+    fn into_iterator_mutable_reference() {
+        let mut qt = mk_quadtree_for_iter_tests();
+        for entry in &mut qt {
+            *entry.value_mut() += 1;
+        }
+        let entries: Vec<&mut Entry<i32, i8>> = (&mut qt).into_iter().collect();
+        debug_assert_eq!(entries.len(), 3);
+        debug_assert!(unordered_elements_are(qt.values(), vec![&11, &-24, &41],));
+
+        // Entries keep their regions, and stay where queries find them.
+        for entry in qt.iter_mut() {
+            *entry.value_mut() = entry.anchor().x() as i8;
+        }
+        for entry in qt.iter() {
+            debug_assert_eq!(*entry.value_ref() as i32, entry.anchor().x());
+        }
+    }
+
+    // Iteration should use constant stack space, no matter how deep the tree or how many times a
+    // handle is duplicated across nodes.
+    #[test]
+    fn iter_deep_tree_on_a_small_stack() {
+        let mut qt = Quadtree::<u64, u32>::new(62);
+        // Points spread along the diagonal each carve out their own path to the bottom.
+        for i in 0..62 {
+            assert!(qt.insert_pt((1 << i, 1 << i).into(), i).is_some());
+        }
+        // A region which isn't aligned to any node is held in thousands of nodes along its edges.
+        assert!(qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((1, 1).into())
+                    .dimensions((1022, 1022))
+                    .build()
+                    .unwrap(),
+                100,
+            )
+            .is_some());
+
+        thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(32 * 1024)
+                .spawn_scoped(scope, || {
+                    debug_assert_eq!(qt.iter().count(), 63);
+                    debug_assert_eq!(qt.values().filter(|v| **v == 100).count(), 1);
+                })
+                .unwrap()
+                .join()
+                .unwrap();
+        });
+    }
+
     #[test]
     fn delete_everything() {
         let mut qt = mk_quadtree_for_iter_tests();
@@ -125,6 +486,66 @@ mod iterator_tests {
         );
     }
 
+    #[test]
+    fn drain_region_all() {
+        let mut qt = mk_quadtree_for_iter_tests();
+        let region = AreaBuilder::default()
+            .anchor((-15, -5).into())
+            .dimensions((16, 26))
+            .build()
+            .unwrap();
+
+        let drained = qt.drain_region(region);
+        debug_assert_eq!(drained.size_hint(), (2, Some(2)));
+        debug_assert!(unordered_elements_are(
+            drained.map(|e| *e.value_ref()),
+            vec![10, -25]
+        ));
+        debug_assert_eq!(qt.len(), 1);
+        debug_assert_eq!(qt.query(region).count(), 0);
+        debug_assert_eq!(qt.values().collect::<Vec<_>>(), vec![&40]);
+    }
+
+    #[test]
+    fn drain_region_miss() {
+        let mut qt = mk_quadtree_for_iter_tests();
+        let region = AreaBuilder::default()
+            .anchor((29, -36).into())
+            .build()
+            .unwrap();
+
+        debug_assert_eq!(qt.drain_region(region).count(), 0);
+        debug_assert_eq!(qt.len(), 3);
+    }
+
+    #[test]
+    fn drain_region_stopped_early() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend((0..16).map(|i| ((i, i), i as u8)));
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((8, 8))
+            .build()
+            .unwrap();
+
+        let taken: Vec<u8> = qt
+            .drain_region(region)
+            .take(3)
+            .map(|e| *e.value_ref())
+            .collect();
+        debug_assert_eq!(taken.len(), 3);
+
+        // The iterator has been dropped; the other five matches stay put.
+        debug_assert_eq!(qt.len(), 13);
+        debug_assert_eq!(qt.iter().count(), 13);
+        debug_assert_eq!(qt.query(region).count(), 5);
+        debug_assert!(qt.query(region).all(|e| !taken.contains(e.value_ref())));
+
+        // And a second drain picks up where the first left off.
+        debug_assert_eq!(qt.drain_region(region).count(), 5);
+        debug_assert_eq!(qt.len(), 8);
+    }
+
     #[test]
     fn delete_region_two() {
         let mut qt = mk_quadtree_for_iter_tests();
@@ -0,0 +1,876 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod util; // For unordered_elements_are and scatter.
+
+// For testing .nearest_neighbors(), .neighbors_iter(), .closest() and .furthest().
+mod neighbor_tests {
+    use {
+        crate::util::{scatter, unordered_elements_are},
+        quadtree_rs::{area::AreaBuilder, point::Point, Quadtree},
+    };
+
+    fn mk_quadtree_for_neighbor_tests() -> Quadtree<i32, char> {
+        //   0123456789
+        // 0 a.........
+        // 1 ..........
+        // 2 ...bb.....
+        // 3 ...bb.....
+        // 4 ..........
+        // 5 ........c.
+        let mut qt = Quadtree::<i32, char>::new(4);
+        qt.insert_pt((0, 0).into(), 'a');
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((3, 2).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+            'b',
+        );
+        qt.insert_pt((8, 5).into(), 'c');
+        qt
+    }
+
+    fn values(qt: &Quadtree<i32, char>, pt: Point<i32>, k: usize) -> Vec<char> {
+        qt.nearest_neighbors(pt, k)
+            .iter()
+            .map(|e| *e.value_ref())
+            .collect()
+    }
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u32, u8>::new(4);
+        debug_assert!(qt.nearest_neighbors((3, 3).into(), 5).is_empty());
+    }
+
+    #[test]
+    fn zero_k() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert!(qt.nearest_neighbors((3, 3).into(), 0).is_empty());
+    }
+
+    #[test]
+    fn sorted_by_distance() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert_eq!(values(&qt, (0, 1).into(), 3), vec!['a', 'b', 'c']);
+        debug_assert_eq!(values(&qt, (9, 6).into(), 3), vec!['c', 'b', 'a']);
+    }
+
+    #[test]
+    fn truncated_to_k() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert_eq!(values(&qt, (7, 5).into(), 1), vec!['c']);
+        debug_assert_eq!(values(&qt, (7, 5).into(), 2), vec!['c', 'b']);
+    }
+
+    #[test]
+    fn k_larger_than_len() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert_eq!(qt.nearest_neighbors((7, 5).into(), 10).len(), 3);
+    }
+
+    #[test]
+    fn inside_a_region() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        // (4, 3) is covered by 'b', so 'b' is at distance zero.
+        debug_assert_eq!(values(&qt, (4, 3).into(), 1), vec!['b']);
+    }
+
+    #[test]
+    fn outside_the_tree() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert_eq!(values(&qt, (-20, -20).into(), 1), vec!['a']);
+        debug_assert_eq!(values(&qt, (40, 5).into(), 1), vec!['c']);
+    }
+
+    #[test]
+    fn ties() {
+        let mut qt = Quadtree::<u32, u8>::new(3);
+        qt.insert_pt((2, 4).into(), 1);
+        qt.insert_pt((6, 4).into(), 2);
+        qt.insert_pt((4, 0).into(), 3);
+        // 1 and 2 are equidistant from (4, 4); either may come back.
+        let nearest = qt.nearest_neighbors((4, 4).into(), 1);
+        debug_assert_eq!(nearest.len(), 1);
+        debug_assert!(*nearest[0].value_ref() <= 2);
+
+        debug_assert!(unordered_elements_are(
+            qt.nearest_neighbors((4, 4).into(), 2)
+                .iter()
+                .map(|e| e.value_ref()),
+            vec![&1, &2],
+        ));
+    }
+
+    #[test]
+    fn skips_deleted_entries() {
+        let mut qt = mk_quadtree_for_neighbor_tests();
+        qt.delete(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .build()
+                .unwrap(),
+        );
+        debug_assert_eq!(values(&qt, (0, 0).into(), 3), vec!['b', 'c']);
+    }
+
+    #[test]
+    fn matches_exhaustive_search() {
+        let mut qt = Quadtree::<u32, u32>::new(6);
+        for (i, region) in scatter(200, 64, |i| (1 + i % 3, 1 + i % 2)) {
+            qt.insert(region, i);
+        }
+
+        let pt: Point<u32> = (20, 41).into();
+        let distance = |x: u32, y: u32, w: u32, h: u32| {
+            let dx = if pt.x() < x {
+                x - pt.x()
+            } else if pt.x() >= x + w {
+                pt.x() - (x + w - 1)
+            } else {
+                0
+            };
+            let dy = if pt.y() < y {
+                y - pt.y()
+            } else if pt.y() >= y + h {
+                pt.y() - (y + h - 1)
+            } else {
+                0
+            };
+            dx * dx + dy * dy
+        };
+
+        let mut expected: Vec<u32> = qt
+            .iter()
+            .map(|e| distance(e.anchor().x(), e.anchor().y(), e.width(), e.height()))
+            .collect();
+        expected.sort_unstable();
+        expected.truncate(15);
+
+        let actual: Vec<u32> = qt
+            .nearest_neighbors(pt, 15)
+            .iter()
+            .map(|e| distance(e.anchor().x(), e.anchor().y(), e.width(), e.height()))
+            .collect();
+        debug_assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn neighbors_iter_yields_everything_once() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert_eq!(qt.neighbors_iter((0, 0).into()).count(), qt.len());
+        debug_assert!(unordered_elements_are(
+            qt.neighbors_iter((5, 5).into()).map(|e| *e.value_ref()),
+            "abc".chars()
+        ));
+
+        let empty = Quadtree::<i32, char>::new(4);
+        debug_assert_eq!(empty.neighbors_iter((0, 0).into()).next(), None);
+    }
+
+    #[test]
+    fn neighbors_iter_extends_nearest_neighbors() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        for &pt in &[(0, 0), (3, 2), (9, 14), (-4, 20)] {
+            let all: Vec<char> = qt
+                .neighbors_iter(pt.into())
+                .map(|e| *e.value_ref())
+                .collect();
+            debug_assert_eq!(all.len(), 3);
+            let distances: Vec<f64> = all
+                .iter()
+                .map(|v| {
+                    let entry = qt.iter().find(|e| e.value_ref() == v).unwrap();
+                    let (x, y) = (pt.0 as f64, pt.1 as f64);
+                    let left = f64::from(entry.anchor().x());
+                    let top = f64::from(entry.anchor().y());
+                    let dx = (left - x)
+                        .max(x - (left + f64::from(entry.width()) - 1.0))
+                        .max(0.0);
+                    let dy = (top - y)
+                        .max(y - (top + f64::from(entry.height()) - 1.0))
+                        .max(0.0);
+                    dx * dx + dy * dy
+                })
+                .collect();
+            debug_assert!(distances.windows(2).all(|w| w[0] <= w[1]));
+        }
+        // Without ties, the first k are exactly the k nearest.
+        debug_assert_eq!(
+            qt.neighbors_iter((0, 0).into())
+                .take(2)
+                .map(|e| *e.value_ref())
+                .collect::<Vec<_>>(),
+            values(&qt, (0, 0).into(), 2)
+        );
+    }
+
+    #[test]
+    fn neighbors_iter_is_fused() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        let mut iter = qt.neighbors_iter((1, 1).into());
+        for _ in 0..3 {
+            debug_assert!(iter.next().is_some());
+        }
+        debug_assert_eq!(iter.next(), None);
+        debug_assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn closest() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert_eq!(qt.closest((1, 1).into()).map(|e| *e.value_ref()), Some('a'));
+        // Inside a region.
+        debug_assert_eq!(qt.closest((4, 3).into()).map(|e| *e.value_ref()), Some('b'));
+        debug_assert_eq!(qt.closest((9, 9).into()).map(|e| *e.value_ref()), Some('c'));
+
+        debug_assert!(Quadtree::<u32, u8>::new(4).closest((3, 3).into()).is_none());
+    }
+
+    #[test]
+    fn closest_agrees_with_nearest_neighbors() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        for x in 0..16 {
+            for y in 0..16 {
+                let pt = (x, y).into();
+                debug_assert_eq!(
+                    qt.closest(pt).map(|e| e.handle()),
+                    qt.nearest_neighbors(pt, 1).first().map(|e| e.handle())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn closest_ties_are_stable() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        // Four points, each at the same distance from (5, 5).
+        qt.extend(vec![((4, 5), 0), ((6, 5), 1), ((5, 4), 2), ((5, 6), 3)]);
+        let first = qt.closest((5, 5).into()).unwrap().handle();
+        for _ in 0..10 {
+            debug_assert_eq!(qt.closest((5, 5).into()).unwrap().handle(), first);
+        }
+    }
+
+    #[test]
+    fn furthest() {
+        let qt = mk_quadtree_for_neighbor_tests();
+        debug_assert_eq!(
+            qt.furthest((1, 1).into()).map(|e| *e.value_ref()),
+            Some('c')
+        );
+        debug_assert_eq!(
+            qt.furthest((8, 5).into()).map(|e| *e.value_ref()),
+            Some('a')
+        );
+        // Measured to the closest point of 'b', which is (3, 3), so 'a' is farther.
+        debug_assert_eq!(
+            qt.furthest((6, 3).into()).map(|e| *e.value_ref()),
+            Some('a')
+        );
+
+        debug_assert!(Quadtree::<u32, u8>::new(4)
+            .furthest((3, 3).into())
+            .is_none());
+    }
+
+    #[test]
+    fn furthest_agrees_with_exhaustive_search() {
+        let mut qt = Quadtree::<i32, u8>::with_capacity((-8, -8).into(), 4, 1);
+        let regions = [
+            ((-8, -8), (1, 1)),
+            ((-3, 2), (4, 2)),
+            ((5, -7), (2, 6)),
+            ((0, 0), (8, 8)),
+            ((-6, 6), (1, 1)),
+            ((2, -2), (1, 1)),
+        ];
+        for (i, &(anchor, dims)) in regions.iter().enumerate() {
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor(anchor.into())
+                    .dimensions(dims)
+                    .build()
+                    .unwrap(),
+                i as u8,
+            );
+        }
+        let distance_sq = |pt: Point<i32>, area: quadtree_rs::area::Area<i32>| {
+            let dx = (area.left_edge() - pt.x())
+                .max(pt.x() - area.right_edge() + 1)
+                .max(0);
+            let dy = (area.top_edge() - pt.y())
+                .max(pt.y() - area.bottom_edge() + 1)
+                .max(0);
+            dx * dx + dy * dy
+        };
+        for x in -8..8 {
+            for y in -8..8 {
+                let pt = (x, y).into();
+                let farthest = qt.iter().map(|e| distance_sq(pt, e.area())).max();
+                debug_assert_eq!(qt.furthest(pt).map(|e| distance_sq(pt, e.area())), farthest);
+            }
+        }
+    }
+
+    #[test]
+    fn furthest_ties_are_stable() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        // Four points, each at the same distance from (5, 5).
+        qt.extend(vec![((4, 5), 0), ((6, 5), 1), ((5, 4), 2), ((5, 6), 3)]);
+        let first = qt.furthest((5, 5).into()).unwrap().handle();
+        for _ in 0..10 {
+            debug_assert_eq!(qt.furthest((5, 5).into()).unwrap().handle(), first);
+        }
+    }
+}
+
+// For testing .within_radius().
+mod radius_tests {
+    use {
+        crate::util::unordered_elements_are,
+        quadtree_rs::{area::AreaBuilder, Quadtree},
+    };
+
+    fn mk_quadtree_for_radius_tests() -> Quadtree<i32, char> {
+        //   0123456789
+        // 0 a.........
+        // 1 ..........
+        // 2 ...bb.....
+        // 3 ...bb.....
+        // 4 ..........
+        // 5 ........c.
+        let mut qt = Quadtree::<i32, char>::new_with_anchor((-8, -8).into(), 5);
+        qt.insert_pt((0, 0).into(), 'a');
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((3, 2).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+            'b',
+        );
+        qt.insert_pt((8, 5).into(), 'c');
+        qt
+    }
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u32, u8>::new(4);
+        debug_assert_eq!(qt.within_radius((3, 3).into(), 5).count(), 0);
+    }
+
+    #[test]
+    fn zero_radius() {
+        let qt = mk_quadtree_for_radius_tests();
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((0, 0).into(), 0).map(|e| e.value_ref()),
+            vec![&'a'],
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((4, 3).into(), 0).map(|e| e.value_ref()),
+            vec![&'b'],
+        ));
+        debug_assert_eq!(qt.within_radius((1, 0).into(), 0).count(), 0);
+    }
+
+    #[test]
+    fn negative_radius() {
+        let qt = mk_quadtree_for_radius_tests();
+        debug_assert_eq!(qt.within_radius((0, 0).into(), -1).count(), 0);
+    }
+
+    #[test]
+    fn boundary_counts_as_inside() {
+        let qt = mk_quadtree_for_radius_tests();
+        // 'b' is exactly 3 away from (0, 2), and 'a' is exactly 2 away.
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((0, 2).into(), 3).map(|e| e.value_ref()),
+            vec![&'a', &'b'],
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((0, 2).into(), 2).map(|e| e.value_ref()),
+            vec![&'a'],
+        ));
+    }
+
+    #[test]
+    fn circle_not_box() {
+        let qt = mk_quadtree_for_radius_tests();
+        // The corner of the bounding box around (5, 2) with radius 3 touches 'c' at (8, 5), but
+        // 'c' is sqrt(18) away.
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((5, 2).into(), 3).map(|e| e.value_ref()),
+            vec![&'b'],
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((5, 2).into(), 6).map(|e| e.value_ref()),
+            vec![&'a', &'b', &'c'],
+        ));
+    }
+
+    #[test]
+    fn circle_partially_outside_the_tree() {
+        let qt = mk_quadtree_for_radius_tests();
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((-20, 0).into(), 20).map(|e| e.value_ref()),
+            vec![&'a'],
+        ));
+        debug_assert_eq!(qt.within_radius((-100, -100).into(), 20).count(), 0);
+    }
+
+    #[test]
+    fn radius_saturates_at_the_coordinate_bounds() {
+        let mut qt = Quadtree::<u8, char>::new(7);
+        qt.insert_pt((127, 127).into(), 'z');
+        qt.insert_pt((0, 0).into(), 'a');
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((126, 126).into(), u8::MAX)
+                .map(|e| e.value_ref()),
+            vec![&'a', &'z'],
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.within_radius((1, 1).into(), 100).map(|e| e.value_ref()),
+            vec![&'a'],
+        ));
+    }
+}
+
+// For testing .within_ring().
+mod ring_tests {
+    use {
+        crate::util::{scatter, unordered_elements_are},
+        quadtree_rs::{area::AreaBuilder, point::Point, Quadtree},
+    };
+
+    fn mk_quadtree_for_ring_tests() -> Quadtree<i32, u32> {
+        let mut qt = Quadtree::<i32, u32>::new_with_anchor((-16, -16).into(), 5);
+        for (i, region) in scatter(120, 32, |i| (1 + i % 3, 1 + i % 4)) {
+            let anchor = region.anchor();
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor((anchor.x() as i32 - 16, anchor.y() as i32 - 16).into())
+                    .dimensions((region.width() as i32, region.height() as i32))
+                    .build()
+                    .unwrap(),
+                i,
+            );
+        }
+        qt
+    }
+
+    fn sorted(it: impl Iterator<Item = u32>) -> Vec<u32> {
+        let mut v: Vec<u32> = it.collect();
+        v.sort_unstable();
+        v
+    }
+
+    #[test]
+    fn zero_inner_radius_is_within_radius() {
+        let qt = mk_quadtree_for_ring_tests();
+        for &(center, outer) in &[((0, 0), 5), ((-10, 7), 9), ((3, -2), 0), ((40, 40), 30)] {
+            let center: Point<i32> = center.into();
+            debug_assert_eq!(
+                sorted(qt.within_ring(center, 0, outer).map(|e| *e.value_ref())),
+                sorted(qt.within_radius(center, outer).map(|e| *e.value_ref()))
+            );
+        }
+    }
+
+    #[test]
+    fn matches_exhaustive_search() {
+        let qt = mk_quadtree_for_ring_tests();
+        let center: Point<i32> = (2, -3).into();
+        let (inner, outer) = (4, 9);
+
+        // The squared distances to the nearest and furthest cells of each region.
+        let expected = sorted(
+            qt.iter()
+                .filter(|e| {
+                    let mut dists = Vec::new();
+                    for x in e.anchor().x()..e.anchor().x() + e.width() {
+                        for y in e.anchor().y()..e.anchor().y() + e.height() {
+                            let (dx, dy) = (x - center.x(), y - center.y());
+                            dists.push(dx * dx + dy * dy);
+                        }
+                    }
+                    let nearest = *dists.iter().min().unwrap();
+                    let furthest = *dists.iter().max().unwrap();
+                    nearest <= outer * outer && furthest >= inner * inner
+                })
+                .map(|e| *e.value_ref()),
+        );
+        debug_assert!(!expected.is_empty());
+        debug_assert_eq!(
+            sorted(qt.within_ring(center, inner, outer).map(|e| *e.value_ref())),
+            expected
+        );
+    }
+
+    #[test]
+    fn boundaries_are_inclusive() {
+        let mut qt = Quadtree::<i32, char>::new_with_anchor((-8, -8).into(), 4);
+        qt.insert_pt((2, 0).into(), 'a');
+        qt.insert_pt((0, 4).into(), 'b');
+        qt.insert_pt((1, 0).into(), 'c');
+        qt.insert_pt((5, 0).into(), 'd');
+        debug_assert!(unordered_elements_are(
+            qt.within_ring((0, 0).into(), 2, 4).map(|e| e.value_ref()),
+            vec![&'a', &'b'],
+        ));
+        // A ring of zero width.
+        debug_assert!(unordered_elements_are(
+            qt.within_ring((0, 0).into(), 4, 4).map(|e| e.value_ref()),
+            vec![&'b'],
+        ));
+    }
+
+    #[test]
+    fn regions_straddling_the_inner_circle() {
+        let mut qt = Quadtree::<i32, char>::new_with_anchor((-8, -8).into(), 4);
+        // Covers the center, but reaches out to the ring.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-3, -1).into())
+                .dimensions((6, 2))
+                .build()
+                .unwrap(),
+            'a',
+        );
+        // Covers the center, and lies wholly inside the inner circle.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-1, -1).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+            'b',
+        );
+        debug_assert!(unordered_elements_are(
+            qt.within_ring((0, 0).into(), 3, 6).map(|e| e.value_ref()),
+            vec![&'a'],
+        ));
+    }
+
+    #[test]
+    fn empty_rings() {
+        let qt = mk_quadtree_for_ring_tests();
+        debug_assert_eq!(qt.within_ring((0, 0).into(), 6, 5).count(), 0);
+        debug_assert_eq!(qt.within_ring((0, 0).into(), 0, -1).count(), 0);
+        debug_assert_eq!(qt.within_ring((0, 0).into(), 1000, 2000).count(), 0);
+        // A negative inner radius excludes nothing.
+        debug_assert_eq!(
+            qt.within_ring((0, 0).into(), -3, 5).count(),
+            qt.within_radius((0, 0).into(), 5).count()
+        );
+    }
+}
+
+// For testing .nearest_neighbors_by() and .within_radius_by().
+mod metric_tests {
+    use {
+        crate::util::{scatter, unordered_elements_are},
+        quadtree_rs::{area::AreaBuilder, metric, point::Point, Quadtree},
+    };
+
+    fn mk_quadtree_for_metric_tests() -> Quadtree<i32, char> {
+        //   0123456789
+        // 0 a.........
+        // 1 ..........
+        // 2 ...bb.....
+        // 3 ...bb.....
+        // 4 ..........
+        // 5 ........c.
+        let mut qt = Quadtree::<i32, char>::new(4);
+        qt.insert_pt((0, 0).into(), 'a');
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((3, 2).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+            'b',
+        );
+        qt.insert_pt((8, 5).into(), 'c');
+        qt
+    }
+
+    fn values<D>(qt: &Quadtree<i32, char>, pt: Point<i32>, k: usize, dist: D) -> Vec<char>
+    where
+        D: Fn(Point<i32>, Point<i32>) -> u64,
+    {
+        qt.nearest_neighbors_by(pt, k, dist)
+            .iter()
+            .map(|e| *e.value_ref())
+            .collect()
+    }
+
+    #[test]
+    fn metrics() {
+        let (a, b) = (Point { x: -2, y: 3 }, Point { x: 1, y: -1 });
+        debug_assert_eq!(metric::euclidean_sq(a, b), 25);
+        debug_assert_eq!(metric::manhattan(a, b), 7);
+        debug_assert_eq!(metric::chebyshev(a, b), 4);
+        debug_assert_eq!(metric::chebyshev(a, a), 0);
+    }
+
+    #[test]
+    fn metrics_saturate() {
+        let (a, b) = (Point { x: i64::MIN, y: 0 }, Point { x: i64::MAX, y: 0 });
+        debug_assert_eq!(metric::manhattan(a, b), u64::MAX);
+        debug_assert_eq!(metric::euclidean_sq(a, b), u64::MAX);
+        debug_assert_eq!(
+            metric::chebyshev(Point { x: 0_u64, y: 0 }, Point { x: u64::MAX, y: 0 }),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn euclidean_matches_nearest_neighbors() {
+        let qt = mk_quadtree_for_metric_tests();
+        for x in 0..16 {
+            for y in 0..16 {
+                let pt = (x, y).into();
+                let by: Vec<u64> = qt
+                    .nearest_neighbors_by(pt, 3, metric::euclidean_sq)
+                    .iter()
+                    .map(|e| e.handle())
+                    .collect();
+                let plain: Vec<u64> = qt
+                    .nearest_neighbors(pt, 3)
+                    .iter()
+                    .map(|e| e.handle())
+                    .collect();
+                debug_assert_eq!(by, plain);
+            }
+        }
+    }
+
+    #[test]
+    fn metric_changes_the_order() {
+        let qt = mk_quadtree_for_metric_tests();
+        // From (6, 0): 'a' is 6 away by either metric. 'b' is 2 + 2 = 4 by taxicab, and 'c' is
+        // 2 + 5 = 7; by chessboard they're 2 and 5.
+        debug_assert_eq!(
+            values(&qt, (6, 0).into(), 3, metric::manhattan),
+            vec!['b', 'a', 'c']
+        );
+        debug_assert_eq!(
+            values(&qt, (6, 0).into(), 3, metric::chebyshev),
+            vec!['b', 'c', 'a']
+        );
+    }
+
+    #[test]
+    fn custom_metric() {
+        let qt = mk_quadtree_for_metric_tests();
+        // Only horizontal distance counts, so how far down the query point is doesn't matter.
+        let dx = |a: Point<i32>, b: Point<i32>| (a.x() - b.x()).unsigned_abs() as u64;
+        debug_assert_eq!(values(&qt, (0, 9).into(), 1, dx), vec!['a']);
+        debug_assert_eq!(values(&qt, (9, 0).into(), 1, dx), vec!['c']);
+    }
+
+    #[test]
+    fn matches_exhaustive_search() {
+        let mut qt = Quadtree::<u32, u32>::new(6);
+        for (i, region) in scatter(200, 64, |i| (1 + i % 3, 1 + i % 2)) {
+            qt.insert(region, i);
+        }
+
+        let pt: Point<u32> = (20, 41).into();
+        for dist in [metric::manhattan, metric::chebyshev] {
+            // The cells of a region, and the distance to the nearest of them.
+            let distance = |e: &quadtree_rs::entry::Entry<u32, u32>| {
+                let mut best = u64::MAX;
+                for x in e.anchor().x()..e.anchor().x() + e.width() {
+                    for y in e.anchor().y()..e.anchor().y() + e.height() {
+                        best = best.min(dist(pt, (x, y).into()));
+                    }
+                }
+                best
+            };
+
+            let mut expected: Vec<u64> = qt.iter().map(distance).collect();
+            expected.sort_unstable();
+            expected.truncate(15);
+
+            let actual: Vec<u64> = qt
+                .nearest_neighbors_by(pt, 15, dist)
+                .into_iter()
+                .map(distance)
+                .collect();
+            debug_assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn within_radius_by() {
+        let qt = mk_quadtree_for_metric_tests();
+        // From (6, 0), radius 4 takes in 'b' by taxicab. The chessboard square of radius 5 also
+        // takes in 'c', on its edge.
+        debug_assert!(unordered_elements_are(
+            qt.within_radius_by((6, 0).into(), 4, metric::manhattan)
+                .map(|e| e.value_ref()),
+            vec![&'b'],
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.within_radius_by((6, 0).into(), 5, metric::chebyshev)
+                .map(|e| e.value_ref()),
+            vec![&'b', &'c'],
+        ));
+        debug_assert_eq!(
+            qt.within_radius_by((6, 0).into(), 6, metric::chebyshev)
+                .map(|e| *e.value_ref())
+                .collect::<Vec<char>>(),
+            vec!['b', 'c', 'a']
+        );
+        debug_assert_eq!(
+            qt.within_radius_by((-50, -50).into(), 10, metric::manhattan)
+                .count(),
+            0
+        );
+    }
+}
+
+// For testing .raycast().
+mod raycast_tests {
+    use quadtree_rs::{area::AreaBuilder, point::Point, Quadtree};
+
+    fn mk_quadtree_for_raycast_tests() -> Quadtree<i32, char> {
+        //    01234567
+        //  0 ........
+        //  1 ..aaa...
+        //  2 ..aaa..b
+        //  3 ........
+        //  4 c.......
+        //  5 .....dd.
+        //  6 .....dd.
+        //  7 ........
+        let mut qt = Quadtree::<i32, char>::new(3);
+        for (anchor, dims, val) in [
+            ((2, 1), (3, 2), 'a'),
+            ((7, 2), (1, 1), 'b'),
+            ((0, 4), (1, 1), 'c'),
+            ((5, 5), (2, 2), 'd'),
+        ] {
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor(anchor.into())
+                    .dimensions(dims)
+                    .build()
+                    .unwrap(),
+                val,
+            );
+        }
+        qt
+    }
+
+    fn hit(qt: &Quadtree<i32, char>, origin: (i32, i32), dir: (i32, i32)) -> Option<char> {
+        qt.raycast(Point::from(origin), dir).map(|e| *e.value_ref())
+    }
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u32, u8>::new(3);
+        debug_assert!(qt.raycast((0, 0).into(), (1, 1)).is_none());
+    }
+
+    #[test]
+    fn axis_aligned() {
+        let qt = mk_quadtree_for_raycast_tests();
+        debug_assert_eq!(hit(&qt, (0, 2), (1, 0)), Some('a'));
+        debug_assert_eq!(hit(&qt, (5, 2), (1, 0)), Some('b'));
+        debug_assert_eq!(hit(&qt, (5, 2), (-1, 0)), Some('a'));
+        debug_assert_eq!(hit(&qt, (6, 0), (0, 1)), Some('d'));
+        debug_assert_eq!(hit(&qt, (0, 7), (0, -1)), Some('c'));
+        debug_assert_eq!(hit(&qt, (3, 7), (0, -1)), Some('a'));
+        debug_assert_eq!(hit(&qt, (7, 7), (0, -1)), Some('b'));
+    }
+
+    #[test]
+    fn diagonal() {
+        let qt = mk_quadtree_for_raycast_tests();
+        debug_assert_eq!(hit(&qt, (0, 0), (1, 1)), Some('a'));
+        debug_assert_eq!(hit(&qt, (3, 3), (1, 1)), Some('d'));
+        debug_assert_eq!(hit(&qt, (4, 0), (-1, 1)), Some('a'));
+        debug_assert_eq!(hit(&qt, (3, 1), (-1, 1)), Some('a'));
+        debug_assert_eq!(hit(&qt, (2, 3), (-1, 1)), None);
+    }
+
+    #[test]
+    fn general_direction() {
+        let qt = mk_quadtree_for_raycast_tests();
+        // From the middle of (0, 7) going two right for every one up: through (2, 6), (4, 5), and
+        // into 'd' at (5, 5).
+        debug_assert_eq!(hit(&qt, (0, 7), (2, -1)), Some('d'));
+        debug_assert_eq!(hit(&qt, (0, 7), (3, -1)), Some('d'));
+        debug_assert_eq!(hit(&qt, (0, 7), (1, -3)), Some('a'));
+    }
+
+    #[test]
+    fn grazing_a_corner() {
+        let qt = mk_quadtree_for_raycast_tests();
+        // Up and to the right from (0, 7) passes exactly through the bottom-right corner of 'a',
+        // at (5, 3).
+        debug_assert_eq!(hit(&qt, (0, 7), (1, -1)), None);
+    }
+
+    #[test]
+    fn misses() {
+        let qt = mk_quadtree_for_raycast_tests();
+        debug_assert_eq!(hit(&qt, (0, 0), (1, 0)), None);
+        debug_assert_eq!(hit(&qt, (1, 7), (0, -1)), None);
+    }
+
+    #[test]
+    fn starting_inside_a_region() {
+        let qt = mk_quadtree_for_raycast_tests();
+        debug_assert_eq!(hit(&qt, (3, 2), (1, 0)), Some('a'));
+        debug_assert_eq!(hit(&qt, (3, 2), (-1, -1)), Some('a'));
+    }
+
+    #[test]
+    fn no_direction() {
+        let qt = mk_quadtree_for_raycast_tests();
+        debug_assert_eq!(hit(&qt, (3, 2), (0, 0)), None);
+    }
+
+    #[test]
+    fn from_outside_the_tree() {
+        let qt = mk_quadtree_for_raycast_tests();
+        debug_assert_eq!(hit(&qt, (-10, 4), (1, 0)), Some('c'));
+        debug_assert_eq!(hit(&qt, (-10, 4), (-1, 0)), None);
+        debug_assert_eq!(hit(&qt, (20, 20), (1, 1)), None);
+    }
+
+    #[test]
+    fn nearest_hit_wins() {
+        // Lots of points along one row; the ray should stop at the first.
+        let mut qt = Quadtree::<u32, u32>::new(6);
+        for x in (10..64).step_by(3) {
+            qt.insert_pt((x, 40).into(), x);
+        }
+        debug_assert_eq!(qt.raycast((0, 40).into(), (1, 0)).unwrap().value_ref(), &10);
+        debug_assert_eq!(
+            qt.raycast((63, 40).into(), (-1, 0)).unwrap().value_ref(),
+            &61
+        );
+        debug_assert_eq!(
+            qt.raycast((30, 40).into(), (1, 0)).unwrap().value_ref(),
+            &31
+        );
+    }
+}
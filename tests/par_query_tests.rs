@@ -0,0 +1,122 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Only built with `--features rayon`.
+#![cfg(feature = "rayon")]
+
+// For testing .par_query() and .par_query_strict().
+mod par_query_tests {
+    use {
+        quadtree_rs::{
+            area::{Area, AreaBuilder},
+            Quadtree,
+        },
+        rayon::iter::ParallelIterator,
+    };
+
+    // A simple linear congruential generator, so that the trees are the same on every run.
+    fn lcg(seed: &mut u64) -> u32 {
+        *seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (*seed >> 33) as u32
+    }
+
+    fn random_area(seed: &mut u64, max: u32) -> Area<u32> {
+        let (x, y) = (lcg(seed) % max, lcg(seed) % max);
+        AreaBuilder::default()
+            .anchor((x, y).into())
+            .dimensions((1 + lcg(seed) % (max - x), 1 + lcg(seed) % (max - y)))
+            .build()
+            .unwrap()
+    }
+
+    // Points, and regions of every size, many of which are held by more than one node.
+    fn mk_quadtree(node_capacity: usize) -> Quadtree<u32, usize> {
+        let mut seed = 7;
+        let mut qt = Quadtree::<u32, usize>::with_capacity((0, 0).into(), 7, node_capacity);
+        for i in 0..500 {
+            if i % 3 == 0 {
+                qt.insert(random_area(&mut seed, 128), i);
+            } else {
+                qt.insert_pt((lcg(&mut seed) % 128, lcg(&mut seed) % 128).into(), i);
+            }
+        }
+        qt
+    }
+
+    fn sorted(handles: impl IntoIterator<Item = u64>) -> Vec<u64> {
+        let mut handles: Vec<u64> = handles.into_iter().collect();
+        handles.sort_unstable();
+        handles
+    }
+
+    #[test]
+    fn matches_query() {
+        for &node_capacity in [0, 4].iter() {
+            let qt = mk_quadtree(node_capacity);
+            let mut seed = 99;
+            for _ in 0..100 {
+                let area = random_area(&mut seed, 128);
+                let expected = sorted(qt.query(area).map(|e| e.handle()));
+                // Each entry exactly once.
+                debug_assert_eq!(
+                    sorted(qt.par_query(area).map(|e| e.handle()).collect::<Vec<_>>()),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_query_strict() {
+        let qt = mk_quadtree(0);
+        let mut seed = 5;
+        for _ in 0..100 {
+            let area = random_area(&mut seed, 128);
+            debug_assert_eq!(
+                sorted(
+                    qt.par_query_strict(area)
+                        .map(|e| e.handle())
+                        .collect::<Vec<_>>()
+                ),
+                sorted(qt.query_strict(area).map(|e| e.handle()))
+            );
+        }
+    }
+
+    #[test]
+    fn whole_tree() {
+        let qt = mk_quadtree(0);
+        debug_assert_eq!(qt.par_query(qt.root_region()).count(), qt.len());
+    }
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u32, u8>::new(4);
+        debug_assert_eq!(qt.par_query(qt.root_region()).count(), 0);
+    }
+
+    #[test]
+    fn after_deletes() {
+        let mut qt = mk_quadtree(0);
+        let area = random_area(&mut 3, 128);
+        qt.delete(area);
+        debug_assert_eq!(qt.par_query(area).count(), 0);
+        debug_assert_eq!(
+            qt.par_query(qt.root_region()).count(),
+            qt.query(qt.root_region()).count()
+        );
+    }
+}
@@ -22,6 +22,15 @@ mod point_tests {
         debug_assert_eq!(p.y(), 2);
     }
 
+    #[test]
+    fn conversions() {
+        let p: Point<i8> = (&(3, -4)).into();
+        debug_assert_eq!(p, Point { x: 3, y: -4 });
+
+        let t: (i8, i8) = p.into();
+        debug_assert_eq!(t, (3, -4));
+    }
+
     #[test]
     fn xy_addition() {
         debug_assert_eq!(Point::from((0, 0)) + Point::from((0, 1)), (0, 1).into());
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-mod util; // For unordered_elements_are.
+mod util; // For unordered_elements_are and scatter.
 
-// For testing .query(), .modify().
+// For testing .query(), .query_filter(), .query_sorted_by(), .query_clipped(), .query_touching(),
+// .adjacent_to(), .count_in_region(), .any_in_region(), .contains_point(), .region_fully_covered(),
+// .get_at_point(), .query_polygon(), .modify().
 mod query_tests {
     use {
         crate::util::unordered_elements_are,
@@ -631,6 +633,79 @@ mod query_tests {
         ));
     }
 
+    #[test]
+    fn query_strict_drops_straddling_entries() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        // Wholly inside the query region.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, 2).into())
+                .dimensions((3, 3))
+                .build()
+                .unwrap(),
+            'a',
+        );
+        // Exactly equal to the query region.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((1, 1).into())
+                .dimensions((6, 6))
+                .build()
+                .unwrap(),
+            'b',
+        );
+        // Pokes one cell out of the right edge.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((5, 2).into())
+                .dimensions((3, 1))
+                .build()
+                .unwrap(),
+            'c',
+        );
+        // Pokes one cell out of the top-left corner.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((2, 2))
+                .build()
+                .unwrap(),
+            'd',
+        );
+        // Covers the query region entirely.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((10, 10))
+                .build()
+                .unwrap(),
+            'e',
+        );
+        // Entirely outside the query region.
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((12, 12).into())
+                .build()
+                .unwrap(),
+            'f',
+        );
+
+        let region = AreaBuilder::default()
+            .anchor((1, 1).into())
+            .dimensions((6, 6))
+            .build()
+            .unwrap();
+
+        debug_assert!(unordered_elements_are(
+            qt.query(region).map(|e| *e.value_ref()),
+            vec!['a', 'b', 'c', 'd', 'e']
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.query_strict(region).map(|e| *e.value_ref()),
+            vec!['a', 'b']
+        ));
+    }
+
     #[test]
     fn query_exhibiting_collection() {
         let mut qt: Quadtree<u8, f32> = Quadtree::new(2);
@@ -655,6 +730,449 @@ mod query_tests {
         debug_assert_eq!(query_obj.next().unwrap().value_ref(), &1.234);
     }
 
+    #[test]
+    fn query_near_the_coordinate_bounds() {
+        let mut qt = Quadtree::<i32, u8>::new_with_anchor((i32::MAX - 16, i32::MAX - 16).into(), 4);
+        assert!(qt
+            .insert_pt((i32::MAX - 1, i32::MAX - 1).into(), 1)
+            .is_some());
+        assert!(qt
+            .insert_pt((i32::MAX - 16, i32::MAX - 16).into(), 2)
+            .is_some());
+        // The largest coordinate is outside of every region, so can't be inserted.
+        assert!(qt.insert_pt((i32::MAX, i32::MAX).into(), 3).is_none());
+        qt.extend(vec![((i32::MAX, 0), 4)]);
+        debug_assert_eq!(qt.len(), 2);
+
+        // A query which would wrap around can't be built at all.
+        debug_assert!(AreaBuilder::default()
+            .anchor((i32::MAX - 1, i32::MAX - 1).into())
+            .dimensions((10, 10))
+            .build()
+            .is_err());
+
+        let corner = AreaBuilder::default()
+            .anchor((i32::MAX - 1, i32::MAX - 1).into())
+            .build()
+            .unwrap();
+        debug_assert!(unordered_elements_are(
+            qt.query(corner).map(|e| e.value_ref()),
+            vec![&1]
+        ));
+        debug_assert!(qt.contains_point((i32::MAX - 1, i32::MAX - 1).into()));
+        debug_assert!(!qt.contains_point((i32::MAX, i32::MAX).into()));
+    }
+
+    #[test]
+    fn query_yields_each_entry_once() {
+        let area = |x, y, w, h| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .dimensions((w, h))
+                .build()
+                .unwrap()
+        };
+        for &capacity in &[0, 1, 3] {
+            let mut qt = Quadtree::<u32, u32>::with_capacity((0, 0).into(), 3, capacity);
+            // Regions straddling every boundary at every level, so each is kept at many nodes.
+            let mut regions = Vec::new();
+            for i in 0..12 {
+                let region = area(i % 5, (i * 3) % 6, 1 + (i * 7) % 7, 1 + i % 3);
+                qt.insert(region, i);
+                regions.push(region);
+            }
+            qt.insert(area(0, 0, 8, 8), 12);
+            regions.push(area(0, 0, 8, 8));
+
+            for x in 0..8 {
+                for y in 0..8 {
+                    for &(w, h) in &[(1, 1), (2, 3), (5, 2), (8 - x, 8 - y)] {
+                        let query = area(x, y, w.min(8 - x), h.min(8 - y));
+                        let mut found: Vec<u32> = qt.query(query).map(|e| *e.value_ref()).collect();
+                        found.sort_unstable();
+                        let expected: Vec<u32> = (0..13)
+                            .filter(|&i| regions[i as usize].intersects(query))
+                            .collect();
+                        debug_assert_eq!(found, expected);
+
+                        let mut found: Vec<u32> =
+                            qt.query_strict(query).map(|e| *e.value_ref()).collect();
+                        found.sort_unstable();
+                        let expected: Vec<u32> = (0..13)
+                            .filter(|&i| query.contains(regions[i as usize]))
+                            .collect();
+                        debug_assert_eq!(found, expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn count_in_region_empty() {
+        let qt = Quadtree::<u32, u8>::new(2);
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+        debug_assert_eq!(qt.count_in_region(region), 0);
+        debug_assert_eq!(qt.count_in_region_strict(region), 0);
+    }
+
+    #[test]
+    fn count_in_region_matches_query() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend((0..16).map(|i| ((i, i), 1)));
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, 2).into())
+                .dimensions((5, 3))
+                .build()
+                .unwrap(),
+            2,
+        );
+
+        for (anchor, dimensions) in [
+            ((0, 0), (1, 1)),
+            ((1, 1), (3, 3)),
+            ((3, 3), (3, 3)),
+            ((2, 2), (5, 3)),
+            ((8, 0), (8, 8)),
+            ((10, 12), (4, 4)),
+        ] {
+            let region = AreaBuilder::default()
+                .anchor(anchor.into())
+                .dimensions(dimensions)
+                .build()
+                .unwrap();
+            debug_assert_eq!(qt.count_in_region(region), qt.query(region).count());
+            debug_assert_eq!(
+                qt.count_in_region_strict(region),
+                qt.query_strict(region).count()
+            );
+        }
+
+        // The region straddling (3, 3)->3x3 overlaps it, but isn't contained.
+        let region = AreaBuilder::default()
+            .anchor((3, 3).into())
+            .dimensions((3, 3))
+            .build()
+            .unwrap();
+        debug_assert_eq!(qt.count_in_region(region), 4);
+        debug_assert_eq!(qt.count_in_region_strict(region), 3);
+    }
+
+    #[test]
+    fn count_in_region_whole_tree() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend((0..16).map(|i| ((i, 15 - i), 1)));
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((16, 16))
+                .build()
+                .unwrap(),
+            2,
+        );
+
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((16, 16))
+            .build()
+            .unwrap();
+        debug_assert_eq!(qt.count_in_region(region), qt.len());
+        debug_assert_eq!(qt.count_in_region_strict(region), qt.len());
+    }
+
+    #[test]
+    fn any_in_region_agrees_with_query() {
+        let qt = mk_grid();
+        for x in -1..5 {
+            for y in -1..5 {
+                for &(w, h) in [(1, 1), (2, 1), (1, 3), (3, 3)].iter() {
+                    let region = AreaBuilder::default()
+                        .anchor((x, y).into())
+                        .dimensions((w, h))
+                        .build()
+                        .unwrap();
+                    match qt.any_in_region(region) {
+                        Some(entry) => {
+                            debug_assert!(qt.query(region).any(|e| e.handle() == entry.handle()))
+                        }
+                        None => debug_assert_eq!(qt.query(region).count(), 0),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn any_in_region_skips_near_misses() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        assert!(qt.any_in_region(qt.root_region()).is_none());
+
+        // A region held by the root's nodes, which the probe's nodes overlap but which the probe
+        // itself doesn't.
+        let handle = qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((0, 0).into())
+                    .dimensions((9, 9))
+                    .build()
+                    .unwrap(),
+                1,
+            )
+            .unwrap();
+        let probe = AreaBuilder::default()
+            .anchor((9, 9).into())
+            .dimensions((2, 2))
+            .build()
+            .unwrap();
+        debug_assert!(qt.any_in_region(probe).is_none());
+
+        let inside = AreaBuilder::default()
+            .anchor((8, 8).into())
+            .dimensions((2, 2))
+            .build()
+            .unwrap();
+        debug_assert_eq!(qt.any_in_region(inside).map(|e| e.handle()), Some(handle));
+
+        qt.delete_by_handle(handle);
+        debug_assert!(qt.any_in_region(inside).is_none());
+    }
+
+    #[test]
+    fn contains_point_empty() {
+        let qt = Quadtree::<u32, u8>::new(2);
+        debug_assert!(!qt.contains_point((0, 0).into()));
+        debug_assert!(!qt.contains_point((3, 3).into()));
+    }
+
+    #[test]
+    fn contains_point_out_of_bounds() {
+        let mut qt = Quadtree::<i32, u8>::new_with_anchor((-2, -2).into(), 2);
+        assert!(qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((-2, -2).into())
+                    .dimensions((4, 4))
+                    .build()
+                    .unwrap(),
+                1,
+            )
+            .is_some());
+        debug_assert!(qt.contains_point((-2, -2).into()));
+        debug_assert!(qt.contains_point((1, 1).into()));
+        debug_assert!(!qt.contains_point((2, 1).into()));
+        debug_assert!(!qt.contains_point((-3, 0).into()));
+        debug_assert!(!qt.contains_point((i32::MIN, i32::MAX).into()));
+    }
+
+    #[test]
+    fn contains_point_in_regions() {
+        //   01234567
+        // 0 a.......
+        // 1 ..bbb...
+        // 2 ..bbb...
+        // 3 ....cccc
+        let mut qt = Quadtree::<u32, char>::new(3);
+        assert!(qt.insert_pt((0, 0).into(), 'a').is_some());
+        assert!(qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((2, 1).into())
+                    .dimensions((3, 2))
+                    .build()
+                    .unwrap(),
+                'b',
+            )
+            .is_some());
+        assert!(qt
+            .insert(
+                AreaBuilder::default()
+                    .anchor((4, 3).into())
+                    .dimensions((4, 1))
+                    .build()
+                    .unwrap(),
+                'c',
+            )
+            .is_some());
+
+        for x in 0..8 {
+            for y in 0..8 {
+                debug_assert_eq!(
+                    qt.contains_point((x, y).into()),
+                    qt.query(
+                        AreaBuilder::default()
+                            .anchor((x, y).into())
+                            .build()
+                            .unwrap()
+                    )
+                    .next()
+                    .is_some(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn contains_point_after_delete() {
+        let mut qt = Quadtree::<u32, u8>::new(3);
+        let handle = qt.insert_pt((5, 2).into(), 1).unwrap();
+        debug_assert!(qt.contains_point((5, 2).into()));
+        qt.delete_by_handle(handle);
+        debug_assert!(!qt.contains_point((5, 2).into()));
+    }
+
+    #[test]
+    fn region_fully_covered_empty() {
+        let qt = Quadtree::<u32, u8>::new(4);
+        let whole = qt.root_region();
+        debug_assert!(!qt.region_fully_covered(whole));
+    }
+
+    #[test]
+    fn region_fully_covered_out_of_bounds() {
+        let mut qt = Quadtree::<u32, u8>::new(2);
+        qt.insert(qt.root_region(), 1);
+        debug_assert!(qt.region_fully_covered(qt.root_region()));
+        let past_the_edge = AreaBuilder::default()
+            .anchor((2, 2).into())
+            .dimensions((3, 1))
+            .build()
+            .unwrap();
+        debug_assert!(!qt.region_fully_covered(past_the_edge));
+    }
+
+    #[test]
+    fn region_fully_covered_matches_every_point() {
+        let area = |x, y, w, h| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .dimensions((w, h))
+                .build()
+                .unwrap()
+        };
+        for &capacity in &[0, 1, 3] {
+            let mut qt = Quadtree::<u32, u8>::with_capacity((0, 0).into(), 3, capacity);
+            qt.insert(area(0, 0, 4, 4), 0);
+            qt.insert(area(3, 1, 5, 2), 1);
+            qt.insert(area(5, 0, 3, 8), 2);
+            qt.insert(area(0, 5, 6, 3), 3);
+            qt.insert_pt((4, 4).into(), 4);
+            // Leaves (0..4, 4) and (4, 3) uncovered.
+            for x in 0..8 {
+                for y in 0..8 {
+                    for w in 1..=(8 - x) {
+                        for h in 1..=(8 - y) {
+                            let query = area(x, y, w, h);
+                            let every_point = (x..x + w)
+                                .all(|px| (y..y + h).all(|py| qt.contains_point((px, py).into())));
+                            debug_assert_eq!(qt.region_fully_covered(query), every_point);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn get_at_point_empty() {
+        let qt = Quadtree::<u32, u8>::new(2);
+        debug_assert_eq!(qt.get_at_point((1, 1).into()).next(), None);
+    }
+
+    #[test]
+    fn get_at_point_out_of_bounds() {
+        let mut qt = Quadtree::<i32, u8>::new_with_anchor((-4, -4).into(), 3);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-4, -4).into())
+                .dimensions((8, 8))
+                .build()
+                .unwrap(),
+            1,
+        );
+        debug_assert_eq!(qt.get_at_point((-5, 0).into()).count(), 0);
+        debug_assert_eq!(qt.get_at_point((4, 0).into()).count(), 0);
+        debug_assert_eq!(qt.get_at_point((0, 0).into()).count(), 1);
+    }
+
+    #[test]
+    fn get_at_point_stacked_regions() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        for (c, anchor, dimensions) in [
+            // Held at the root.
+            ('a', (0, 0), (16, 16)),
+            // Held by one quadrant.
+            ('b', (0, 0), (8, 8)),
+            // Split over many nodes, some high up and some at the bottom.
+            ('c', (1, 1), (6, 5)),
+            ('d', (3, 3), (1, 1)),
+            ('e', (3, 3), (1, 1)),
+            ('f', (4, 4), (2, 2)),
+        ] {
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor(anchor.into())
+                    .dimensions(dimensions)
+                    .build()
+                    .unwrap(),
+                c,
+            );
+        }
+
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((3, 3).into()).map(|e| *e.value_ref()),
+            vec!['a', 'b', 'c', 'd', 'e']
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((5, 5).into()).map(|e| *e.value_ref()),
+            vec!['a', 'b', 'c', 'f']
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((0, 7).into()).map(|e| *e.value_ref()),
+            vec!['a', 'b']
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((12, 3).into()).map(|e| *e.value_ref()),
+            vec!['a']
+        ));
+
+        // Agrees with checking every entry, and never repeats one.
+        for x in 0..16 {
+            for y in 0..16 {
+                let expected: Vec<u64> = qt
+                    .iter()
+                    .filter(|e| e.area().contains_pt((x, y).into()))
+                    .map(|e| e.handle())
+                    .collect();
+                let found: Vec<u64> = qt.get_at_point((x, y).into()).map(|e| e.handle()).collect();
+                debug_assert_eq!(found.len(), expected.len());
+                debug_assert!(unordered_elements_are(found, expected));
+            }
+        }
+    }
+
+    #[test]
+    fn get_at_point_after_delete() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        let region = AreaBuilder::default()
+            .anchor((1, 1).into())
+            .dimensions((6, 5))
+            .build()
+            .unwrap();
+        let handle = qt.insert(region, 'a').unwrap();
+        qt.insert(region, 'b');
+
+        qt.delete_by_handle(handle);
+        debug_assert!(unordered_elements_are(
+            qt.get_at_point((3, 3).into()).map(|e| *e.value_ref()),
+            vec!['b']
+        ));
+    }
+
     #[test]
     fn modify_empty() {
         // Modification shouldn't change the emptiness.
@@ -754,4 +1272,938 @@ mod query_tests {
             debug_assert_eq!(entry.value_ref(), &0);
         }
     }
+
+    #[test]
+    fn modify_leaves_the_rest_alone() {
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        qt.extend((0..16).flat_map(|x| (0..16).map(move |y| ((x, y), 100))));
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, 2).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+            100,
+        );
+        let blast = AreaBuilder::default()
+            .anchor((4, 4).into())
+            .dimensions((3, 2))
+            .build()
+            .unwrap();
+
+        let mut hits = 0;
+        qt.modify(blast, |v| {
+            hits += 1;
+            *v -= 10;
+        });
+        // Six points, and the region straddling the edge of the blast, each once.
+        debug_assert_eq!(hits, 7);
+
+        for entry in qt.iter() {
+            let expected = if entry.area().intersects(blast) {
+                90
+            } else {
+                100
+            };
+            debug_assert_eq!(entry.value_ref(), &expected);
+        }
+
+        // The strict variant skips the straddling region.
+        hits = 0;
+        qt.modify_strict(blast, |v| {
+            hits += 1;
+            *v -= 10;
+        });
+        debug_assert_eq!(hits, 6);
+        debug_assert_eq!(qt.values().filter(|&&v| v == 80).count(), 6);
+        debug_assert_eq!(qt.values().filter(|&&v| v == 90).count(), 1);
+    }
+
+    // 'a'..'p' at every point of a 4x4 grid, row by row.
+    fn mk_grid() -> Quadtree<i8, char> {
+        let mut qt = Quadtree::<i8, char>::new(3);
+        qt.extend((0..16_u8).map(|i| (((i % 4) as i8, (i / 4) as i8), (b'a' + i) as char)));
+        qt
+    }
+
+    #[test]
+    fn query_polygon_convex() {
+        let qt = mk_grid();
+        //   0123
+        // 0 abcd
+        // 1 efgh
+        // 2 ijkl
+        // 3 mnop
+        // The triangle through the corners (0,0), (4,0) and (0,4) covers half of the grid cells on
+        // the diagonal, and touches the cells just past it at their corners.
+        debug_assert!(unordered_elements_are(
+            qt.query_polygon(&[(0, 0), (4, 0), (0, 4)])
+                .map(|e| *e.value_ref()),
+            "abcdefgijm".chars()
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.query_polygon_strict(&[(0, 0), (4, 0), (0, 4)])
+                .map(|e| *e.value_ref()),
+            "abcefi".chars()
+        ));
+    }
+
+    #[test]
+    fn query_polygon_winding_does_not_matter() {
+        let qt = mk_grid();
+        let mut clockwise: Vec<char> = qt
+            .query_polygon(&[(1, 1), (3, 1), (3, 3), (1, 3)])
+            .map(|e| *e.value_ref())
+            .collect();
+        let mut counterclockwise: Vec<char> = qt
+            .query_polygon(&[(1, 3), (3, 3), (3, 1), (1, 1)])
+            .map(|e| *e.value_ref())
+            .collect();
+        clockwise.sort_unstable();
+        counterclockwise.sort_unstable();
+        debug_assert_eq!(clockwise, vec!['f', 'g', 'j', 'k']);
+        debug_assert_eq!(clockwise, counterclockwise);
+    }
+
+    #[test]
+    fn query_polygon_concave() {
+        let qt = mk_grid();
+        // An L along the left and bottom edges, whose bounding box is the whole grid.
+        let ell = [(0, 0), (1, 0), (1, 3), (4, 3), (4, 4), (0, 4)];
+        debug_assert!(unordered_elements_are(
+            qt.query_polygon(&ell).map(|e| *e.value_ref()),
+            "aeimnop".chars()
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.query_polygon_strict(&ell).map(|e| *e.value_ref()),
+            "aeimnop".chars()
+        ));
+    }
+
+    #[test]
+    fn query_polygon_straddling_regions() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((8, 2))
+                .build()
+                .unwrap(),
+            'a',
+        );
+        // The diamond pokes into the bottom of 'a', but doesn't contain it.
+        let diamond = [(4, 1), (7, 4), (4, 7), (1, 4)];
+        debug_assert_eq!(qt.query_polygon(&diamond).count(), 1);
+        debug_assert_eq!(qt.query_polygon_strict(&diamond).count(), 0);
+
+        // Lowered by one, it only touches 'a' at a corner.
+        let lowered = [(4, 2), (7, 5), (4, 8), (1, 5)];
+        debug_assert_eq!(qt.query_polygon(&lowered).count(), 0);
+    }
+
+    #[test]
+    fn query_polygon_degenerate() {
+        let qt = mk_grid();
+        debug_assert_eq!(qt.query_polygon(&[]).count(), 0);
+        debug_assert_eq!(qt.query_polygon(&[(1, 1)]).count(), 0);
+        debug_assert_eq!(qt.query_polygon(&[(0, 0), (4, 4)]).count(), 0);
+        // Three collinear vertices enclose nothing.
+        debug_assert_eq!(qt.query_polygon(&[(0, 0), (2, 2), (4, 4)]).count(), 0);
+        debug_assert_eq!(qt.query_polygon(&[(0, 1), (2, 1), (4, 1)]).count(), 0);
+    }
+
+    #[test]
+    fn query_polygon_outside_the_tree() {
+        let qt = mk_grid();
+        debug_assert_eq!(
+            qt.query_polygon(&[(-100, -100), (-50, -100), (-50, -50)])
+                .count(),
+            0
+        );
+        // A polygon far larger than the tree, whose bounding box couldn't be built as an Area.
+        debug_assert_eq!(
+            qt.query_polygon(&[(-128, -128), (127, -128), (127, 127), (-128, 127)])
+                .count(),
+            16
+        );
+    }
+
+    #[test]
+    fn query_filter_matches_filtered_query() {
+        let qt = mk_grid();
+        let region = AreaBuilder::default()
+            .anchor((1, 1).into())
+            .dimensions((2, 3))
+            .build()
+            .unwrap();
+        let vowel = |c: &char| "aeiou".contains(*c);
+        debug_assert!(unordered_elements_are(
+            qt.query_filter(region, vowel).map(|e| *e.value_ref()),
+            qt.query(region)
+                .map(|e| *e.value_ref())
+                .filter(|c| vowel(c))
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.query_filter(region, |&c| c > 'j')
+                .map(|e| *e.value_ref()),
+            "kno".chars()
+        ));
+    }
+
+    #[test]
+    fn query_filter_strict() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend(vec![((1, 1), 1), ((2, 2), 2), ((3, 3), 3)]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, 2).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+            4,
+        );
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+
+        debug_assert!(unordered_elements_are(
+            qt.query_filter(region, |&v| v > 1).map(|e| *e.value_ref()),
+            vec![2, 3, 4]
+        ));
+        // The straddling region is dropped, whatever its value.
+        debug_assert!(unordered_elements_are(
+            qt.query_filter_strict(region, |&v| v > 1)
+                .map(|e| *e.value_ref()),
+            vec![2, 3]
+        ));
+    }
+
+    #[test]
+    fn query_sorted_by() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.extend(vec![((1, 1), 3), ((2, 2), 1), ((3, 3), 2), ((9, 9), 0)]);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, 2).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+            1,
+        );
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+
+        let sorted = qt.query_sorted_by(region, |a, b| a.value_ref().cmp(b.value_ref()));
+        debug_assert_eq!(
+            sorted.iter().map(|e| *e.value_ref()).collect::<Vec<_>>(),
+            vec![1, 1, 2, 3]
+        );
+        // Ties come out in handle order.
+        debug_assert!(sorted[0].handle() < sorted[1].handle());
+        debug_assert_eq!(sorted[1].width(), 4);
+
+        let strict = qt.query_sorted_by_strict(region, |a, b| b.value_ref().cmp(a.value_ref()));
+        debug_assert_eq!(
+            strict.iter().map(|e| *e.value_ref()).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+
+        let nowhere = AreaBuilder::default()
+            .anchor((12, 0).into())
+            .build()
+            .unwrap();
+        assert!(qt
+            .query_sorted_by(nowhere, |a, b| a.value_ref().cmp(b.value_ref()))
+            .is_empty());
+    }
+
+    #[test]
+    fn query_filter_only_tests_entries_in_the_region() {
+        use std::cell::Cell;
+
+        let qt = mk_grid();
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((2, 2))
+            .build()
+            .unwrap();
+        let calls = Cell::new(0);
+        let found = qt
+            .query_filter(region, |_| {
+                calls.set(calls.get() + 1);
+                false
+            })
+            .count();
+        debug_assert_eq!(found, 0);
+        // Only the four entries under the region were ever tested.
+        debug_assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn query_clipped_matches_query() {
+        let qt = mk_grid();
+        let region = AreaBuilder::default()
+            .anchor((1, 1).into())
+            .dimensions((2, 3))
+            .build()
+            .unwrap();
+        debug_assert!(unordered_elements_are(
+            qt.query_clipped(region).map(|(e, _)| *e.value_ref()),
+            qt.query(region).map(|e| *e.value_ref())
+        ));
+        // Points are either wholly inside or wholly outside.
+        for (entry, clipped) in qt.query_clipped(region) {
+            debug_assert_eq!(clipped, entry.area());
+        }
+    }
+
+    #[test]
+    fn query_clipped_straddling_regions() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.insert_pt((1, 1).into(), 1);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((2, 3).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+            2,
+        );
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((0, 0).into())
+                .dimensions((16, 16))
+                .build()
+                .unwrap(),
+            3,
+        );
+        let region = AreaBuilder::default()
+            .anchor((0, 0).into())
+            .dimensions((4, 4))
+            .build()
+            .unwrap();
+
+        let mut found: Vec<_> = qt
+            .query_clipped(region)
+            .map(|(e, clipped)| (*e.value_ref(), clipped.into()))
+            .collect();
+        found.sort();
+        debug_assert_eq!(
+            found,
+            vec![
+                (1, ((1, 1), (1, 1))),
+                (2, ((2, 3), (2, 1))),
+                (3, ((0, 0), (4, 4))),
+            ]
+        );
+
+        // Only the point lies wholly within the region.
+        let strict: Vec<_> = qt
+            .query_clipped_strict(region)
+            .map(|(e, clipped)| (*e.value_ref(), clipped))
+            .collect();
+        debug_assert_eq!(strict.len(), 1);
+        debug_assert_eq!(strict[0].0, 1);
+        debug_assert_eq!(strict[0].1.anchor(), (1, 1).into());
+    }
+
+    #[test]
+    fn query_touching_a_point() {
+        let qt = mk_grid();
+        //   0123
+        // 0 abcd
+        // 1 efgh
+        // 2 ijkl
+        // 3 mnop
+        // The point (x, y) is the top-left corner of the cell at (x, y).
+        debug_assert!(unordered_elements_are(
+            qt.query_touching((2, 2).into(), (0, 0))
+                .map(|e| *e.value_ref()),
+            "fgjk".chars()
+        ));
+        // On the corner of the tree, there's only one cell to touch.
+        debug_assert!(unordered_elements_are(
+            qt.query_touching((0, 0).into(), (0, 0))
+                .map(|e| *e.value_ref()),
+            "a".chars()
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.query_touching((4, 4).into(), (0, 0))
+                .map(|e| *e.value_ref()),
+            "p".chars()
+        ));
+        debug_assert_eq!(qt.query_touching((5, 5).into(), (0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn query_touching_a_line() {
+        let qt = mk_grid();
+        // A vertical line from (1, 1) to (1, 3), and a horizontal one from (0, 4) to (2, 4).
+        debug_assert!(unordered_elements_are(
+            qt.query_touching((1, 1).into(), (0, 2))
+                .map(|e| *e.value_ref()),
+            "abefijmn".chars()
+        ));
+        debug_assert!(unordered_elements_are(
+            qt.query_touching((0, 4).into(), (2, 0))
+                .map(|e| *e.value_ref()),
+            "mno".chars()
+        ));
+    }
+
+    #[test]
+    fn query_touching_a_region() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((4, 4).into())
+                .dimensions((4, 4))
+                .build()
+                .unwrap(),
+            1,
+        );
+        // The region's right edge is at x = 8.
+        debug_assert_eq!(qt.query_touching((8, 0).into(), (3, 3)).count(), 0);
+        debug_assert_eq!(qt.query_touching((8, 0).into(), (3, 4)).count(), 1);
+        // Inside the region.
+        debug_assert_eq!(qt.query_touching((5, 5).into(), (0, 0)).count(), 1);
+    }
+
+    #[test]
+    fn query_touching_malformed() {
+        let qt = mk_grid();
+        debug_assert_eq!(qt.query_touching((2, 2).into(), (-1, 0)).count(), 0);
+        debug_assert_eq!(qt.query_touching((2, 2).into(), (2, -3)).count(), 0);
+        // Near the bounds of the coordinate type. The first stops at (-1, -1), short of the tree.
+        debug_assert_eq!(
+            qt.query_touching((i8::MIN, i8::MIN).into(), (i8::MAX, i8::MAX))
+                .count(),
+            0
+        );
+        debug_assert_eq!(
+            qt.query_touching((-1, -1).into(), (i8::MAX, i8::MAX))
+                .count(),
+            16
+        );
+        debug_assert_eq!(
+            qt.query_touching((i8::MAX, i8::MAX).into(), (i8::MAX, 0))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn adjacent_to() {
+        let qt = mk_grid();
+        //   0123
+        // 0 abcd
+        // 1 efgh
+        // 2 ijkl
+        // 3 mnop
+        let region = |x, y, w, h| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .dimensions((w, h))
+                .build()
+                .unwrap()
+        };
+        // Not the corners a, d, m or p, nor f, g, j and k themselves.
+        debug_assert!(unordered_elements_are(
+            qt.adjacent_to(region(1, 1, 2, 2)).map(|e| *e.value_ref()),
+            "bcehilno".chars()
+        ));
+        // Against the edge of the tree, there's nothing on the far side.
+        debug_assert!(unordered_elements_are(
+            qt.adjacent_to(region(0, 0, 1, 4)).map(|e| *e.value_ref()),
+            "bfjn".chars()
+        ));
+        // Outside the tree, sharing its right edge.
+        debug_assert!(unordered_elements_are(
+            qt.adjacent_to(region(4, 3, 2, 2)).map(|e| *e.value_ref()),
+            "p".chars()
+        ));
+        debug_assert_eq!(qt.adjacent_to(region(5, 0, 2, 2)).count(), 0);
+    }
+
+    #[test]
+    fn adjacent_to_large_regions() {
+        let mut qt = Quadtree::<u32, char>::new(4);
+        let region = |x, y, w, h| {
+            AreaBuilder::default()
+                .anchor((x, y).into())
+                .dimensions((w, h))
+                .build()
+                .unwrap()
+        };
+        qt.insert(region(0, 0, 8, 8), 'a');
+        qt.insert(region(8, 4, 8, 2), 'b');
+        qt.insert(region(2, 8, 1, 8), 'c');
+        qt.insert(region(8, 8, 8, 8), 'd');
+        // 'a' shares part of its right edge with 'b' and its bottom edge with 'c', and only a
+        // corner with 'd'.
+        debug_assert!(unordered_elements_are(
+            qt.adjacent_to(region(0, 0, 8, 8)).map(|e| *e.value_ref()),
+            "bc".chars()
+        ));
+        // Straddling 'b' and 'd' overlaps both, leaving 'a' along its left edge.
+        debug_assert!(unordered_elements_are(
+            qt.adjacent_to(region(8, 5, 2, 4)).map(|e| *e.value_ref()),
+            "a".chars()
+        ));
+    }
+}
+
+// For testing .overlapping_pairs().
+mod overlapping_pairs {
+    use {
+        crate::util::scatter,
+        quadtree_rs::{area::AreaBuilder, Quadtree},
+    };
+
+    fn insert(qt: &mut Quadtree<u32, u32>, anchor: (u32, u32), dims: (u32, u32), val: u32) {
+        qt.insert(
+            AreaBuilder::default()
+                .anchor(anchor.into())
+                .dimensions(dims)
+                .build()
+                .unwrap(),
+            val,
+        )
+        .unwrap();
+    }
+
+    // The pairs of values, each smaller first, in sorted order.
+    fn pairs(qt: &Quadtree<u32, u32>) -> Vec<(u32, u32)> {
+        let mut pairs: Vec<(u32, u32)> = qt
+            .overlapping_pairs()
+            .map(|(a, b)| {
+                let (a, b) = (*a.value_ref(), *b.value_ref());
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u32, u32>::new(4);
+        debug_assert_eq!(qt.overlapping_pairs().count(), 0);
+    }
+
+    #[test]
+    fn hand_placed() {
+        //   0123456789abcdef
+        // 0 11..............
+        // 1 11..............
+        // 2 ..2222..........
+        // 3 ..2$$3.......55.
+        // 4 ..2$$3.......5&6
+        // 5 ...333.......566
+        // 6 ..............66
+        // 7 4444............
+        // 8 4444............
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        insert(&mut qt, (0, 0), (2, 2), 1);
+        insert(&mut qt, (2, 2), (4, 3), 2);
+        insert(&mut qt, (3, 3), (3, 3), 3);
+        insert(&mut qt, (0, 7), (4, 2), 4);
+        insert(&mut qt, (13, 3), (2, 3), 5);
+        insert(&mut qt, (14, 4), (2, 3), 6);
+        // 1 and 2 touch at a corner, and 4 sits just below 2, but neither pair overlaps.
+        debug_assert_eq!(pairs(&qt), vec![(2, 3), (5, 6)]);
+    }
+
+    #[test]
+    fn straddling_regions_are_paired_once() {
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        // Both straddle the middle of the tree, so each is kept by several nodes in common.
+        insert(&mut qt, (4, 4), (8, 8), 1);
+        insert(&mut qt, (6, 6), (4, 4), 2);
+        // Held at the root, and overlapping everything.
+        insert(&mut qt, (0, 0), (16, 16), 3);
+        insert(&mut qt, (0, 0), (1, 1), 4);
+        debug_assert_eq!(pairs(&qt), vec![(1, 2), (1, 3), (2, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn identical_regions() {
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        insert(&mut qt, (5, 5), (3, 3), 1);
+        insert(&mut qt, (5, 5), (3, 3), 2);
+        insert(&mut qt, (5, 5), (3, 3), 3);
+        debug_assert_eq!(pairs(&qt), vec![(1, 2), (1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn skips_deleted_entries() {
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        insert(&mut qt, (0, 0), (4, 4), 1);
+        insert(&mut qt, (2, 2), (4, 4), 2);
+        insert(&mut qt, (3, 3), (4, 4), 3);
+        let handle = qt.iter().find(|e| *e.value_ref() == 2).unwrap().handle();
+        qt.delete_by_handle(handle);
+        debug_assert_eq!(pairs(&qt), vec![(1, 3)]);
+    }
+
+    #[test]
+    fn matches_exhaustive_search() {
+        let mut qt = Quadtree::<u32, u32>::new(6);
+        for (i, region) in scatter(150, 64, |i| (1 + (i * 7) % 9, 1 + (i * 5) % 6)) {
+            qt.insert(region, i);
+        }
+
+        let entries: Vec<_> = qt.iter().collect();
+        let mut expected = Vec::new();
+        for (i, a) in entries.iter().enumerate() {
+            for b in &entries[i + 1..] {
+                if a.area().intersects(b.area()) {
+                    let (a, b) = (*a.value_ref(), *b.value_ref());
+                    expected.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        expected.sort_unstable();
+        debug_assert!(!expected.is_empty());
+        debug_assert_eq!(pairs(&qt), expected);
+    }
+}
+
+// For testing .intersects().
+mod intersects {
+    use {
+        crate::util::scatter,
+        quadtree_rs::{
+            area::{Area, AreaBuilder},
+            Quadtree,
+        },
+    };
+
+    fn insert(qt: &mut Quadtree<i32, u32>, anchor: (i32, i32), dims: (i32, i32), val: u32) {
+        qt.insert(
+            AreaBuilder::default()
+                .anchor(anchor.into())
+                .dimensions(dims)
+                .build()
+                .unwrap(),
+            val,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn empty_trees() {
+        let mut a = Quadtree::<i32, u32>::new(4);
+        let b = Quadtree::<i32, u32>::new(4);
+        assert!(!a.intersects(&b));
+        insert(&mut a, (0, 0), (16, 16), 1);
+        assert!(!a.intersects(&b));
+        assert!(!b.intersects(&a));
+    }
+
+    #[test]
+    fn one_overlapping_pair() {
+        let mut a = Quadtree::<i32, u32>::new(5);
+        let mut b = Quadtree::<i32, u32>::new_with_anchor((8, 8).into(), 4);
+        // Interleaved, but never quite meeting.
+        for i in 0..8 {
+            insert(&mut a, (8 + 2 * i, 8), (1, 16), i as u32);
+            insert(&mut b, (9 + 2 * i, 8), (1, 16), i as u32);
+        }
+        assert!(!a.intersects(&b));
+        assert!(!b.intersects(&a));
+
+        // Bridging the gap between a 3 and b 3.
+        insert(&mut b, (14, 20), (2, 1), 100);
+        assert!(a.intersects(&b));
+        assert!(b.intersects(&a));
+    }
+
+    #[test]
+    fn touching_isnt_overlapping() {
+        let mut a = Quadtree::<i32, u32>::new(4);
+        let mut b = Quadtree::<i32, char>::new_with_anchor((16, 0).into(), 4);
+        insert(&mut a, (0, 0), (16, 16), 1);
+        b.insert_pt((16, 5).into(), 'x');
+        assert!(!a.intersects(&b));
+        b.insert_pt((15, 5).into(), 'y');
+        // Out of bounds for b, so not inserted.
+        assert!(!a.intersects(&b));
+    }
+
+    #[test]
+    fn matches_exhaustive_search() {
+        // Trees of different depths and node capacities, overlapping off-center.
+        let mut a = Quadtree::<i32, u32>::new_with_anchor((-16, -16).into(), 6);
+        let mut b = Quadtree::<i32, u32>::with_capacity((-24, -24).into(), 5, 3);
+        // scatter() covers a square at the origin, so each region is moved onto its tree.
+        let dims = |i: u32| (1 + (i * 7) % 5, 1 + (i * 5) % 4);
+        let moved = |region: Area<u32>, (dx, dy): (i32, i32)| {
+            let anchor = region.anchor();
+            (
+                (anchor.x() as i32 + dx, anchor.y() as i32 + dy),
+                (region.width() as i32, region.height() as i32),
+            )
+        };
+        for (i, region) in scatter(60, 64, dims) {
+            let (anchor, dims) = moved(region, (-16, -16));
+            insert(&mut a, anchor, dims, i);
+        }
+        let regions: Vec<Area<u32>> = scatter(47, 32, dims).skip(7).map(|(_, r)| r).collect();
+        let mut first_overlap = None;
+        for (round, &region) in regions.iter().enumerate() {
+            let (anchor, dims) = moved(region, (-24, -24));
+            insert(&mut b, anchor, dims, round as u32);
+
+            let expected = a
+                .iter()
+                .any(|e| b.iter().any(|f| e.area().intersects(f.area())));
+            debug_assert_eq!(a.intersects(&b), expected);
+            debug_assert_eq!(b.intersects(&a), expected);
+            if expected && first_overlap.is_none() {
+                first_overlap = Some(round);
+            }
+        }
+        // Both answers came up.
+        debug_assert!(first_overlap.unwrap() > 0);
+
+        // And one entry at a time, against the whole of a.
+        for &region in &regions {
+            let mut c = Quadtree::<i32, u32>::new_with_anchor((-24, -24).into(), 5);
+            let (anchor, dims) = moved(region, (-24, -24));
+            insert(&mut c, anchor, dims, 0);
+            let expected = a
+                .iter()
+                .any(|e| c.iter().any(|f| e.area().intersects(f.area())));
+            debug_assert_eq!(a.intersects(&c), expected);
+            debug_assert_eq!(c.intersects(&a), expected);
+        }
+    }
+}
+
+// For testing .query_halfplane().
+mod query_halfplane {
+    use {
+        crate::util::unordered_elements_are,
+        quadtree_rs::{
+            area::AreaBuilder,
+            halfplane::{Axis, Side},
+            Quadtree,
+        },
+    };
+
+    fn mk_quadtree() -> Quadtree<i8, char> {
+        //    -4  0  3
+        // -4 a.......
+        //    ...bbbb.
+        //    ...bbbb.
+        //    ........
+        //  0 .c..dd..
+        //    .c..dd..
+        //    .c......
+        //  3 ......ee
+        let mut qt = Quadtree::<i8, char>::new_with_anchor((-4, -4).into(), 3);
+        for (anchor, dims, value) in [
+            ((-4, -4), (1, 1), 'a'),
+            ((-1, -3), (4, 2), 'b'),
+            ((-3, 0), (1, 3), 'c'),
+            ((0, 0), (2, 2), 'd'),
+            ((2, 3), (2, 1), 'e'),
+        ] {
+            qt.insert(
+                AreaBuilder::default()
+                    .anchor(anchor.into())
+                    .dimensions(dims)
+                    .build()
+                    .unwrap(),
+                value,
+            );
+        }
+        qt
+    }
+
+    fn values(qt: &Quadtree<i8, char>, axis: Axis, threshold: i8, side: Side) -> String {
+        let mut values: Vec<char> = qt
+            .query_halfplane(axis, threshold, side)
+            .map(|e| *e.value_ref())
+            .collect();
+        values.sort_unstable();
+        values.into_iter().collect()
+    }
+
+    #[test]
+    fn straddling_regions_are_included() {
+        let qt = mk_quadtree();
+        debug_assert_eq!(values(&qt, Axis::X, 1, Side::GreaterOrEqual), "bde");
+        debug_assert_eq!(values(&qt, Axis::X, 1, Side::Less), "abcd");
+        debug_assert_eq!(values(&qt, Axis::Y, -2, Side::GreaterOrEqual), "bcde");
+        debug_assert_eq!(values(&qt, Axis::Y, -2, Side::Less), "ab");
+    }
+
+    #[test]
+    fn boundary_is_half_open() {
+        let qt = mk_quadtree();
+        // 'd' spans x = 0 and 1, so its right edge is 2.
+        debug_assert_eq!(values(&qt, Axis::X, 2, Side::GreaterOrEqual), "be");
+        debug_assert_eq!(values(&qt, Axis::X, 0, Side::Less), "abc");
+        debug_assert_eq!(values(&qt, Axis::X, 0, Side::GreaterOrEqual), "bde");
+    }
+
+    #[test]
+    fn beyond_the_tree() {
+        let qt = mk_quadtree();
+        debug_assert_eq!(values(&qt, Axis::X, 4, Side::GreaterOrEqual), "");
+        debug_assert_eq!(values(&qt, Axis::Y, -4, Side::Less), "");
+        debug_assert_eq!(values(&qt, Axis::Y, i8::MAX, Side::Less), "abcde");
+        debug_assert_eq!(values(&qt, Axis::X, i8::MIN, Side::GreaterOrEqual), "abcde");
+        debug_assert_eq!(values(&qt, Axis::X, i8::MIN, Side::Less), "");
+        debug_assert_eq!(
+            qt.query_halfplane(Axis::X, 100, Side::GreaterOrEqual)
+                .size_hint(),
+            (0, Some(0))
+        );
+    }
+
+    #[test]
+    fn matches_exhaustive_search() {
+        let qt = mk_quadtree();
+        for threshold in -6..6 {
+            for &axis in &[Axis::X, Axis::Y] {
+                for &side in &[Side::Less, Side::GreaterOrEqual] {
+                    let expected = qt.iter().filter(|e| {
+                        let (start, end) = match axis {
+                            Axis::X => (e.area().left_edge(), e.area().right_edge()),
+                            Axis::Y => (e.area().top_edge(), e.area().bottom_edge()),
+                        };
+                        match side {
+                            Side::Less => start < threshold,
+                            Side::GreaterOrEqual => end > threshold,
+                        }
+                    });
+                    debug_assert!(unordered_elements_are(
+                        qt.query_halfplane(axis, threshold, side)
+                            .map(|e| *e.value_ref()),
+                        expected.map(|e| *e.value_ref())
+                    ));
+                }
+            }
+        }
+    }
+}
+
+// For testing .try_visit() and .try_visit_strict().
+mod try_visit {
+    use {
+        crate::util::scatter,
+        quadtree_rs::{
+            area::{Area, AreaBuilder},
+            Quadtree,
+        },
+        std::ops::ControlFlow,
+    };
+
+    fn area(anchor: (u32, u32), dims: (u32, u32)) -> Area<u32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dims)
+            .build()
+            .unwrap()
+    }
+
+    fn mk_quadtree() -> Quadtree<u32, u32> {
+        let mut qt = Quadtree::<u32, u32>::new(6);
+        for (i, region) in scatter(100, 64, |i| (1 + (i * 7) % 9, 1 + (i * 5) % 6)) {
+            qt.insert(region, i);
+        }
+        qt
+    }
+
+    // Every value visited, in order of visiting.
+    fn visited(qt: &Quadtree<u32, u32>, region: Area<u32>, strict: bool) -> Vec<u32> {
+        let mut seen = Vec::new();
+        let visit = |e: &quadtree_rs::entry::Entry<u32, u32>| {
+            seen.push(*e.value_ref());
+            ControlFlow::<()>::Continue(())
+        };
+        let result = if strict {
+            qt.try_visit_strict(region, visit)
+        } else {
+            qt.try_visit(region, visit)
+        };
+        debug_assert_eq!(result, ControlFlow::Continue(()));
+        seen
+    }
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u32, u32>::new(4);
+        debug_assert!(visited(&qt, qt.root_region(), false).is_empty());
+    }
+
+    #[test]
+    fn visits_what_a_query_finds_once_each() {
+        let qt = mk_quadtree();
+        for &(anchor, dims) in &[((0, 0), (64, 64)), ((10, 20), (17, 9)), ((31, 31), (2, 2))] {
+            let region = area(anchor, dims);
+
+            let mut seen = visited(&qt, region, false);
+            seen.sort_unstable();
+            let mut expected: Vec<u32> = qt.query(region).map(|e| *e.value_ref()).collect();
+            expected.sort_unstable();
+            debug_assert_eq!(seen, expected);
+
+            let mut seen = visited(&qt, region, true);
+            seen.sort_unstable();
+            let mut expected: Vec<u32> = qt.query_strict(region).map(|e| *e.value_ref()).collect();
+            expected.sort_unstable();
+            debug_assert_eq!(seen, expected);
+        }
+    }
+
+    #[test]
+    fn straddling_regions_once() {
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        // Kept by many nodes.
+        qt.insert(area((1, 1), (14, 14)), 1);
+        qt.insert(area((7, 7), (2, 2)), 2);
+        debug_assert_eq!(visited(&qt, area((0, 0), (16, 16)), false).len(), 2);
+        debug_assert_eq!(visited(&qt, area((8, 8), (8, 8)), false).len(), 2);
+    }
+
+    #[test]
+    fn stops_at_the_first_break() {
+        let qt = mk_quadtree();
+        let mut calls = 0;
+        let found = qt.try_visit(qt.root_region(), |e| {
+            calls += 1;
+            if *e.value_ref() == 42 {
+                ControlFlow::Break(e.handle())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        let expected = qt.iter().find(|e| *e.value_ref() == 42).unwrap().handle();
+        debug_assert_eq!(found, ControlFlow::Break(expected));
+        // Not everything was visited, and nothing after the break.
+        debug_assert!(calls < qt.len());
+        let seen = visited(&qt, qt.root_region(), false);
+        debug_assert_eq!(seen.iter().position(|&v| v == 42), Some(calls - 1));
+    }
+
+    #[test]
+    fn break_on_the_first_entry() {
+        let qt = mk_quadtree();
+        let mut calls = 0;
+        let found = qt.try_visit(qt.root_region(), |_| {
+            calls += 1;
+            ControlFlow::Break("done")
+        });
+        debug_assert_eq!(found, ControlFlow::Break("done"));
+        debug_assert_eq!(calls, 1);
+    }
 }
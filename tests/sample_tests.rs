@@ -0,0 +1,100 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Only built with `--features rand`.
+#![cfg(feature = "rand")]
+
+// For testing .sample().
+mod sample_tests {
+    use {
+        quadtree_rs::Quadtree,
+        rand::{rngs::StdRng, SeedableRng},
+        std::collections::HashMap,
+    };
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u32, u8>::new(4);
+        let mut rng = StdRng::seed_from_u64(0);
+        debug_assert!(qt.sample(&mut rng).is_none());
+    }
+
+    #[test]
+    fn single_entry() {
+        let mut qt = Quadtree::<u32, u8>::new(4);
+        let handle = qt.insert_pt((3, 3).into(), 1).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10 {
+            debug_assert_eq!(qt.sample(&mut rng).unwrap().handle(), handle);
+        }
+    }
+
+    #[test]
+    fn reproducible_with_a_seed() {
+        let qt: Quadtree<u32, u32> = (0..64).map(|i| ((i % 8, i / 8), i)).collect();
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| qt.sample(&mut rng).unwrap().handle())
+                .collect::<Vec<u64>>()
+        };
+        debug_assert_eq!(draw(3), draw(3));
+    }
+
+    #[test]
+    fn skips_deleted_entries() {
+        let mut qt: Quadtree<u32, u32> = (0..16).map(|i| ((i % 4, i / 4), i)).collect();
+        qt.retain(|_, &v| v % 2 == 0);
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            debug_assert_eq!(qt.sample(&mut rng).unwrap().value_ref() % 2, 0);
+        }
+    }
+
+    #[test]
+    fn uniform() {
+        // Clustered points, plus one region covering most of the tree, shouldn't skew the draw.
+        let mut qt = Quadtree::<u32, u32>::new(4);
+        qt.extend((0..9).map(|i| ((i % 3, i / 3), i)));
+        qt.insert(
+            quadtree_rs::area::AreaBuilder::default()
+                .anchor((4, 4).into())
+                .dimensions((12, 12))
+                .build()
+                .unwrap(),
+            9,
+        );
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let draws = 20_000;
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for _ in 0..draws {
+            *counts
+                .entry(*qt.sample(&mut rng).unwrap().value_ref())
+                .or_default() += 1;
+        }
+
+        // Each of the ten entries should come up close to 2000 times; the standard deviation is
+        // about 42, so this only fails if something is wrong.
+        debug_assert_eq!(counts.len(), 10);
+        for (value, count) in counts {
+            debug_assert!(
+                (1800..2200).contains(&count),
+                "{} drawn {} times",
+                value,
+                count
+            );
+        }
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Only built with `--features serde`.
+#![cfg(feature = "serde")]
+
+// For testing that a Quadtree survives a round trip through serde.
+mod serde_tests {
+    use quadtree_rs::{area::AreaBuilder, eviction::EvictionPolicy, Quadtree};
+
+    fn round_trip(qt: &Quadtree<i32, String>) -> Quadtree<i32, String> {
+        serde_json::from_str(&serde_json::to_string(qt).unwrap()).unwrap()
+    }
+
+    fn mk_quadtree() -> Quadtree<i32, String> {
+        let mut qt = Quadtree::<i32, String>::new_with_anchor((-8, -8).into(), 4);
+        qt.extend((0..10).map(|i| ((i - 5, 2 * i - 8), format!("pt{}", i))));
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-3, -3).into())
+                .dimensions((6, 4))
+                .build()
+                .unwrap(),
+            "box".to_string(),
+        );
+        qt
+    }
+
+    // An entry's handle, anchor, dimensions and value.
+    type Row = (u64, (i32, i32), (i32, i32), String);
+
+    // Every entry, in handle order.
+    fn entries(qt: &Quadtree<i32, String>) -> Vec<Row> {
+        let mut entries: Vec<_> = qt
+            .iter()
+            .map(|e| {
+                let (anchor, dims) = e.area().into();
+                (e.handle(), anchor, dims, e.value_ref().clone())
+            })
+            .collect();
+        entries.sort_unstable();
+        entries
+    }
+
+    #[test]
+    fn entries_and_queries_match() {
+        let qt = mk_quadtree();
+        let copy = round_trip(&qt);
+        debug_assert_eq!(copy.len(), qt.len());
+        debug_assert_eq!(copy.root_region(), qt.root_region());
+        debug_assert_eq!(entries(&copy), entries(&qt));
+
+        let region = AreaBuilder::default()
+            .anchor((-4, -4).into())
+            .dimensions((5, 7))
+            .build()
+            .unwrap();
+        let found = |qt: &Quadtree<i32, String>| {
+            let mut found: Vec<u64> = qt.query(region).map(|e| e.handle()).collect();
+            found.sort_unstable();
+            found
+        };
+        debug_assert!(!found(&qt).is_empty());
+        debug_assert_eq!(found(&copy), found(&qt));
+    }
+
+    #[test]
+    fn handles_stay_valid() {
+        let mut qt = mk_quadtree();
+        let handles: Vec<u64> = qt.iter().map(|e| e.handle()).collect();
+        // A gap in the handles, which a fresh insert mustn't fill.
+        let deleted = handles[3];
+        qt.delete_by_handle(deleted);
+
+        let mut copy = round_trip(&qt);
+        for &handle in &handles {
+            debug_assert_eq!(
+                copy.get(handle).map(|e| e.value_ref()),
+                qt.get(handle).map(|e| e.value_ref())
+            );
+        }
+        debug_assert!(copy.get(deleted).is_none());
+
+        let fresh = copy.insert_pt((0, 0).into(), "new".to_string()).unwrap();
+        debug_assert!(handles.iter().all(|&h| h < fresh));
+        debug_assert_eq!(
+            fresh,
+            qt.insert_pt((0, 0).into(), "new".to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn keeps_growable_and_max_entries() {
+        let mut growable = Quadtree::<i32, String>::new_growable((0, 0).into(), 2);
+        growable.insert_pt((1, 1).into(), "a".to_string());
+        let mut growable = round_trip(&growable);
+        debug_assert!(growable
+            .insert_pt((100, 100).into(), "b".to_string())
+            .is_some());
+        debug_assert!(growable.depth() > 2);
+
+        let mut capped =
+            Quadtree::<i32, String>::with_max_entries((0, 0).into(), 4, 2, EvictionPolicy::Oldest);
+        let oldest = capped.insert_pt((1, 1).into(), "a".to_string()).unwrap();
+        capped.insert_pt((2, 2).into(), "b".to_string());
+        let mut capped = round_trip(&capped);
+        capped.insert_pt((3, 3).into(), "c".to_string());
+        debug_assert_eq!(capped.len(), 2);
+        debug_assert!(capped.get(oldest).is_none());
+    }
+
+    #[test]
+    fn growable_and_max_entries_default_when_missing() {
+        let mut capped =
+            Quadtree::<i32, String>::with_max_entries((0, 0).into(), 4, 1, EvictionPolicy::Oldest);
+        capped.insert_pt((1, 1).into(), "a".to_string());
+        let mut json: serde_json::Value = serde_json::to_value(&capped).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        assert!(fields.remove("growable").is_some());
+        assert!(fields.remove("max_entries").is_some());
+
+        // As written before either setting existed: fixed-size and uncapped.
+        let mut qt: Quadtree<i32, String> = serde_json::from_value(json).unwrap();
+        debug_assert!(qt.insert_pt((2, 2).into(), "b".to_string()).is_some());
+        debug_assert_eq!(qt.len(), 2);
+        debug_assert!(qt.insert_pt((100, 0).into(), "c".to_string()).is_none());
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// For testing .into_shared() and SharedQuadtree.
+mod shared_tests {
+    use {
+        quadtree_rs::{area::AreaBuilder, shared::SharedQuadtree, Quadtree},
+        std::thread,
+    };
+
+    fn is_send_and_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn send_and_sync() {
+        is_send_and_sync::<SharedQuadtree<u32, String>>();
+    }
+
+    #[test]
+    fn one_writer_many_readers() {
+        let shared = Quadtree::<u32, u32>::new(6).into_shared();
+        let writer = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                for i in 0..64 {
+                    shared.insert(
+                        AreaBuilder::default()
+                            .anchor((i, i).into())
+                            .build()
+                            .unwrap(),
+                        i,
+                    );
+                }
+            })
+        };
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        // Whatever a reader sees is some whole prefix of the writes.
+                        shared.read(|qt| {
+                            let len = qt.len() as u32;
+                            let query = AreaBuilder::default()
+                                .anchor((0, 0).into())
+                                .dimensions((64, 64))
+                                .build()
+                                .unwrap();
+                            debug_assert_eq!(qt.query(query).count() as u32, len);
+                            debug_assert!(qt.values().all(|&v| v < len));
+                        });
+                    }
+                })
+            })
+            .collect();
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        debug_assert_eq!(shared.len(), 64);
+    }
+
+    #[test]
+    fn write_and_delete() {
+        let shared = Quadtree::<u32, char>::new(4).into_shared();
+        debug_assert!(shared.is_empty());
+        let handle = shared
+            .insert(
+                AreaBuilder::default()
+                    .anchor((3, 3).into())
+                    .dimensions((2, 2))
+                    .build()
+                    .unwrap(),
+                'a',
+            )
+            .unwrap();
+        shared.write(|qt| *qt.get_mut(handle).unwrap().value_mut() = 'b');
+        debug_assert_eq!(shared.read(|qt| *qt.get(handle).unwrap().value_ref()), 'b');
+        debug_assert_eq!(
+            shared.delete_by_handle(handle).map(|e| *e.value_ref()),
+            Some('b')
+        );
+        debug_assert!(shared.is_empty());
+    }
+
+    #[test]
+    fn into_inner() {
+        let shared = Quadtree::<u32, u8>::new(4).into_shared();
+        shared.write(|qt| qt.insert_pt((1, 2).into(), 7));
+        let other = shared.clone();
+
+        // Still shared with `other`.
+        let shared = shared.into_inner().unwrap_err();
+        drop(other);
+        let qt = shared.into_inner().unwrap();
+        debug_assert_eq!(qt.len(), 1);
+        debug_assert!(qt.contains_point((1, 2).into()));
+    }
+}
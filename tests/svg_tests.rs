@@ -0,0 +1,90 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Only built with `--features debug-viz`.
+#![cfg(feature = "debug-viz")]
+
+// For testing .to_svg().
+mod svg_tests {
+    use quadtree_rs::{area::AreaBuilder, Quadtree};
+
+    fn mk_quadtree() -> Quadtree<i32, char> {
+        let mut qt = Quadtree::<i32, char>::new_with_anchor((-8, -8).into(), 4);
+        qt.insert_pt((-3, 2).into(), 'a');
+        qt.insert_pt((5, 5).into(), 'b');
+        qt.insert(
+            AreaBuilder::default()
+                .anchor((-2, -6).into())
+                .dimensions((6, 3))
+                .build()
+                .unwrap(),
+            'c',
+        );
+        qt
+    }
+
+    // Checks that every tag is closed, in order.
+    fn is_well_formed(svg: &str) -> bool {
+        let mut open: Vec<&str> = Vec::new();
+        for tag in svg.split('<').skip(1) {
+            let tag = &tag[..tag.find('>').unwrap()];
+            if let Some(name) = tag.strip_prefix('/') {
+                if open.pop() != Some(name) {
+                    return false;
+                }
+            } else if !tag.ends_with('/') {
+                open.push(tag.split_whitespace().next().unwrap());
+            }
+        }
+        open.is_empty()
+    }
+
+    #[test]
+    fn well_formed() {
+        let svg = mk_quadtree().to_svg();
+        debug_assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        debug_assert!(svg.trim_end().ends_with("</svg>"));
+        debug_assert!(is_well_formed(&svg));
+    }
+
+    #[test]
+    fn one_box_per_node_and_entry() {
+        let qt = mk_quadtree();
+        let svg = qt.to_svg();
+        debug_assert_eq!(svg.matches("<rect").count(), qt.node_count() + qt.len());
+        debug_assert_eq!(svg.matches("<title>handle").count(), qt.len());
+    }
+
+    #[test]
+    fn view_box_is_the_tree() {
+        let svg = mk_quadtree().to_svg();
+        debug_assert!(svg.contains(r#"viewBox="-8 -8 16 16""#));
+        debug_assert!(svg.contains(r#"width="512" height="512""#));
+        // The region 'c'.
+        debug_assert!(svg.contains(r#"<rect x="-2" y="-6" width="6" height="3">"#));
+    }
+
+    #[test]
+    fn empty_tree() {
+        let qt = Quadtree::<u8, u8>::new(2);
+        let svg = qt.to_svg();
+        debug_assert!(is_well_formed(&svg));
+        debug_assert_eq!(svg.matches("<rect").count(), 1);
+    }
+
+    #[test]
+    fn deterministic() {
+        debug_assert_eq!(mk_quadtree().to_svg(), mk_quadtree().to_svg());
+    }
+}
@@ -1,6 +1,6 @@
 use {
     num::{cast::FromPrimitive, PrimInt},
-    quadtree_rs::area::AreaBuilder,
+    quadtree_rs::area::{Area, AreaBuilder},
     std::{collections::HashSet, default::Default, fmt::Debug, hash::Hash, iter::FromIterator},
 };
 
@@ -59,3 +59,36 @@ where
     }
     println!("┘");
 }
+
+// A small linear congruential generator, so test data can be random-looking but reproducible.
+#[allow(dead_code)]
+pub fn lcg(seed: &mut u64) -> u32 {
+    *seed = seed
+        .wrapping_mul(6_364_136_223_846_793_005)
+        .wrapping_add(1_442_695_040_888_963_407);
+    (*seed >> 33) as u32
+}
+
+// A cheap deterministic scatter of `n` regions over the square `side` wide at the origin, for
+// checking a query against an exhaustive search. The i-th region is `dims(i)` in size, and sits at
+// ((i * 37) % side, (i * 101) % side), moved up and left as far as it takes to fit. (One wider
+// or taller than `side` can't fit, so it's moved to the edge and sticks out past the far side.)
+#[allow(dead_code)]
+pub fn scatter<F>(n: u32, side: u32, dims: F) -> impl Iterator<Item = (u32, Area<u32>)>
+where
+    F: Fn(u32) -> (u32, u32),
+{
+    (0..n).map(move |i| {
+        let (w, h) = dims(i);
+        let anchor = (
+            ((i * 37) % side).min(side.saturating_sub(w)),
+            ((i * 101) % side).min(side.saturating_sub(h)),
+        );
+        let region = AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions((w, h))
+            .build()
+            .unwrap();
+        (i, region)
+    })
+}
@@ -0,0 +1,145 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Only built with `--features validate`.
+#![cfg(feature = "validate")]
+
+mod util; // For scatter.
+
+// For testing .validate(), and through it the structure left behind by each mutating operation.
+mod validate_tests {
+    use {
+        crate::util::scatter,
+        quadtree_rs::{
+            area::{Area, AreaBuilder},
+            point::Point,
+            Quadtree,
+        },
+    };
+
+    fn area(anchor: (i32, i32), dims: (i32, i32)) -> Area<i32> {
+        AreaBuilder::default()
+            .anchor(anchor.into())
+            .dimensions(dims)
+            .build()
+            .unwrap()
+    }
+
+    // Fills @qt with a scatter of regions of assorted sizes, moved onto it.
+    fn fill(qt: &mut Quadtree<i32, u32>, n: u32) {
+        let origin = qt.anchor();
+        let side = qt.width() as u32;
+        for (i, region) in scatter(n, side, |i| (1 + (i * 7) % 9, 1 + (i * 5) % 6)) {
+            let anchor = (
+                origin.x() + region.anchor().x() as i32,
+                origin.y() + region.anchor().y() as i32,
+            );
+            qt.insert(
+                area(anchor, (region.width() as i32, region.height() as i32)),
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn fresh_trees() {
+        debug_assert_eq!(Quadtree::<u32, u8>::new(0).validate(), Ok(()));
+        debug_assert_eq!(Quadtree::<i8, u8>::default().validate(), Ok(()));
+        let mut qt = Quadtree::<i32, u32>::new_with_anchor((-32, -32).into(), 6);
+        fill(&mut qt, 200);
+        debug_assert_eq!(qt.validate(), Ok(()));
+    }
+
+    #[test]
+    fn with_node_capacity() {
+        let mut qt = Quadtree::<i32, u32>::with_capacity((0, 0).into(), 6, 3);
+        fill(&mut qt, 200);
+        debug_assert_eq!(qt.validate(), Ok(()));
+    }
+
+    #[test]
+    fn after_deletions() {
+        let mut qt = Quadtree::<i32, u32>::new(6);
+        fill(&mut qt, 200);
+        qt.delete(area((10, 10), (20, 5)));
+        qt.delete_strict(area((40, 0), (24, 24)));
+        let handle = qt.iter().next().unwrap().handle();
+        qt.delete_by_handle(handle);
+        qt.retain(|_, &v| v % 3 != 0);
+        qt.drain_region(area((0, 40), (10, 10))).for_each(drop);
+        debug_assert_eq!(qt.validate(), Ok(()));
+
+        qt.compact();
+        debug_assert_eq!(qt.validate(), Ok(()));
+        qt.reset();
+        debug_assert_eq!(qt.validate(), Ok(()));
+    }
+
+    #[test]
+    fn after_relocations() {
+        let mut qt = Quadtree::<i32, u32>::with_capacity((0, 0).into(), 6, 2);
+        fill(&mut qt, 100);
+        let handles: Vec<u64> = qt.iter().map(|e| e.handle()).collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            let i = i as i32;
+            qt.relocate(
+                handle,
+                area(((i * 13) % 60, (i * 7) % 60), (1 + i % 4, 1 + i % 3)),
+            );
+        }
+        qt.modify_all(|v| *v += 1);
+        debug_assert_eq!(qt.validate(), Ok(()));
+    }
+
+    #[test]
+    fn after_growing_moving_and_splitting() {
+        let mut qt = Quadtree::<i32, u32>::new_growable((0, 0).into(), 2);
+        fill(&mut qt, 10);
+        qt.insert_pt(Point { x: -40, y: 70 }, 1000);
+        qt.insert(area((100, -100), (30, 3)), 1001);
+        debug_assert_eq!(qt.validate(), Ok(()));
+
+        qt.translate(-500, 1000).unwrap();
+        debug_assert_eq!(qt.validate(), Ok(()));
+
+        let region = qt.root_region();
+        let quarter = area(
+            (region.anchor().x(), region.anchor().y()),
+            (region.width() / 2, region.height() / 2),
+        );
+        let mut other = qt.split_off_region(quarter);
+        debug_assert_eq!(qt.validate(), Ok(()));
+        debug_assert_eq!(other.validate(), Ok(()));
+
+        debug_assert!(other.merge(qt).is_ok());
+        debug_assert_eq!(other.validate(), Ok(()));
+    }
+
+    #[test]
+    fn copies() {
+        let mut qt = Quadtree::<i32, u32>::new_with_anchor((-32, -32).into(), 6);
+        fill(&mut qt, 150);
+        debug_assert_eq!(qt.clone_with_new_handles().validate(), Ok(()));
+        debug_assert_eq!(qt.map(|v| v * 2).validate(), Ok(()));
+
+        let bulk: Quadtree<i32, u32> = (0..300).map(|i| ((i % 40, i / 40), i as u32)).collect();
+        debug_assert_eq!(bulk.validate(), Ok(()));
+        let loaded = Quadtree::<u32, u32>::from_entries(
+            (0, 0).into(),
+            5,
+            (0..300_u32).map(|i| ((i * 7 % 32, i * 13 % 32), i)),
+        );
+        debug_assert_eq!(loaded.validate(), Ok(()));
+    }
+}
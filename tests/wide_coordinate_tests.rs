@@ -0,0 +1,209 @@
+// Copyright 2019 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// For testing quadtrees over coordinate types wider than 64 bits, and over usize, right up to the
+// largest coordinates they can hold.
+mod wide_coordinate_tests {
+    use quadtree_rs::{
+        area::{Area, AreaBuilder},
+        point::Point,
+        Quadtree,
+    };
+
+    fn region<U>(x: U, y: U, w: U, h: U) -> Area<U>
+    where
+        U: num::PrimInt + Default,
+    {
+        AreaBuilder::default()
+            .anchor(Point { x, y })
+            .dimensions((w, h))
+            .build()
+            .unwrap()
+    }
+
+    // The largest tree u128 allows, reaching all the way to u128::MAX.
+    fn mk_u128_quadtree() -> Quadtree<u128, char> {
+        let anchor = u128::MAX - (1 << 127);
+        Quadtree::<u128, char>::new_with_anchor((anchor, anchor).into(), 127)
+    }
+
+    #[test]
+    fn u128_root_reaches_max() {
+        let qt = mk_u128_quadtree();
+        debug_assert_eq!(qt.root_region().right_edge(), u128::MAX);
+        debug_assert_eq!(qt.root_region().bottom_edge(), u128::MAX);
+        debug_assert_eq!(qt.root_region().width(), 1 << 127);
+    }
+
+    #[test]
+    fn u128_insert_and_query_near_max() {
+        let mut qt = mk_u128_quadtree();
+        let max = u128::MAX;
+
+        // Subdivides the whole way down, 127 levels, to the very last cell.
+        let corner = qt.insert_pt((max - 1, max - 1).into(), 'a').unwrap();
+        debug_assert_eq!(qt.max_depth(), 127);
+        let wide = qt.insert(region(max - 8, max - 3, 8, 3), 'b').unwrap();
+        // Straddles the middle of the tree.
+        let middle = qt.root_region().center().x();
+        qt.insert(region(middle - 2, middle - 2, 4, 4), 'c')
+            .unwrap();
+
+        let found: Vec<char> = qt
+            .query(region(max - 2, max - 2, 2, 2))
+            .map(|e| *e.value_ref())
+            .collect();
+        debug_assert_eq!(found.len(), 2);
+        assert!(found.contains(&'a') && found.contains(&'b'));
+
+        let mut at_corner: Vec<u64> = qt
+            .get_at_point((max - 1, max - 1).into())
+            .map(|e| e.handle())
+            .collect();
+        at_corner.sort_unstable();
+        debug_assert_eq!(at_corner, vec![corner, wide]);
+        debug_assert_eq!(
+            qt.get_at_point((middle, middle).into())
+                .next()
+                .map(|e| *e.value_ref()),
+            Some('c')
+        );
+        debug_assert_eq!(qt.query_strict(region(max - 8, max - 8, 8, 8)).count(), 2);
+
+        assert!(qt.delete_by_handle(wide).is_some());
+        debug_assert_eq!(qt.query(region(max - 8, max - 3, 8, 3)).count(), 1);
+        qt.delete(region(max - 1, max - 1, 1, 1));
+        debug_assert_eq!(qt.len(), 1);
+    }
+
+    #[test]
+    fn u128_contains_near_max() {
+        let max = u128::MAX;
+        let outer = region(max - 4, max - 4, 4, 4);
+        assert!(outer.contains(region(max - 1, max - 1, 1, 1)));
+        assert!(outer.contains(outer));
+        assert!(!region(max - 4, max - 4, 3, 3).contains(outer));
+        assert!(outer.contains_pt((max - 1, max - 1).into()));
+        assert!(!outer.contains_pt((max, max).into()));
+        assert!(outer.intersects(region(max - 1, 0, 1, max - 1)));
+        debug_assert_eq!(
+            outer.center(),
+            Point {
+                x: max - 2,
+                y: max - 2
+            }
+        );
+    }
+
+    #[test]
+    fn u128_statistics_do_not_overflow() {
+        let mut qt = mk_u128_quadtree();
+        let anchor = qt.anchor().x();
+        qt.insert(region(anchor, anchor, 1 << 126, 1 << 126), 'a');
+        qt.insert_pt((u128::MAX - 1, u128::MAX - 1).into(), 'b');
+
+        // Far more than a u64 can count.
+        debug_assert_eq!(qt.coverage_area(), u64::MAX);
+        debug_assert_eq!(qt.total_entry_area(), u64::MAX);
+
+        debug_assert_eq!(qt.density_grid(2, 2), vec![vec![1, 0], vec![0, 1]]);
+        debug_assert_eq!(
+            qt.rasterize(4, 4, '.').into_iter().collect::<String>(),
+            "aa..aa..........",
+        );
+    }
+
+    #[test]
+    fn u128_nearest_neighbors() {
+        let mut qt = mk_u128_quadtree();
+        let max = u128::MAX;
+        qt.insert_pt((max - 1, max - 1).into(), 'a');
+        qt.insert_pt((max - 10, max - 10).into(), 'b');
+        let nearest: Vec<char> = qt
+            .nearest_neighbors((max - 2, max - 2).into(), 2)
+            .iter()
+            .map(|e| *e.value_ref())
+            .collect();
+        debug_assert_eq!(nearest, vec!['a', 'b']);
+    }
+
+    #[test]
+    fn u128_region_fully_covered_near_max() {
+        let mut qt = mk_u128_quadtree();
+        let max = u128::MAX;
+        qt.insert(region(max - 8, max - 8, 8, 4), 'a');
+        qt.insert(region(max - 8, max - 4, 4, 4), 'b');
+        debug_assert!(!qt.region_fully_covered(region(max - 8, max - 8, 8, 8)));
+        qt.insert(region(max - 4, max - 4, 4, 4), 'c');
+        debug_assert!(qt.region_fully_covered(region(max - 8, max - 8, 8, 8)));
+        // The whole tree, far too large to measure by area.
+        debug_assert!(!qt.region_fully_covered(qt.root_region()));
+        qt.insert(qt.root_region(), 'd');
+        debug_assert!(qt.region_fully_covered(qt.root_region()));
+    }
+
+    #[test]
+    fn i128_spans_negative_and_positive() {
+        // 2^127 is too wide for i128, so this is the largest tree it allows.
+        let half = 1 << 125;
+        let mut qt = Quadtree::<i128, char>::new_with_anchor((-half, -half).into(), 126);
+        debug_assert_eq!(qt.root_region().right_edge(), half);
+
+        qt.insert_pt((-half, -half).into(), 'a');
+        qt.insert_pt((half - 1, half - 1).into(), 'b');
+        // Straddles the origin, which is the middle of the tree.
+        qt.insert(region(-2, -2, 4, 4), 'c');
+
+        debug_assert_eq!(
+            qt.get_at_point((-half, -half).into())
+                .next()
+                .map(|e| *e.value_ref()),
+            Some('a')
+        );
+        debug_assert_eq!(
+            qt.get_at_point((-1, 1).into())
+                .next()
+                .map(|e| *e.value_ref()),
+            Some('c')
+        );
+        debug_assert_eq!(
+            qt.nearest_neighbors((half - 3, half - 2).into(), 1)[0].value_ref(),
+            &'b'
+        );
+        debug_assert_eq!(qt.query(qt.root_region()).count(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn i128_too_deep() {
+        let _qt = Quadtree::<i128, char>::new_with_anchor((i128::MIN, i128::MIN).into(), 127);
+    }
+
+    #[test]
+    fn usize_near_max() {
+        let depth = usize::BITS as usize - 1;
+        let anchor = usize::MAX - (1 << depth);
+        let mut qt = Quadtree::<usize, char>::new_with_anchor((anchor, anchor).into(), depth);
+        debug_assert_eq!(qt.width(), 1 << depth);
+
+        qt.insert_pt((usize::MAX - 1, anchor).into(), 'a');
+        qt.insert(region(usize::MAX - 5, usize::MAX - 5, 5, 5), 'b');
+        debug_assert_eq!(
+            qt.query(region(usize::MAX - 2, anchor, 2, usize::MAX - anchor))
+                .count(),
+            2
+        );
+        debug_assert_eq!(qt.density_grid(1, 1), vec![vec![2]]);
+    }
+}